@@ -1,20 +1,69 @@
 use criterion::{criterion_group, criterion_main, Criterion, black_box, BenchmarkId};
-use glstsp::load_problem;
+use glstsp::bench_support::{configs, random_problem, LocalSearchInput, SIZES};
+
+fn construction_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+
+    for &size in SIZES.iter() {
+        let gls = random_problem(size);
+        group.bench_with_input(BenchmarkId::new("sequential", size), &gls, |b, gls| {
+            b.iter(|| gls.sequential())
+        });
+        group.bench_with_input(BenchmarkId::new("nearest_neighbor", size), &gls, |b, gls| {
+            b.iter(|| gls.nearest_neighbor())
+        });
+    }
+
+    group.finish();
+}
+
+fn local_search_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("local_search");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let gls = random_problem(size);
+        let input = LocalSearchInput::new(&gls, 666);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+            b.iter(|| input.run(&gls))
+        });
+    }
+
+    group.finish();
+}
 
 fn gls_benchmark(c: &mut Criterion) {
-    let gls = load_problem();
+    let mut group = c.benchmark_group("gls");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let gls = random_problem(size);
+        for config in configs() {
+            let id = BenchmarkId::new(format!("{}", size), format!("solve({}, {})", config.seed, config.steps));
+            group.bench_function(id, |b| b.iter(|| gls.solve(black_box(config.seed), black_box(config.steps))));
+        }
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "bundled-data")]
+fn pcb3038_benchmark(c: &mut Criterion) {
+    let gls = glstsp::test_fixture();
 
     let mut group = c.benchmark_group("PCB3038");
     group.sample_size(10);
 
-    for step in [1, 5, 10, 25].iter() {
-        group.bench_with_input(BenchmarkId::from_parameter(format!("gls(666, {})", step)), step, |b, &step| {
-            b.iter(|| gls.solve(black_box(666), black_box(step)))
-        });
+    for config in configs() {
+        let id = BenchmarkId::from_parameter(format!("solve({}, {})", config.seed, config.steps));
+        group.bench_function(id, |b| b.iter(|| gls.solve(black_box(config.seed), black_box(config.steps))));
     }
 
     group.finish();
 }
 
-criterion_group!(benches, gls_benchmark);
+#[cfg(not(feature = "bundled-data"))]
+criterion_group!(benches, construction_benchmark, local_search_benchmark, gls_benchmark);
+#[cfg(feature = "bundled-data")]
+criterion_group!(benches, construction_benchmark, local_search_benchmark, gls_benchmark, pcb3038_benchmark);
 criterion_main!(benches);
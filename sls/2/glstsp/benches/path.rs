@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
+use glstsp::bench_support::{random_path, random_twists, SIZES};
+use glstsp::types::path::Path;
+
+fn twist_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("twist");
+
+    for &size in SIZES.iter() {
+        let twists = random_twists(size, 100);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &twists, |b, twists| {
+            let mut path = random_path(size);
+            b.iter(|| {
+                for &(i, j) in twists {
+                    path.twist(i, j);
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn edges_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("edges");
+
+    for &size in SIZES.iter() {
+        let path = random_path(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &path, |b, path| {
+            b.iter(|| path.edges().fold(0, |acc, (a, b)| acc ^ (a * b)))
+        });
+    }
+
+    group.finish();
+}
+
+fn successors_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("successors");
+
+    for &size in SIZES.iter() {
+        let path = random_path(size);
+        let successors = path.to_successor_vec();
+        group.bench_with_input(BenchmarkId::new("to_successor_vec", size), &path, |b, path| {
+            b.iter(|| path.to_successor_vec())
+        });
+        group.bench_with_input(BenchmarkId::new("from_successor_vec", size), &successors, |b, successors| {
+            b.iter(|| Path::from_successor_vec(successors).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, twist_benchmark, edges_benchmark, successors_benchmark);
+criterion_main!(benches);
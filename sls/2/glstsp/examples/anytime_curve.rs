@@ -0,0 +1,31 @@
+//! Anytime curve of a time-limited search on `berlin52`: the best cost each time it improved,
+//! and the gap of the final tour to the optimum, from a `SolveReport`.
+//!
+//! `cargo run --release --example anytime_curve [milliseconds]`
+
+use glstsp::types::anytime::Budget;
+use glstsp::types::gls::GuidedLocalSearch;
+use glstsp::types::tsplib::Tsplib;
+use std::env;
+use std::time::Duration;
+
+const OPTIMUM: i32 = 7542;
+
+fn main() {
+    let millis = env::args().nth(1).map_or(200, |arg| arg.parse().expect("a number of milliseconds"));
+    let budget = Budget::Time(Duration::from_millis(millis)).validate().expect("a positive time");
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tsplib/berlin52.tsp");
+    let instance = Tsplib::read_file(path).expect("berlin52 is bundled").into_instance();
+    let report = GuidedLocalSearch::from_instance(&instance)
+        .solve_anytime(666, budget)
+        .with_optimum(OPTIMUM);
+
+    println!("{:>10} {:>8}", "elapsed", "best");
+    for (elapsed, cost) in &report.profile {
+        println!("{:>8.2}ms {:>8}", elapsed.as_secs_f64() * 1000.0, cost);
+    }
+    println!(
+        "{} steps, final cost {}, {:.2}% above the optimum",
+        report.history.len() - 1, report.route.cost, 100.0 * report.optimality_gap.unwrap());
+}
@@ -0,0 +1,51 @@
+//! Cost and run time of the initial tour constructions on random points, before and after
+//! `Instance::improve` runs GLS from them.
+//!
+//! `cargo run --release --example compare_constructions`
+
+use glstsp::types::config::GlsConfig;
+use glstsp::types::gls::GuidedLocalSearch;
+use glstsp::types::instance::Instance;
+use glstsp::types::point::Point;
+use glstsp::types::route::Route;
+use rand::{Rng, SeedableRng};
+use rand_mt::Mt64;
+use std::time::Instant;
+
+const SIZE: usize = 1000;
+
+type Construction = fn(&GuidedLocalSearch, &[Point]) -> Route;
+
+fn main() {
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+    let points: Vec<_> = (0..SIZE)
+        .map(|_| Point::new(rng.gen_range(0..10_000), rng.gen_range(0..10_000)))
+        .collect();
+    let instance = Instance::from_points(points.clone());
+    let gls = GuidedLocalSearch::from_instance(&instance);
+    let config = GlsConfig::fast();
+
+    let constructions: [(&str, Construction); 6] = [
+        ("sequential", |gls, _| gls.sequential()),
+        ("nearest neighbor", |gls, _| gls.nearest_neighbor()),
+        ("cheapest insertion", |gls, _| gls.cheapest_insertion(None)),
+        ("farthest insertion", |gls, _| gls.farthest_insertion(None)),
+        ("convex hull insertion", |gls, points| gls.convex_hull_insertion(points)),
+        ("savings", |gls, _| gls.savings(0)),
+    ];
+
+    println!("{:<22} {:>10} {:>8} {:>10} {:>8}", "construction", "cost", "ms", "after GLS", "ms");
+    for (name, construct) in &constructions {
+        let start = Instant::now();
+        let route = construct(&gls, &points);
+        let built = start.elapsed();
+
+        let start = Instant::now();
+        let improved = instance.improve(route.clone(), &config).expect("same instance");
+        let searched = start.elapsed();
+
+        println!(
+            "{:<22} {:>10} {:>8} {:>10} {:>8}",
+            name, route.cost, built.as_millis(), improved.cost, searched.as_millis());
+    }
+}
@@ -0,0 +1,110 @@
+//! Edit random points from the standard input while `DynamicSolver` keeps their tour short,
+//! e.g. to try the incremental updates by hand:
+//!
+//! ```text
+//! add <x> <y>          new stop, numbered after the others
+//! move <stop> <x> <y>  new position of a stop
+//! remove <stop>        remove a stop, renumbering the following ones
+//! cost <a> <b> <cost>  new cost of an edge
+//! watch [<ms>]         print the improvements of the tour for a while (1000 ms by default)
+//! show                 print the tour
+//! quit
+//! ```
+//!
+//! `cargo run --release --example interactive [<stops>]`
+
+use glstsp::types::config::GlsConfig;
+use glstsp::types::dynamic::{DynamicSolver, Event, RouteHandle};
+use glstsp::types::instance::Instance;
+use glstsp::types::point::Point;
+use rand::{Rng, SeedableRng};
+use rand_mt::Mt64;
+use std::convert::TryFrom;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_SIZE: usize = 50;
+
+fn main() {
+    let size = env::args().nth(1).map_or(DEFAULT_SIZE, |arg| arg.parse().expect("number of stops"));
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+    let points: Vec<_> = (0..size)
+        .map(|_| Point::new(rng.gen_range(0..1000), rng.gen_range(0..1000)))
+        .collect();
+
+    let solver = DynamicSolver::start(Instance::from_points(points), GlsConfig::fast());
+    let route = solver.route();
+    println!("{} stops, cost {}", size, route.cost());
+
+    prompt();
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("standard input");
+        let words: Vec<_> = line.split_whitespace().collect();
+        let numbers: Option<Vec<i32>> = words.iter().skip(1).map(|word| word.parse().ok()).collect();
+        // Negative numbers of stops are out of range too
+        let stop = |stop: i32| usize::try_from(stop).unwrap_or(usize::MAX);
+
+        let event = match (words.first().copied(), numbers.as_deref()) {
+            (None, _) => None,
+            (Some("add"), Some(&[x, y])) => Some(Event::AddStop(Point::new(x, y))),
+            (Some("move"), Some(&[vertex, x, y])) => Some(Event::MoveStop { vertex: stop(vertex), point: Point::new(x, y) }),
+            (Some("remove"), Some(&[vertex])) => Some(Event::CancelStop(stop(vertex))),
+            (Some("cost"), Some(&[a, b, cost])) if cost >= 0 => Some(Event::CostChange { a: stop(a), b: stop(b), cost }),
+            (Some("watch"), Some(&[])) => {
+                watch(&route, Duration::from_secs(1));
+                None
+            },
+            (Some("watch"), Some(&[ms])) if ms >= 0 => {
+                watch(&route, Duration::from_millis(ms as u64));
+                None
+            },
+            (Some("show"), Some(&[])) => {
+                let route = route.get();
+                println!("cost {}: {:?}", route.cost, route.path.as_slice());
+                None
+            },
+            (Some("quit"), _) => break,
+            _ => {
+                println!("commands: add <x> <y>, move <stop> <x> <y>, remove <stop>, cost <a> <b> <cost>, watch [<ms>], show, quit");
+                None
+            },
+        };
+
+        if let Some(event) = event {
+            match solver.send(event) {
+                Ok(()) => {
+                    // The event is applied after the current round of steps
+                    thread::sleep(Duration::from_millis(10));
+                    println!("cost {}", route.cost());
+                },
+                Err(error) => println!("rejected: {:?}", error),
+            }
+        }
+        prompt();
+    }
+
+    let (instance, route) = solver.stop();
+    println!("{} stops, cost {}", instance.size(), route.cost);
+}
+
+fn prompt() {
+    print!("> ");
+    io::stdout().flush().expect("standard output");
+}
+
+/// Print the cost of `route` whenever it changes during `duration`.
+fn watch(route: &RouteHandle, duration: Duration) {
+    let start = Instant::now();
+    let mut cost = route.cost();
+    while start.elapsed() < duration {
+        thread::sleep(Duration::from_millis(5));
+        let current = route.cost();
+        if current != cost {
+            cost = current;
+            println!("{:>6} ms  cost {}", start.elapsed().as_millis(), cost);
+        }
+    }
+    println!("cost {}", cost);
+}
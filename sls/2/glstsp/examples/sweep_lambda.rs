@@ -0,0 +1,28 @@
+//! Mean tour cost on `berlin52` across a grid of penalty weights (`GlsConfig::alpha_percent`,
+//! the `λ` of the augmented cost in percent of the mean edge length), over a few seeds.
+//!
+//! `cargo run --release --example sweep_lambda`
+
+use glstsp::types::config::GlsConfig;
+use glstsp::types::gls::GuidedLocalSearch;
+use glstsp::types::tsplib::Tsplib;
+
+const ALPHAS: [u32; 7] = [5, 10, 20, 30, 50, 80, 120];
+const SEEDS: u64 = 10;
+const OPTIMUM: i32 = 7542;
+
+fn main() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tsplib/berlin52.tsp");
+    let instance = Tsplib::read_file(path).expect("berlin52 is bundled").into_instance();
+
+    println!("{:>6} {:>10} {:>8} {:>8}", "alpha", "mean cost", "gap %", "best");
+    for alpha in ALPHAS {
+        let config = GlsConfig::default().with_steps(100).with_alpha_percent(alpha);
+        let gls = GuidedLocalSearch::from_instance(&instance).with_config(&config);
+
+        let costs: Vec<i32> = (0..SEEDS).map(|seed| gls.solve(seed, config.steps).cost).collect();
+        let mean = costs.iter().map(|&cost| cost as f64).sum::<f64>() / costs.len() as f64;
+        let gap = 100.0 * (mean - OPTIMUM as f64) / OPTIMUM as f64;
+        println!("{:>6} {:>10.1} {:>8.2} {:>8}", alpha, mean, gap, costs.iter().min().unwrap());
+    }
+}
@@ -0,0 +1,29 @@
+#![no_main]
+
+use glstsp::types::tsplib::Tsplib;
+use libfuzzer_sys::fuzz_target;
+
+// Any input must either be rejected with an error or give a consistent symmetric instance.
+fuzz_target!(|data: &[u8]| {
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+
+    if let Ok(tsplib) = Tsplib::parse(text) {
+        let distances = &tsplib.distances;
+        let size = distances.size();
+        assert!(size >= 2);
+
+        if let Some(coords) = &tsplib.coords {
+            assert_eq!(coords.len(), size);
+        }
+
+        for i in 0..size {
+            assert_eq!(distances[(i, i)], 0);
+            for j in i + 1..size {
+                assert_eq!(distances[(i, j)], distances[(j, i)]);
+            }
+        }
+    }
+});
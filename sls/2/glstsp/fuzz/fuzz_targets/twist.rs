@@ -0,0 +1,37 @@
+#![no_main]
+
+use glstsp::types::path::Path;
+use libfuzzer_sys::fuzz_target;
+
+/// Reverse the positions `i, i + 1, ..., j` (modulo the length) one by one.
+fn reverse_model(path: &mut [usize], i: usize, j: usize) {
+    let len = path.len();
+    let positions: Vec<_> = (0..(j + len - i) % len + 1).map(|k| (i + k) % len).collect();
+    let values: Vec<_> = positions.iter().map(|&p| path[p]).collect();
+    for (&p, &v) in positions.iter().zip(values.iter().rev()) {
+        path[p] = v;
+    }
+}
+
+// The first byte picks the length, every following pair of bytes is a twist.
+fuzz_target!(|data: &[u8]| {
+    let (&len, twists) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let len = 2 + len as usize % 63;
+
+    let mut actual = Path::sequential(len);
+    let mut expected: Vec<_> = (0..len).collect();
+
+    for twist in twists.chunks_exact(2) {
+        let i = twist[0] as usize % len;
+        let j = twist[1] as usize % len;
+
+        actual.twist(i, j);
+        reverse_model(&mut expected, i, j);
+
+        assert!(actual.is_hamiltonian());
+        assert_eq!(Path::try_new(expected.clone()).as_ref(), Ok(&actual));
+    }
+});
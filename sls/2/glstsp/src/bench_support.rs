@@ -0,0 +1,84 @@
+//! Fixtures of the benchmarks in `benches/`: instances of a few sizes, the configurations they
+//! are solved with, and the inputs of each phase of the solver so it can be measured alone.
+//! Behind the `testing` feature, as the benchmarks: `cargo bench --features testing`.
+
+pub use crate::testing::{random_gls as random_problem, random_points};
+use crate::types::config::GlsConfig;
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use rand_mt::Mt64;
+use rand::{SeedableRng, Rng};
+use rand::seq::SliceRandom;
+
+/// Number of points of the generated instances.
+pub const SIZES: [usize; 3] = [100, 500, 1000];
+
+/// Penalization steps of the benchmarked GLS runs.
+pub const STEPS: [usize; 4] = [1, 5, 10, 25];
+
+/// Random tour of `size` vertices, always the same for a given size.
+pub fn random_path(size: usize) -> Path {
+    let mut vertices: Vec<_> = (0..size).collect();
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+    vertices.shuffle(&mut rng);
+    Path::new(vertices)
+}
+
+/// `count` random `Path::twist` arguments for a tour of `size` vertices, about half of them wrapping
+/// around the end of the tour (`i > j`).
+pub fn random_twists(size: usize, count: usize) -> Vec<(usize, usize)> {
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+    (0..count).map(|_| (rng.gen_range(0..size), rng.gen_range(0..size))).collect()
+}
+
+/// Default configuration with each number of `STEPS`.
+pub fn configs() -> Vec<GlsConfig> {
+    STEPS.iter().map(|&steps| GlsConfig::default().with_steps(steps)).collect()
+}
+
+/// Inputs of a single local search without penalties, from the nearest neighbor tour.
+pub struct LocalSearchInput {
+    pub start: Path,
+    pub neighborhood: Path,
+    pub penalties: Penalties,
+}
+
+impl LocalSearchInput {
+    pub fn new(gls: &GuidedLocalSearch, seed: u64) -> Self {
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        Self {
+            start: gls.nearest_neighbor().path,
+            neighborhood: gls.neighborhood(&mut rng),
+            penalties: Penalties::from_size(gls.distances().size()),
+        }
+    }
+
+    /// Run the local search on a copy of the starting tour and return the local minimum.
+    pub fn run(&self, gls: &GuidedLocalSearch) -> Path {
+        let mut path = self.start.clone();
+        let mut penalties = self.penalties.clone();
+        gls.local_search(&mut path, &self.neighborhood, 0, &mut penalties);
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench_support::{configs, random_path, random_points, random_problem, random_twists, LocalSearchInput, STEPS};
+
+    #[test]
+    fn fixtures() {
+        assert_eq!(random_points(100), random_points(100));
+        assert_eq!(configs().iter().map(|config| config.steps).collect::<Vec<_>>(), STEPS);
+
+        let gls = random_problem(100);
+        let input = LocalSearchInput::new(&gls, 666);
+        let minimum = input.run(&gls);
+        assert!(minimum.is_hamiltonian());
+        assert!(gls.cost(&minimum) <= gls.cost(&input.start));
+
+        assert!(random_path(100).is_hamiltonian());
+        assert!(random_twists(100, 50).iter().all(|&(i, j)| i < 100 && j < 100));
+    }
+}
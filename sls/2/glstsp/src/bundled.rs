@@ -0,0 +1,171 @@
+use crate::types::checksum::Fnv64;
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::instance::Instance;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::point::Point;
+use crate::types::route::Route;
+use crate::types::tsplib::Tsplib;
+use std::convert::TryFrom;
+use std::fs;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const PCB3038: &str = include_str!("../data/pcb3038.preprocessed.tsp");
+
+/// Bundled TSPLIB instances, by name.
+const TSPLIB: &[(&str, &str)] = &[
+    ("burma14", include_str!("../data/tsplib/burma14.tsp")),
+    ("ulysses16", include_str!("../data/tsplib/ulysses16.tsp")),
+    ("ulysses22", include_str!("../data/tsplib/ulysses22.tsp")),
+    ("berlin52", include_str!("../data/tsplib/berlin52.tsp")),
+];
+
+/// Instance embedded in the library, `None` if there is none with this name.
+///
+/// Besides the TSPLIB instances of `data/tsplib`, `pcb3038` is the benchmark instance with its
+/// coordinates truncated to integers. Files are read at runtime with `Tsplib::read_file`.
+pub fn load_instance(name: &str) -> Option<Instance> {
+    if name == "pcb3038" {
+        return Some(Instance::from_points(PCB3038.lines().map(Point::from).collect()));
+    }
+
+    let (_, text) = TSPLIB.iter().find(|(tsplib_name, _)| *tsplib_name == name)?;
+    Some(Tsplib::parse(text).unwrap().into_instance())
+}
+
+pub fn load_matrix() -> SymmetricMatrix {
+    let tsp = PCB3038
+        .lines()
+        .map(Point::from)
+        .collect::<Vec<_>>();
+    SymmetricMatrix::from_euclidean_coords(&tsp)
+}
+
+/// Same as `load_matrix`, reading the distances from the file at `path` when it holds them, or
+/// else writing them there for the next run.
+///
+/// The file starts with a hash of the bundled coordinates and a hash of the matrix that
+/// follows (see `SymmetricMatrix::write_binary`), both FNV-1a as little-endian `u64`. A file
+/// from other coordinates or whose matrix doesn't match its hash is replaced.
+pub fn load_matrix_cached(path: impl AsRef<Path>) -> io::Result<SymmetricMatrix> {
+    let path = path.as_ref();
+    if let Ok(data) = fs::read(path) {
+        if let Some(matrix) = read_cache(&data) {
+            return Ok(matrix);
+        }
+    }
+
+    let matrix = load_matrix();
+    let mut body = Vec::new();
+    matrix.write_binary(&mut body)?;
+    let mut file = File::create(path)?;
+    file.write_all(&hash(PCB3038.as_bytes()).to_le_bytes())?;
+    file.write_all(&hash(&body).to_le_bytes())?;
+    file.write_all(&body)?;
+    Ok(matrix)
+}
+
+/// FNV-1a hash of `bytes`.
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hash = Fnv64::new();
+    hash.write(bytes);
+    hash.finish()
+}
+
+/// Matrix of a file written by `load_matrix_cached`, `None` unless it is the current one.
+fn read_cache(data: &[u8]) -> Option<SymmetricMatrix> {
+    if data.len() < 16 {
+        return None;
+    }
+    let (header, mut body) = data.split_at(16);
+    let word = |i: usize| u64::from_le_bytes(<[u8; 8]>::try_from(&header[8 * i..8 * i + 8]).unwrap());
+    if word(0) != hash(PCB3038.as_bytes()) || word(1) != hash(body) {
+        return None;
+    }
+    SymmetricMatrix::read_binary(&mut body).ok()
+}
+
+/// Distances between the first `count` points of `pcb3038`, without building the whole
+/// matrix, e.g. for quick tests and examples.
+pub fn load_matrix_subset(count: usize) -> SymmetricMatrix {
+    let tsp = PCB3038
+        .lines()
+        .take(count)
+        .map(Point::from)
+        .collect::<Vec<_>>();
+    assert_eq!(tsp.len(), count, "pcb3038 has fewer points");
+    SymmetricMatrix::from_euclidean_coords(&tsp)
+}
+
+pub fn load_problem() -> GuidedLocalSearch {
+    let matrix = load_matrix();
+    GuidedLocalSearch::new(matrix)
+}
+
+/// Same as `load_problem`, built on the first call and shared by the later ones, so the tests
+/// and benchmarks of a process compute the 3038 × 3038 distances once.
+pub fn test_fixture() -> &'static GuidedLocalSearch {
+    static FIXTURE: OnceLock<GuidedLocalSearch> = OnceLock::new();
+    FIXTURE.get_or_init(load_problem)
+}
+
+/// GLS on the `pcb3038` benchmark instance, seed 666, as the benchmarks run it.
+pub fn run_pcb3038(steps: usize) -> Route {
+    test_fixture().solve(666, steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bundled::{load_instance, load_matrix_cached, load_matrix_subset, test_fixture};
+    use std::fs;
+    use std::process;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(load_instance("burma14").unwrap().size(), 14);
+        assert_eq!(load_instance("berlin52").unwrap().size(), 52);
+        assert!(load_instance("pcb3038").unwrap().distances().data == test_fixture().distances().data);
+        assert!(load_instance("pcb442").is_none());
+    }
+
+    #[test]
+    fn subset() {
+        let subset = load_matrix_subset(100);
+        assert_eq!(subset.size(), 100);
+        assert!(load_instance("pcb3038").unwrap().take(100).distances().data == subset.data);
+    }
+
+    #[test]
+    fn cached() {
+        let path = std::env::temp_dir().join(format!("glstsp-pcb3038-{}.matrix", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let built = load_matrix_cached(&path).unwrap();
+        assert!(built.data == test_fixture().distances().data);
+        let read = load_matrix_cached(&path).unwrap();
+        assert!(read.data == built.data);
+        let cache = fs::read(&path).unwrap();
+
+        // A damaged file is replaced
+        fs::write(&path, b"GLSM").unwrap();
+        assert!(load_matrix_cached(&path).unwrap().data == built.data);
+
+        // So is one of the right size whose distances changed, or from other coordinates
+        let mut changed = cache.clone();
+        *changed.last_mut().unwrap() ^= 1;
+        fs::write(&path, &changed).unwrap();
+        assert!(load_matrix_cached(&path).unwrap().data == built.data);
+        assert_eq!(fs::read(&path).unwrap(), cache);
+
+        let mut stale = cache.clone();
+        stale[0] ^= 1;
+        fs::write(&path, &stale).unwrap();
+        assert!(load_matrix_cached(&path).unwrap().data == built.data);
+        assert_eq!(fs::read(&path).unwrap(), cache);
+        fs::remove_file(path).unwrap();
+    }
+}
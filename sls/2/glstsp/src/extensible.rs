@@ -0,0 +1,173 @@
+//! The components of the search as traits, to assemble variants of GLS from new parts.
+//!
+//! This module is exempt from semantic versioning: the traits follow the internals of the
+//! solver and may change in any release. The rest of the crate doesn't depend on them.
+
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::perturb;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+/// Builds the first tour.
+pub trait Construction {
+    fn construct(&self, gls: &GuidedLocalSearch, rng: &mut Mt64) -> Path;
+}
+
+/// Improves `path` until it is a local minimum of the augmented cost, `penalty_factor` being
+/// the weight of the penalties.
+pub trait LocalSearch {
+    fn improve(&mut self, gls: &GuidedLocalSearch, path: &mut Path, penalties: &mut Penalties, penalty_factor: i32, rng: &mut Mt64);
+}
+
+/// Penalizes features of a local minimum, returning the number of penalized edges.
+pub trait Penalizer {
+    fn penalize(&self, gls: &GuidedLocalSearch, path: &Path, penalties: &mut Penalties, rng: &mut Mt64) -> usize;
+}
+
+/// Kicks the penalized local minimum before the local search of each step.
+pub trait Perturbation {
+    fn perturb(&self, path: &mut Path, penalties: &Penalties, rng: &mut Mt64);
+}
+
+/// Nearest neighbor tour from vertex 0, as `solve` starts from.
+pub struct NearestNeighbor;
+
+impl Construction for NearestNeighbor {
+    fn construct(&self, gls: &GuidedLocalSearch, _: &mut Mt64) -> Path {
+        gls.nearest_neighbor().path
+    }
+}
+
+/// The 2-opt local search of `GuidedLocalSearch::local_search`, visiting the positions in an
+/// order shuffled on the first call.
+#[derive(Default)]
+pub struct TwoOpt {
+    neighborhood: Option<Path>,
+}
+
+impl LocalSearch for TwoOpt {
+    fn improve(&mut self, gls: &GuidedLocalSearch, path: &mut Path, penalties: &mut Penalties, penalty_factor: i32, rng: &mut Mt64) {
+        let neighborhood = self.neighborhood.get_or_insert_with(|| gls.neighborhood(rng));
+        gls.local_search(path, neighborhood, penalty_factor, penalties);
+    }
+}
+
+/// Penalizes the edges of maximum utility, with the options of the solver (see
+/// `GuidedLocalSearch::with_utility` and `with_tie_break`).
+pub struct MaxUtility;
+
+impl Penalizer for MaxUtility {
+    fn penalize(&self, gls: &GuidedLocalSearch, path: &Path, penalties: &mut Penalties, rng: &mut Mt64) -> usize {
+        gls.penalize(path, penalties, rng)
+    }
+}
+
+/// Leaves the tour as is, as plain GLS does.
+pub struct NoPerturbation;
+
+impl Perturbation for NoPerturbation {
+    fn perturb(&self, _: &mut Path, _: &Penalties, _: &mut Mt64) {}
+}
+
+/// `perturb::penalized_double_bridge`, breaking the most penalized edges first.
+pub struct PenalizedDoubleBridge;
+
+impl Perturbation for PenalizedDoubleBridge {
+    fn perturb(&self, path: &mut Path, penalties: &Penalties, rng: &mut Mt64) {
+        perturb::penalized_double_bridge(path, penalties, rng);
+    }
+}
+
+/// GLS assembled from the given components, returning the best tour found. Each step penalizes
+/// the local minimum reached by the previous one, perturbs it and runs the local search, a
+/// last local search without penalties improving the best tour.
+///
+/// With `NearestNeighbor`, `TwoOpt`, `MaxUtility` and `NoPerturbation`, it is `solve` with
+/// `Acceptance::Always`, only keeping the best tour rather than the last one.
+pub fn guided_local_search(
+    gls: &GuidedLocalSearch,
+    construction: &impl Construction,
+    local_search: &mut impl LocalSearch,
+    penalizer: &impl Penalizer,
+    perturbation: &impl Perturbation,
+    seed: u64,
+    steps: usize) -> Route
+{
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+    let mut penalties = Penalties::from_size(gls.distances().size());
+
+    let mut path = construction.construct(gls, &mut rng);
+    local_search.improve(gls, &mut path, &mut penalties, 0, &mut rng);
+    let mut best = Route::new(gls.cost(&path), path.clone());
+    let penalty_factor = gls.penalty_factor(best.cost);
+
+    for _ in 0..steps {
+        penalizer.penalize(gls, &path, &mut penalties, &mut rng);
+        perturbation.perturb(&mut path, &penalties, &mut rng);
+        local_search.improve(gls, &mut path, &mut penalties, penalty_factor, &mut rng);
+
+        let cost = gls.cost(&path);
+        if cost < best.cost {
+            best = Route::new(cost, path.clone());
+        }
+    }
+
+    local_search.improve(gls, &mut best.path, &mut penalties, 0, &mut rng);
+    best.cost = gls.cost(&best.path);
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::extensible::{guided_local_search, LocalSearch, MaxUtility, NearestNeighbor, NoPerturbation, PenalizedDoubleBridge, TwoOpt};
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::observer::Progress;
+    use crate::types::path::Path;
+    use crate::types::penalties::Penalties;
+    use rand_mt::Mt64;
+
+    /// `TwoOpt` keeping the local minimum of each call.
+    #[derive(Default)]
+    struct Recorded {
+        inner: TwoOpt,
+        minima: Vec<Path>,
+    }
+
+    impl LocalSearch for Recorded {
+        fn improve(&mut self, gls: &GuidedLocalSearch, path: &mut Path, penalties: &mut Penalties, penalty_factor: i32, rng: &mut Mt64) {
+            self.inner.improve(gls, path, penalties, penalty_factor, rng);
+            self.minima.push(path.clone());
+        }
+    }
+
+    #[test]
+    fn components() {
+        let gls = random_gls(100);
+
+        let plain = guided_local_search(&gls, &NearestNeighbor, &mut TwoOpt::default(), &MaxUtility, &NoPerturbation, 666, 20);
+        assert!(plain.path.is_hamiltonian());
+        assert_eq!(plain.cost, gls.cost(&plain.path));
+        assert!(plain.cost <= gls.solve(666, 20).cost);
+
+        let kicked = guided_local_search(&gls, &NearestNeighbor, &mut TwoOpt::default(), &MaxUtility, &PenalizedDoubleBridge, 666, 20);
+        assert!(kicked.path.is_hamiltonian());
+        assert_eq!(kicked.cost, gls.cost(&kicked.path));
+    }
+
+    #[test]
+    fn same_as_solve() {
+        let gls = random_gls(100);
+        let mut expected = Vec::new();
+        gls.solve_observed(666, 20, &mut |progress: &Progress| expected.push(progress.path.clone()));
+
+        // The first local search and one per step, before the last one on the best tour
+        let mut local_search = Recorded::default();
+        guided_local_search(&gls, &NearestNeighbor, &mut local_search, &MaxUtility, &NoPerturbation, 666, 20);
+        assert_eq!(expected.len(), 21);
+        assert_eq!(&local_search.minima[..21], &expected[..]);
+    }
+}
@@ -1,59 +1,19 @@
 #![feature(in_band_lifetimes)]
 
-use crate::types::gls::GuidedLocalSearch;
-use crate::types::point::Point;
-use crate::types::matrix::SymmetricMatrix;
-
 pub mod types;
-
-pub fn load_matrix() -> SymmetricMatrix {
-    let tsp = include_str!("../data/pcb3038.preprocessed.tsp");
-    let tsp = tsp
-        .lines()
-        .map(Point::from)
-        .collect::<Vec<_>>();
-    SymmetricMatrix::from_euclidean_coords(&tsp)
-}
-
-pub fn load_problem() -> GuidedLocalSearch {
-    let matrix = load_matrix();
-    GuidedLocalSearch::new(matrix)
-}
-
-pub fn gls(steps: usize, expected: i32) {
-    let tsp = load_problem();
-    let solution = tsp.solve(666, steps);
-
-    // Optimal solution
-    assert_eq!(solution.cost, expected);
-    println!("{:?}", solution);
-}
-
-pub fn main() {
-    gls(10, 152979);
-}
-
-#[cfg(test)]
-mod main {
-    use crate::gls;
-
-    #[test]
-    fn gls0() {
-        gls(0, 152991);
-    }
-
-    #[test]
-    fn gls1() {
-        gls(1, 152979);
-    }
-
-    #[test]
-    fn gls10() {
-        gls(10, 152979);
-    }
-
-    #[test]
-    fn gls25() {
-        gls(25, 152777);
-    }
-}
+#[cfg(any(test, feature = "testing"))]
+pub mod bench_support;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "plots")]
+pub mod plots;
+#[cfg(feature = "extensible")]
+pub mod extensible;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "bundled-data")]
+mod bundled;
+
+pub use crate::types::solve::{solve_file, solve_points, solve_until_gap, GapStop};
+#[cfg(feature = "bundled-data")]
+pub use crate::bundled::{load_instance, load_matrix, load_matrix_cached, load_matrix_subset, load_problem, run_pcb3038, test_fixture};
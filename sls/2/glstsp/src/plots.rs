@@ -0,0 +1,138 @@
+use crate::types::anytime::SolveReport;
+use plotters::prelude::*;
+use plotters::style::FontStyle;
+use std::ops::Range;
+
+const FONT_FAMILY: &str = "sans-serif";
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum PlotError {
+    /// The bytes given to `register_font` are not a TrueType or OpenType font.
+    InvalidFont,
+    /// Drawing or saving failed, e.g. because no font was registered.
+    Drawing(String),
+}
+
+/// Register the font used for the captions and axis labels, e.g. `include_bytes!` of a TTF
+/// file. It must be done once before drawing, no font being bundled.
+pub fn register_font(bytes: &'static [u8]) -> Result<(), PlotError> {
+    plotters::style::register_font(FONT_FAMILY, FontStyle::Normal, bytes)
+        .map_err(|_| PlotError::InvalidFont)
+}
+
+/// Write a PNG chart of the current and best costs after each step of the search.
+pub fn write_cost_by_step_png(report: &SolveReport, path: &std::path::Path, size: (u32, u32)) -> Result<(), PlotError> {
+    let best: Vec<_> = report.history.iter()
+        .scan(i32::MAX, |best, &cost| {
+            *best = (*best).min(cost);
+            Some(*best)
+        })
+        .collect();
+
+    let steps = report.history.len().max(2) - 1;
+    let by_step = |costs: &[i32]| costs.iter().enumerate().map(|(i, &c)| (i as f64, c)).collect::<Vec<_>>();
+
+    draw(path, size, "Cost by step", "step", 0.0..steps as f64, cost_range(&report.history), |chart| {
+        line(chart, by_step(&report.history), BLUE, "current")?;
+        line(chart, by_step(&best), RED, "best")
+    })
+}
+
+/// Write a PNG chart of the best cost over time, the anytime curve of the search.
+pub fn write_cost_by_time_png(report: &SolveReport, path: &std::path::Path, size: (u32, u32)) -> Result<(), PlotError> {
+    // Hold each cost until the next improvement
+    let mut points: Vec<(f64, i32)> = Vec::with_capacity(2 * report.profile.len());
+    for (i, &(elapsed, cost)) in report.profile.iter().enumerate() {
+        if i > 0 {
+            points.push((elapsed.as_secs_f64(), points[points.len() - 1].1));
+        }
+        points.push((elapsed.as_secs_f64(), cost));
+    }
+
+    let costs: Vec<_> = points.iter().map(|&(_, c)| c).collect();
+    let end = points.last().map_or(0.0, |&(t, _)| t).max(1e-3);
+    draw(path, size, "Best cost by time", "seconds", 0.0..end, cost_range(&costs), |chart| {
+        line(chart, points, RED, "best")
+    })
+}
+
+type Chart<'a> = ChartContext<'a, BitMapBackend<'a>, Cartesian2d<plotters::coord::types::RangedCoordf64, plotters::coord::types::RangedCoordi32>>;
+type DrawResult = Result<(), DrawingAreaErrorKind<<BitMapBackend<'static> as DrawingBackend>::ErrorType>>;
+
+fn draw(
+    path: &std::path::Path,
+    size: (u32, u32),
+    caption: &str,
+    x_desc: &str,
+    x_range: Range<f64>,
+    y_range: Range<i32>,
+    series: impl FnOnce(&mut Chart) -> DrawResult) -> Result<(), PlotError>
+{
+    let root = BitMapBackend::new(path, size).into_drawing_area();
+    let res: DrawResult = (|| {
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(caption, (FONT_FAMILY, 20))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(70)
+            .build_cartesian_2d(x_range, y_range)?;
+        chart.configure_mesh().x_desc(x_desc).y_desc("cost").draw()?;
+        series(&mut chart)?;
+        chart.configure_series_labels().background_style(WHITE).border_style(BLACK).draw()?;
+        root.present()
+    })();
+    res.map_err(|e| PlotError::Drawing(e.to_string()))
+}
+
+fn line(chart: &mut Chart, points: Vec<(f64, i32)>, color: RGBColor, label: &str) -> DrawResult {
+    chart.draw_series(LineSeries::new(points, color))?
+        .label(label)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    Ok(())
+}
+
+/// Y axis range covering `costs`, never empty.
+fn cost_range(costs: &[i32]) -> Range<i32> {
+    let min = costs.iter().copied().min().unwrap_or(0);
+    let max = costs.iter().copied().max().unwrap_or(0);
+    min..max.max(min + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scattered_gls;
+    use crate::plots::{register_font, write_cost_by_step_png, write_cost_by_time_png, PlotError};
+    use crate::types::anytime::Budget;
+    use std::fs;
+    use std::process;
+
+    const FONT: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+    #[test]
+    fn invalid_font() {
+        assert_eq!(register_font(b"not a font"), Err(PlotError::InvalidFont));
+    }
+
+    #[test]
+    #[ignore = "needs the DejaVu Sans font, run with --ignored where it is installed"]
+    fn png() {
+        // Drawing needs a font, no font being bundled
+        let font = fs::read(FONT).unwrap_or_else(|error| panic!("{}: {}", FONT, error));
+        register_font(Box::leak(font.into_boxed_slice())).unwrap();
+
+        let gls = scattered_gls(40);
+        let report = gls.solve_anytime(666, Budget::Steps(20));
+
+        let dir = std::env::temp_dir();
+        let by_step = dir.join(format!("glstsp-cost-by-step-{}.png", process::id()));
+        let by_time = dir.join(format!("glstsp-cost-by-time-{}.png", process::id()));
+        write_cost_by_step_png(&report, &by_step, (640, 480)).unwrap();
+        write_cost_by_time_png(&report, &by_time, (640, 480)).unwrap();
+
+        for path in [by_step, by_time] {
+            assert!(fs::read(&path).unwrap().starts_with(b"\x89PNG"));
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Helpers to declare small instances inline in tests, instead of a `SymmetricMatrix::set`
+//! per edge:
+//!
+//! ```
+//! use glstsp::instance;
+//!
+//! let instance = instance! { 0-1: 2, 0-2: 7, 0-3: 3, 1-2: 4, 1-3: 1, 2-3: 9 };
+//! assert_eq!(instance.size(), 4);
+//! assert_eq!(instance.distances()[(3, 1)], 1);
+//! ```
+//!
+//! to compare solver output with golden files (see `check_golden`), and to get the same
+//! generated instances in every test (see `random_points`).
+
+use crate::types::config::GlsConfig;
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::instance::Instance;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use crate::types::point::Point;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::io;
+
+/// Version of the golden file format, on their first line.
+pub const GOLDEN_VERSION: u32 = 2;
+
+/// Set to rewrite the golden files with the current output instead of checking it.
+pub const UPDATE_GOLDEN_VAR: &str = "GLSTSP_UPDATE_GOLDEN";
+
+/// Instance of the listed edge costs, `a-b: cost`, the size being one more than the largest
+/// vertex. See `InstanceBuilder` for costs of the unlisted edges.
+#[macro_export]
+macro_rules! instance {
+    ($($a:literal - $b:literal : $cost:expr),* $(,)?) => {
+        $crate::testing::InstanceBuilder::new()
+            $(.edge($a, $b, $cost))*
+            .build()
+    };
+}
+
+/// Same as `instance!`, returning the distance matrix.
+#[macro_export]
+macro_rules! matrix {
+    ($($a:literal - $b:literal : $cost:expr),* $(,)?) => {
+        $crate::testing::InstanceBuilder::new()
+            $(.edge($a, $b, $cost))*
+            .matrix()
+    };
+}
+
+/// Builder of small instances from their edge costs.
+#[derive(Clone, Default, Debug)]
+pub struct InstanceBuilder {
+    size: usize,
+    edges: Vec<(usize, usize, i32)>,
+    otherwise: i32,
+}
+
+impl InstanceBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// At least `size` vertices, more if an edge names a larger vertex.
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = self.size.max(size);
+        self
+    }
+
+    pub fn edge(mut self, a: usize, b: usize, cost: i32) -> Self {
+        assert_ne!(a, b, "loop on vertex {}", a);
+        self.size = self.size.max(a.max(b) + 1);
+        self.edges.push((a, b, cost));
+        self
+    }
+
+    /// Cost of the edges not given with `edge`, 0 by default.
+    pub fn otherwise(mut self, cost: i32) -> Self {
+        self.otherwise = cost;
+        self
+    }
+
+    pub fn matrix(&self) -> SymmetricMatrix {
+        let mut res = SymmetricMatrix::from_fn(self.size, |_, _| self.otherwise);
+        for &(a, b, cost) in &self.edges {
+            res.set(a, b, cost);
+        }
+        res
+    }
+
+    pub fn build(&self) -> Instance {
+        Instance::from_matrix(self.matrix())
+    }
+}
+
+/// `size` points drawn uniformly in a 1000 x 1000 square, always the same for a given size,
+/// the first ones being the same for any size.
+pub fn random_points(size: usize) -> Vec<Point> {
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+    (0..size)
+        .map(|_| Point::new(rng.gen_range(0..1000), rng.gen_range(0..1000)))
+        .collect()
+}
+
+/// Solver of `random_points(size)`.
+pub fn random_gls(size: usize) -> GuidedLocalSearch {
+    GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&random_points(size)))
+}
+
+/// `size` points spread over a 101 x 97 rectangle without randomness, the `i`-th one at
+/// `(37 i mod 101, 53 i mod 97)`: no two of the first 97 share a coordinate.
+pub fn scattered_points(size: usize) -> Vec<Point> {
+    (0..size as i32).map(|i| Point::new(i * 37 % 101, i * 53 % 97)).collect()
+}
+
+/// Solver of `scattered_points(size)`.
+pub fn scattered_gls(size: usize) -> GuidedLocalSearch {
+    GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&scattered_points(size)))
+}
+
+/// Lines are 1-based.
+#[derive(Eq, PartialEq, Debug)]
+pub enum GoldenError {
+    Io(io::ErrorKind),
+    /// No golden file for this run: set `GLSTSP_UPDATE_GOLDEN` to create it.
+    Missing(String),
+    Parse { line: usize },
+    UnsupportedVersion(u32),
+    /// The file was written by a run of another configuration, by `GlsConfig::fingerprint`,
+    /// e.g. after a change of a preset: set `GLSTSP_UPDATE_GOLDEN` if the change is meant.
+    Config { expected: u64, found: u64 },
+    /// The route differs from the stored one.
+    Mismatch { expected_cost: i32, found_cost: i32 },
+}
+
+/// Compare `route`, the output of a run of `config` with `seed` on the instance `name`, with
+/// the tour stored in `dir`, one file per instance, configuration and seed. `config_name`
+/// names the file, e.g. `balanced` for `GlsConfig::balanced`, and the file records the
+/// `GlsConfig::fingerprint` of `config` to tell when the configuration changed.
+///
+/// When the `GLSTSP_UPDATE_GOLDEN` environment variable is set, the file is written instead,
+/// e.g. after a change of the search meant to change its output.
+pub fn check_golden(
+    dir: impl AsRef<std::path::Path>,
+    name: &str,
+    config_name: &str,
+    seed: u64,
+    config: &GlsConfig,
+    route: &Route) -> Result<(), GoldenError>
+{
+    let file = dir.as_ref().join(format!("{}-{}-{}.tour", name, config_name, seed));
+
+    if std::env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        fs::create_dir_all(dir.as_ref()).map_err(|e| GoldenError::Io(e.kind()))?;
+        return fs::write(&file, format_golden(config.fingerprint(), route)).map_err(|e| GoldenError::Io(e.kind()));
+    }
+
+    let text = match fs::read_to_string(&file) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(GoldenError::Missing(file.display().to_string())),
+        Err(e) => return Err(GoldenError::Io(e.kind())),
+    };
+    let (fingerprint, expected) = parse_golden(&text)?;
+    if fingerprint != config.fingerprint() {
+        return Err(GoldenError::Config { expected: fingerprint, found: config.fingerprint() });
+    }
+    if expected != *route {
+        return Err(GoldenError::Mismatch { expected_cost: expected.cost, found_cost: route.cost });
+    }
+    Ok(())
+}
+
+fn format_golden(fingerprint: u64, route: &Route) -> String {
+    let tour: Vec<_> = route.path.as_slice().iter().map(|v| v.to_string()).collect();
+    format!("GOLDEN {}\nCONFIG {:016x}\nCOST {}\nTOUR {}\n", GOLDEN_VERSION, fingerprint, route.cost, tour.join(" "))
+}
+
+/// Configuration fingerprint and tour of a golden file.
+fn parse_golden(text: &str) -> Result<(u64, Route), GoldenError> {
+    let mut lines = text.lines();
+    let mut field = |line: usize, key: &str| {
+        lines.next()
+            .and_then(|content| content.strip_prefix(key))
+            .map(str::trim)
+            .ok_or(GoldenError::Parse { line })
+    };
+
+    let version = field(1, "GOLDEN ")?.parse().map_err(|_| GoldenError::Parse { line: 1 })?;
+    if version != GOLDEN_VERSION {
+        return Err(GoldenError::UnsupportedVersion(version));
+    }
+    let fingerprint = u64::from_str_radix(field(2, "CONFIG ")?, 16).map_err(|_| GoldenError::Parse { line: 2 })?;
+    let cost = field(3, "COST ")?.parse().map_err(|_| GoldenError::Parse { line: 3 })?;
+    let tour = field(4, "TOUR ")?
+        .split_whitespace()
+        .map(|v| v.parse().map_err(|_| GoldenError::Parse { line: 4 }))
+        .collect::<Result<Vec<usize>, _>>()?;
+    let path = Path::try_new(tour).map_err(|_| GoldenError::Parse { line: 4 })?;
+
+    Ok((fingerprint, Route::new(cost, path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{format_golden, parse_golden, GoldenError, InstanceBuilder};
+    use crate::types::config::GlsConfig;
+    use crate::types::path::Path;
+    use crate::types::route::Route;
+    use std::fs;
+
+    #[test]
+    fn builder() {
+        let matrix = InstanceBuilder::new().size(4).edge(2, 0, 5).otherwise(9).matrix();
+        assert_eq!(matrix.size(), 4);
+        assert_eq!(matrix.row(0), &[0, 9, 5, 9]);
+
+        let instance = crate::instance! { 0-1: 2, 1-2: 4, 0-2: 7 };
+        assert_eq!(instance.distances().row(2), &[7, 4, 0]);
+        assert_eq!(crate::matrix! {}.size(), 0);
+    }
+
+    #[test]
+    fn golden_format() {
+        let route = Route::new(42, Path::new(vec![2, 0, 1]));
+        let text = format_golden(0xabc, &route);
+        assert_eq!(text, "GOLDEN 2\nCONFIG 0000000000000abc\nCOST 42\nTOUR 2 0 1\n");
+        assert_eq!(parse_golden(&text), Ok((0xabc, route)));
+
+        assert_eq!(parse_golden("GOLDEN 1\n"), Err(GoldenError::UnsupportedVersion(1)));
+        assert_eq!(parse_golden("GOLDEN 2\nCONFIG 1\nCOST 42\nTOUR 2 0 0\n"), Err(GoldenError::Parse { line: 4 }));
+        assert_eq!(parse_golden("GOLDEN 2\nCONFIG 1\nTOUR 2 0 1\n"), Err(GoldenError::Parse { line: 3 }));
+        assert_eq!(parse_golden("GOLDEN 2\nCOST 42\n"), Err(GoldenError::Parse { line: 2 }));
+    }
+
+    #[test]
+    fn check_golden() {
+        let dir = std::env::temp_dir().join(format!("glstsp-golden-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = GlsConfig::default();
+        let route = Route::new(42, Path::new(vec![2, 0, 1]));
+        fs::write(dir.join("tiny-default-7.tour"), format_golden(config.fingerprint(), &route)).unwrap();
+
+        assert_eq!(super::check_golden(&dir, "tiny", "default", 7, &config, &route), Ok(()));
+        let other = config.with_steps(5);
+        assert_eq!(
+            super::check_golden(&dir, "tiny", "default", 7, &other, &route),
+            Err(GoldenError::Config { expected: config.fingerprint(), found: other.fingerprint() }),
+        );
+        let shorter = Route::new(41, Path::new(vec![2, 1, 0]));
+        assert_eq!(
+            super::check_golden(&dir, "tiny", "default", 7, &config, &shorter),
+            Err(GoldenError::Mismatch { expected_cost: 42, found_cost: 41 }),
+        );
+        assert!(matches!(super::check_golden(&dir, "tiny", "default", 8, &config, &route), Err(GoldenError::Missing(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
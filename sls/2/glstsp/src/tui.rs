@@ -0,0 +1,176 @@
+use crate::types::observer::{Observer, Progress};
+use crate::types::penalties::Penalties;
+use ratatui::DefaultTerminal;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::{BarChart, Block, Paragraph, Sparkline};
+use std::io;
+use std::time::{Duration, Instant};
+
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// Live terminal dashboard for long runs.
+///
+/// Pass it to `GuidedLocalSearch::solve_observed` to get the current and best cost, the
+/// step rate, a convergence sparkline and a histogram of the penalties while the search runs.
+/// The terminal is restored when the dashboard is dropped.
+pub struct Dashboard {
+    terminal: DefaultTerminal,
+    history: Vec<i32>,
+    refresh: Duration,
+    last_draw: Option<Instant>,
+}
+
+impl Dashboard {
+    pub fn new() -> io::Result<Self> {
+        Self::with_refresh(Duration::from_millis(250))
+    }
+
+    /// Create a dashboard redrawing at most once per `refresh`.
+    pub fn with_refresh(refresh: Duration) -> io::Result<Self> {
+        let terminal = ratatui::try_init()?;
+        Ok(Self { terminal, history: Vec::new(), refresh, last_draw: None })
+    }
+
+    fn draw(&mut self, progress: &Progress) -> io::Result<()> {
+        let stats = format!(
+            "step:      {} / {}\ncost:      {}\nbest cost: {}\nsteps/s:   {:.2}\nelapsed:   {:.1}s",
+            progress.step,
+            progress.steps,
+            progress.cost,
+            progress.best_cost,
+            progress.steps_per_sec(),
+            progress.elapsed.as_secs_f64(),
+        );
+
+        let histogram = penalty_histogram(progress.penalties, HISTOGRAM_BUCKETS);
+        let bars: Vec<_> = histogram.iter()
+            .map(|(label, count)| (label.as_str(), *count))
+            .collect();
+
+        let history = &self.history;
+
+        self.terminal.draw(|frame| {
+            let [stats_area, sparkline_area, histogram_area] = Layout::vertical([
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Min(8),
+            ]).areas(frame.area());
+
+            let width = sparkline_area.width.saturating_sub(2) as usize;
+            let sparkline = sparkline_data(history, width);
+
+            frame.render_widget(
+                Paragraph::new(stats).block(Block::bordered().title("Guided Local Search")),
+                stats_area,
+            );
+            frame.render_widget(
+                Sparkline::default().block(Block::bordered().title("Cost")).data(&sparkline),
+                sparkline_area,
+            );
+            frame.render_widget(
+                BarChart::default().block(Block::bordered().title("Penalties")).bar_width(7).data(&bars),
+                histogram_area,
+            );
+        })?;
+
+        Ok(())
+    }
+}
+
+impl Observer for Dashboard {
+    fn on_step(&mut self, progress: &Progress) {
+        self.history.push(progress.cost);
+
+        let last = progress.step == progress.steps;
+        let due = self.last_draw.is_none_or(|t| t.elapsed() >= self.refresh);
+
+        if last || due {
+            // A failed redraw must not abort the search, the next one will try again.
+            let _ = self.draw(progress);
+            self.last_draw = Some(Instant::now());
+        }
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// Count the penalized edges grouping their penalty values in at most `buckets` ranges.
+fn penalty_histogram(penalties: &Penalties, buckets: usize) -> Vec<(String, u64)> {
+    let values: Vec<_> = penalties.nonzero().map(|(_, p)| p).collect();
+
+    let max = match values.iter().copied().max() {
+        Some(max) => max as usize,
+        None => return Vec::new(),
+    };
+
+    let width = max.div_ceil(buckets);
+    let mut counts = vec![0u64; max.div_ceil(width)];
+    for p in values {
+        counts[(p as usize - 1) / width] += 1;
+    }
+
+    counts.into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let from = i * width + 1;
+            let to = (i + 1) * width;
+            let label = if from == to { from.to_string() } else { format!("{}-{}", from, to) };
+            (label, count)
+        })
+        .collect()
+}
+
+/// Last `width` costs shifted so the lowest one is drawn as the shortest bar.
+fn sparkline_data(history: &[i32], width: usize) -> Vec<u64> {
+    let window = &history[history.len().saturating_sub(width)..];
+    let min = window.iter().copied().min().unwrap_or(0);
+    window.iter().map(|&cost| (cost - min) as u64 + 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(test)]
+    mod penalty_histogram {
+        use crate::tui::penalty_histogram;
+        use crate::types::penalties::Penalties;
+
+        #[test]
+        fn empty() {
+            let penalties = Penalties::from_size(4);
+            assert_eq!(penalty_histogram(&penalties, 10), vec![]);
+        }
+
+        #[test]
+        fn single_values() {
+            let mut penalties = Penalties::from_size(4);
+            penalties.set((0, 1), 1);
+            penalties.set((1, 2), 1);
+            penalties.set((2, 3), 3);
+
+            let expected = vec![("1".to_owned(), 2), ("2".to_owned(), 0), ("3".to_owned(), 1)];
+            assert_eq!(penalty_histogram(&penalties, 10), expected);
+        }
+
+        #[test]
+        fn ranges() {
+            let mut penalties = Penalties::from_size(4);
+            penalties.set((0, 1), 1);
+            penalties.set((1, 2), 4);
+            penalties.set((2, 3), 5);
+
+            let expected = vec![("1-3".to_owned(), 1), ("4-6".to_owned(), 2)];
+            assert_eq!(penalty_histogram(&penalties, 2), expected);
+        }
+    }
+
+    #[test]
+    fn sparkline_data() {
+        let history = vec![10, 8, 9, 7];
+        assert_eq!(crate::tui::sparkline_data(&history, 10), vec![4, 2, 3, 1]);
+        assert_eq!(crate::tui::sparkline_data(&history, 2), vec![3, 1]);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::types::config::ConfigError;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::observer::Progress;
+use crate::types::path::Path;
+use crate::types::route::Route;
+use std::mem;
+use std::time::{Duration, Instant};
+
+/// When to stop a search.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Budget {
+    /// Number of penalization steps, the search being reproducible.
+    Steps(usize),
+    /// Wall-clock time, checked between steps.
+    Time(Duration),
+}
+
+impl Budget {
+    pub fn validate(self) -> Result<Self, ConfigError> {
+        match self {
+            Budget::Steps(0) => Err(ConfigError::ZeroSteps),
+            Budget::Time(time) if time.is_zero() => Err(ConfigError::ZeroTimeLimit),
+            _ => Ok(self),
+        }
+    }
+
+    /// Steps and deadline of a search started at `start`.
+    fn limits(self, start: Instant) -> (usize, Option<Instant>) {
+        match self {
+            Budget::Steps(steps) => (steps, None),
+            Budget::Time(time) => (usize::MAX, Some(start + time)),
+        }
+    }
+}
+
+/// Best route of a search and its quality profile.
+#[derive(Clone, Debug)]
+pub struct SolveReport {
+    pub route: Route,
+    /// `(elapsed, cost)` each time the best cost improved, the last cost being `route.cost`.
+    pub profile: Vec<(Duration, i32)>,
+    /// Cost of the current tour after each step, starting with the first local search.
+    pub history: Vec<i32>,
+    /// Edges penalized by each step, aligned with `history` (0 for the first local search).
+    pub penalized: Vec<usize>,
+    /// Largest penalty of an edge after each step, aligned with `history`.
+    pub max_penalty: Vec<i32>,
+    /// Sum of the penalties of all the edges after each step, aligned with `history`.
+    pub penalty_mass: Vec<i64>,
+    /// Moves applied by the local search of each step, aligned with `history`.
+    pub moves: Vec<u64>,
+    /// `Route::gap_to_cost` of the route, when the optimum is known (see `with_optimum`).
+    pub optimality_gap: Option<f64>,
+}
+
+impl SolveReport {
+    /// Report the gap of the route to the `optimum` cost of the instance.
+    pub fn with_optimum(mut self, optimum: i32) -> Self {
+        self.optimality_gap = Some(self.route.gap_to_cost(optimum));
+        self
+    }
+}
+
+impl GuidedLocalSearch {
+    /// Same as `solve` within `budget`, returning the best tour seen rather than the last one,
+    /// along with the anytime curve of the search.
+    pub fn solve_anytime(&self, seed: u64, budget: Budget) -> SolveReport {
+        let start = Instant::now();
+        let (steps, deadline) = budget.limits(start);
+
+        let mut best: Option<Route> = None;
+        let mut profile = Vec::new();
+        let mut history = Vec::new();
+        let mut penalized = Vec::new();
+        let mut max_penalty = Vec::new();
+        let mut penalty_mass = Vec::new();
+        let mut moves = Vec::new();
+        let mut observer = |progress: &Progress| {
+            history.push(progress.cost);
+            penalized.push(progress.penalized);
+            max_penalty.push(progress.penalties.max());
+            penalty_mass.push(progress.penalties.total());
+            moves.push(progress.moves);
+            if best.as_ref().is_none_or(|best| progress.cost < best.cost) {
+                best = Some(Route::new(progress.cost, progress.path.clone()));
+                profile.push((start.elapsed(), progress.cost));
+            }
+        };
+        let last = self.search_in(seed, steps, deadline, &mut observer, &mut SolverWorkspace::new());
+
+        // The last pass without penalties may still improve the tour
+        let info = last.info.clone();
+        let mut best = match best {
+            Some(best) if best.cost <= last.cost => best,
+            _ => {
+                profile.push((start.elapsed(), last.cost));
+                last
+            },
+        };
+        best.info = info;
+
+        SolveReport {
+            route: self.pinned(best),
+            profile,
+            history,
+            penalized,
+            max_penalty,
+            penalty_mass,
+            moves,
+            optimality_gap: None,
+        }
+    }
+
+    /// Second phase of `solve`, running GLS from `route` within `budget` and replacing it with
+    /// the tour reached. `solve(seed, steps)` is
+    /// `improve(&mut construct(Construction::NearestNeighbor, seed), seed, Budget::Steps(steps))`.
+    ///
+    /// Panics unless `route` is a tour of the vertices of the instance.
+    pub fn improve(&self, route: &mut Route, seed: u64, budget: Budget) {
+        let (steps, deadline) = budget.limits(Instant::now());
+        let start = mem::replace(route, Route::new(0, Path(Vec::new())));
+        *route = self.search_from(start, seed, steps, deadline, &mut |_: &Progress| {}, &mut SolverWorkspace::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::anytime::Budget;
+    use crate::types::config::ConfigError;
+    use crate::types::construction::Construction;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn validate() {
+        assert_eq!(Budget::Steps(10).validate(), Ok(Budget::Steps(10)));
+        assert_eq!(Budget::Steps(0).validate(), Err(ConfigError::ZeroSteps));
+        assert_eq!(Budget::Time(Duration::ZERO).validate(), Err(ConfigError::ZeroTimeLimit));
+    }
+
+    #[test]
+    fn two_phases() {
+        let gls = random_gls(200);
+        let expected = gls.solve(666, 20);
+
+        let mut route = gls.construct(Construction::NearestNeighbor, 666);
+        gls.improve(&mut route, 666, Budget::Steps(20));
+        assert_eq!((route.cost, &route.path), (expected.cost, &expected.path));
+
+        for construction in [Construction::CheapestInsertion, Construction::FarthestInsertion, Construction::Random] {
+            let mut route = gls.construct(construction, 666);
+            assert!(route.path.is_hamiltonian());
+            assert_eq!(route.cost, gls.cost(&route.path));
+
+            gls.improve(&mut route, 666, Budget::Time(Duration::from_millis(50)));
+            assert!(route.path.is_hamiltonian());
+            assert_eq!(route.cost, gls.cost(&route.path));
+        }
+    }
+
+    #[test]
+    fn steps() {
+        let gls = random_gls(200);
+        let actual = gls.solve_anytime(666, Budget::Steps(20));
+
+        assert!(actual.route.path.is_hamiltonian());
+        assert_eq!(actual.route.cost, gls.cost(&actual.route.path));
+        assert!(actual.route.cost <= gls.solve(666, 20).cost);
+        let info = actual.route.info.unwrap();
+        assert_eq!((info.seed, info.iterations), (666, 20));
+
+        assert_eq!(actual.profile.last().unwrap().1, actual.route.cost);
+        assert_eq!(actual.history.len(), 21);
+        assert_eq!(actual.penalized.len(), 21);
+        assert_eq!(actual.penalized[0], 0);
+        assert!(actual.penalized[1..].iter().all(|&count| count >= 1));
+
+        assert_eq!((actual.max_penalty.len(), actual.penalty_mass.len(), actual.moves.len()), (21, 21, 21));
+        assert_eq!((actual.max_penalty[0], actual.penalty_mass[0]), (0, 0));
+        assert!(actual.moves[0] > 0);
+        // Only the last local search, without penalties, is left out
+        assert!(actual.moves.iter().sum::<u64>() <= info.moves);
+        let mut mass = 0;
+        for (step, &count) in actual.penalized.iter().enumerate() {
+            mass += count as i64;
+            assert_eq!(actual.penalty_mass[step], mass);
+            assert!(actual.max_penalty[step] as i64 <= mass);
+        }
+        for pair in actual.profile.windows(2) {
+            assert!(pair[0].0 <= pair[1].0);
+            assert!(pair[0].1 > pair[1].1);
+        }
+    }
+
+    #[test]
+    fn time() {
+        let gls = random_gls(200);
+        let start = Instant::now();
+        let actual = gls.solve_anytime(666, Budget::Time(Duration::from_millis(100)));
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(actual.route.path.is_hamiltonian());
+        assert_eq!(actual.profile.last().unwrap().1, actual.route.cost);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::types::instance::Instance;
+use crate::types::path::Path;
+use crate::types::route::Route;
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+
+const MAGIC: &[u8; 4] = b"GLST";
+const VERSION: u8 = 1;
+/// Same as `VERSION` with the fingerprint of the instance (8 bytes, little-endian) after its size.
+const FINGERPRINT_VERSION: u8 = 2;
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum ArchiveError {
+    Io(io::ErrorKind),
+    /// Not an archive.
+    Magic,
+    UnsupportedVersion(u8),
+    /// The data ends in the middle of a value.
+    Truncated,
+    /// A value doesn't fit its type.
+    Overflow,
+    /// The tour (0-based) is not a permutation of the instance vertices.
+    InvalidTour(usize),
+    /// The tours belong to another instance, see `Archive::check`.
+    InstanceMismatch,
+}
+
+/// Tours read from an archive.
+#[derive(Eq, PartialEq, Debug)]
+pub struct Archive {
+    pub size: usize,
+    /// `Instance::fingerprint` of the instance, for archives written by `write_instance_tours`.
+    pub fingerprint: Option<u64>,
+    pub routes: Vec<Route>,
+}
+
+impl Archive {
+    /// Refuse to apply the tours to `instance` if they were written for another one: another
+    /// size, or another fingerprint when the archive has one.
+    pub fn check(&self, instance: &Instance) -> Result<(), ArchiveError> {
+        let same_fingerprint = self.fingerprint.is_none_or(|fingerprint| fingerprint == instance.fingerprint());
+        if self.size != instance.size() || !same_fingerprint {
+            return Err(ArchiveError::InstanceMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Write `routes`, all tours of an instance with `size` vertices, in a compact binary format
+/// meant for storing many tours (elite pools, search trajectories).
+///
+/// Integers are LEB128 varints, signed ones zigzag encoded. After a header (magic `GLST`, format
+/// version, size and number of tours) each tour is its cost, its first vertex, then the
+/// difference between each vertex and the previous one. Good tours mostly link close vertices,
+/// so with spatially ordered vertex ids (as in most TSPLIB instances) these take a byte or two.
+pub fn write_tours(size: usize, routes: &[Route], writer: &mut impl Write) -> io::Result<()> {
+    write(size, None, routes, writer)
+}
+
+/// Same as `write_tours` for tours of `instance`, also storing its fingerprint so they can't
+/// be applied to another instance by mistake (see `Archive::check`).
+pub fn write_instance_tours(instance: &Instance, routes: &[Route], writer: &mut impl Write) -> io::Result<()> {
+    write(instance.size(), Some(instance.fingerprint()), routes, writer)
+}
+
+fn write(size: usize, fingerprint: Option<u64>, routes: &[Route], writer: &mut impl Write) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(MAGIC);
+    buffer.push(if fingerprint.is_some() { FINGERPRINT_VERSION } else { VERSION });
+    write_unsigned(&mut buffer, size as u64);
+    if let Some(fingerprint) = fingerprint {
+        buffer.extend_from_slice(&fingerprint.to_le_bytes());
+    }
+    write_unsigned(&mut buffer, routes.len() as u64);
+
+    for route in routes {
+        assert_eq!(route.path.len(), size);
+        write_signed(&mut buffer, route.cost as i64);
+
+        let mut previous = 0;
+        for (i, vertex) in route.path.0.iter().copied().enumerate() {
+            if i == 0 {
+                write_unsigned(&mut buffer, vertex as u64);
+            } else {
+                write_signed(&mut buffer, vertex as i64 - previous as i64);
+            }
+            previous = vertex;
+        }
+    }
+
+    writer.write_all(&buffer)
+}
+
+pub fn read_tours(reader: &mut impl Read) -> Result<Archive, ArchiveError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).map_err(|e| ArchiveError::Io(e.kind()))?;
+    let mut input = data.as_slice();
+
+    if input.len() < MAGIC.len() + 1 || &input[..MAGIC.len()] != MAGIC {
+        return Err(ArchiveError::Magic);
+    }
+    let version = input[MAGIC.len()];
+    if version != VERSION && version != FINGERPRINT_VERSION {
+        return Err(ArchiveError::UnsupportedVersion(version));
+    }
+    input = &input[MAGIC.len() + 1..];
+
+    let size = usize::try_from(read_unsigned(&mut input)?).map_err(|_| ArchiveError::Overflow)?;
+    let fingerprint = if version == FINGERPRINT_VERSION {
+        if input.len() < 8 {
+            return Err(ArchiveError::Truncated);
+        }
+        let (bytes, rest) = input.split_at(8);
+        input = rest;
+        Some(u64::from_le_bytes(<[u8; 8]>::try_from(bytes).unwrap()))
+    } else {
+        None
+    };
+    let count = read_unsigned(&mut input)?;
+
+    // Don't trust the header for allocations: every vertex takes at least one byte
+    let mut routes = Vec::new();
+    for index in 0..count {
+        let cost = i32::try_from(read_signed(&mut input)?).map_err(|_| ArchiveError::Overflow)?;
+        if input.len() < size {
+            return Err(ArchiveError::Truncated);
+        }
+
+        let mut path = Vec::with_capacity(size);
+        let mut previous = 0i64;
+        for i in 0..size {
+            let vertex = if i == 0 {
+                i64::try_from(read_unsigned(&mut input)?).map_err(|_| ArchiveError::Overflow)?
+            } else {
+                previous.checked_add(read_signed(&mut input)?).ok_or(ArchiveError::Overflow)?
+            };
+            path.push(usize::try_from(vertex).map_err(|_| ArchiveError::InvalidTour(index as usize))?);
+            previous = vertex;
+        }
+
+        let path = Path::try_new(path).map_err(|_| ArchiveError::InvalidTour(index as usize))?;
+        routes.push(Route::new(cost, path));
+    }
+
+    Ok(Archive { size, fingerprint, routes })
+}
+
+fn write_unsigned(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn write_signed(buffer: &mut Vec<u8>, value: i64) {
+    write_unsigned(buffer, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+fn read_unsigned(input: &mut &[u8]) -> Result<u64, ArchiveError> {
+    let mut res = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input.split_first().ok_or(ArchiveError::Truncated)?;
+        *input = rest;
+
+        let bits = (byte & 0x7f) as u64;
+        if shift == 63 && bits > 1 {
+            return Err(ArchiveError::Overflow);
+        }
+        res |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(res);
+        }
+    }
+    Err(ArchiveError::Overflow)
+}
+
+fn read_signed(input: &mut &[u8]) -> Result<i64, ArchiveError> {
+    let value = read_unsigned(input)?;
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scattered_points;
+    use crate::types::archive::{read_signed, read_tours, read_unsigned, write_instance_tours, write_signed, write_tours, write_unsigned, Archive, ArchiveError};
+    use crate::types::instance::Instance;
+    use crate::types::path::Path;
+    use crate::types::point::Point;
+    use crate::types::route::Route;
+
+    fn create_routes() -> Vec<Route> {
+        vec![
+            Route::new(42, Path::new(vec![0, 1, 2, 3, 4, 5])),
+            Route::new(-7, Path::new(vec![5, 3, 1, 0, 2, 4])),
+            Route::new(i32::MAX, Path::new(vec![2, 3, 4, 5, 0, 1])),
+        ]
+    }
+
+    #[test]
+    fn varints() {
+        for value in [0, 1, 127, 128, 300, u64::MAX] {
+            let mut buffer = Vec::new();
+            write_unsigned(&mut buffer, value);
+            assert_eq!(read_unsigned(&mut buffer.as_slice()), Ok(value));
+        }
+        for value in [0, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+            let mut buffer = Vec::new();
+            write_signed(&mut buffer, value);
+            assert_eq!(read_signed(&mut buffer.as_slice()), Ok(value));
+        }
+
+        let mut buffer = Vec::new();
+        write_signed(&mut buffer, -1);
+        assert_eq!(buffer, vec![1]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut data = Vec::new();
+        write_tours(6, &create_routes(), &mut data).unwrap();
+
+        // Header, costs and one byte per vertex, except i32::MAX taking 5 bytes
+        assert_eq!(data.len(), 5 + 2 + 3 * 6 + 1 + 1 + 5);
+
+        let actual = read_tours(&mut data.as_slice()).unwrap();
+        assert_eq!(actual, Archive { size: 6, fingerprint: None, routes: create_routes() });
+    }
+
+    #[test]
+    fn fingerprint() {
+        let instance = Instance::from_points(scattered_points(6));
+        let mut data = Vec::new();
+        write_instance_tours(&instance, &create_routes(), &mut data).unwrap();
+
+        let actual = read_tours(&mut data.as_slice()).unwrap();
+        assert_eq!(actual.fingerprint, Some(instance.fingerprint()));
+        assert_eq!(actual.routes, create_routes());
+        assert_eq!(actual.check(&instance), Ok(()));
+
+        let mut other = Instance::from_points(scattered_points(6));
+        other.set_distance(0, 1, 1);
+        assert_eq!(actual.check(&other), Err(ArchiveError::InstanceMismatch));
+
+        // Without fingerprint only the size is checked
+        let mut data = Vec::new();
+        write_tours(6, &create_routes(), &mut data).unwrap();
+        let archive = read_tours(&mut data.as_slice()).unwrap();
+        assert_eq!(archive.check(&other), Ok(()));
+        let larger = Instance::from_points((0..7).map(|i| Point::new(i, 0)).collect());
+        assert_eq!(archive.check(&larger), Err(ArchiveError::InstanceMismatch));
+    }
+
+    #[test]
+    fn errors() {
+        let mut data = Vec::new();
+        write_tours(6, &create_routes(), &mut data).unwrap();
+
+        assert_eq!(read_tours(&mut &b"GLSX\x01"[..]).err(), Some(ArchiveError::Magic));
+        assert_eq!(read_tours(&mut &b"GLST\x03"[..]).err(), Some(ArchiveError::UnsupportedVersion(3)));
+        assert_eq!(read_tours(&mut &data[..data.len() - 1]).err(), Some(ArchiveError::Truncated));
+
+        // The second tour now starts at 4 and its differences lead below 0
+        let mut corrupted = data.clone();
+        corrupted[5 + 2 + 1 + 6 + 1] = 4;
+        assert_eq!(read_tours(&mut corrupted.as_slice()).err(), Some(ArchiveError::InvalidTour(1)));
+
+        // Huge size and count in the header
+        let mut header = b"GLST\x01".to_vec();
+        write_unsigned(&mut header, 1 << 40);
+        write_unsigned(&mut header, 1 << 40);
+        header.push(0);
+        assert_eq!(read_tours(&mut header.as_slice()).err(), Some(ArchiveError::Truncated));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::types::config::GlsConfig;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::instance::Instance;
+use crate::types::route::Route;
+use rayon::prelude::*;
+
+/// Solve every instance with the same configuration, in parallel across instances.
+///
+/// Meant for many small problems (e.g. dispatching hundreds of 20-100 stop tours): each
+/// thread keeps its search buffers between the instances it solves. The result is
+/// reproducible, the routes being in the same order as `instances`.
+pub fn solve_batch(instances: &[Instance], config: &GlsConfig) -> Vec<Route> {
+    instances.par_iter()
+        .map_init(SolverWorkspace::new, |workspace, instance| {
+            GuidedLocalSearch::from_instance(instance)
+                .with_config(config)
+                .solve_with_workspace(config.seed, config.steps, workspace)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::batch::solve_batch;
+    use crate::types::config::GlsConfig;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::instance::Instance;
+
+    fn create_instances() -> Vec<Instance> {
+        (0..12).map(|i| Instance::from_points(random_points(20 + 5 * i))).collect()
+    }
+
+    #[test]
+    fn same_as_solve() {
+        let instances = create_instances();
+        let config = GlsConfig::default().with_steps(10);
+        let actual = solve_batch(&instances, &config);
+
+        assert_eq!(actual.len(), instances.len());
+        for (route, instance) in actual.iter().zip(&instances) {
+            let expected = GuidedLocalSearch::from_instance(instance).solve(config.seed, config.steps);
+            assert_eq!(route, &expected);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert!(solve_batch(&[], &GlsConfig::default()).is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::types::matrix::SymmetricMatrix;
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+use std::mem;
+
+const MAGIC: &[u8; 4] = b"GLSM";
+const VERSION: u8 = 1;
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum MatrixFileError {
+    Io(io::ErrorKind),
+    /// Not a matrix file.
+    Magic,
+    UnsupportedVersion(u8),
+    /// The data ends before the last value, or continues after it.
+    Truncated,
+    /// The size doesn't fit in memory.
+    Overflow,
+}
+
+impl SymmetricMatrix {
+    /// Write the matrix in a binary format, e.g. to cache the distances of a large instance
+    /// instead of computing them on every run.
+    ///
+    /// After a header (magic `GLSM`, format version, size as a little-endian `u64`) come the
+    /// values of the upper triangle with the diagonal, row by row, as little-endian `i32`.
+    pub fn write_binary(&self, writer: &mut impl Write) -> io::Result<()> {
+        let values = self.size * (self.size + 1) / 2;
+        let mut buffer = Vec::with_capacity(MAGIC.len() + 1 + 8 + values * mem::size_of::<i32>());
+        buffer.extend_from_slice(MAGIC);
+        buffer.push(VERSION);
+        buffer.extend_from_slice(&(self.size as u64).to_le_bytes());
+        for i in 0..self.size {
+            for value in &self.row(i)[i..] {
+                buffer.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        writer.write_all(&buffer)
+    }
+
+    /// Matrix written by `write_binary`.
+    pub fn read_binary(reader: &mut impl Read) -> Result<Self, MatrixFileError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).map_err(|e| MatrixFileError::Io(e.kind()))?;
+
+        if data.len() < MAGIC.len() + 1 || &data[..MAGIC.len()] != MAGIC {
+            return Err(MatrixFileError::Magic);
+        }
+        let version = data[MAGIC.len()];
+        if version != VERSION {
+            return Err(MatrixFileError::UnsupportedVersion(version));
+        }
+        let input = &data[MAGIC.len() + 1..];
+        if input.len() < 8 {
+            return Err(MatrixFileError::Truncated);
+        }
+        let (size, input) = input.split_at(8);
+        let size = usize::try_from(u64::from_le_bytes(<[u8; 8]>::try_from(size).unwrap()))
+            .map_err(|_| MatrixFileError::Overflow)?;
+
+        // Check the length before allocating the matrix from the header
+        let bytes = size.checked_add(1)
+            .and_then(|next| size.checked_mul(next))
+            .and_then(|values| (values / 2).checked_mul(mem::size_of::<i32>()))
+            .ok_or(MatrixFileError::Overflow)?;
+        if input.len() != bytes {
+            return Err(MatrixFileError::Truncated);
+        }
+
+        let mut res = Self::from_size(size);
+        let mut values = input
+            .chunks_exact(mem::size_of::<i32>())
+            .map(|bytes| i32::from_le_bytes(<[u8; 4]>::try_from(bytes).unwrap()));
+        for i in 0..size {
+            for j in i..size {
+                res.set(i, j, values.next().unwrap());
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::binary::MatrixFileError;
+    use crate::types::matrix::SymmetricMatrix;
+
+    fn create_matrix() -> SymmetricMatrix {
+        let mut matrix = SymmetricMatrix::from_fn(5, |i, j| (i * 10 + j) as i32 - 20);
+        matrix.set(3, 3, i32::MAX);
+        matrix
+    }
+
+    #[test]
+    fn round_trip() {
+        let matrix = create_matrix();
+        let mut buffer = Vec::new();
+        matrix.write_binary(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), 4 + 1 + 8 + 15 * 4);
+        assert!(SymmetricMatrix::read_binary(&mut buffer.as_slice()).unwrap() == matrix);
+
+        let mut empty = Vec::new();
+        SymmetricMatrix::from_size(0).write_binary(&mut empty).unwrap();
+        assert_eq!(SymmetricMatrix::read_binary(&mut empty.as_slice()).unwrap().size(), 0);
+    }
+
+    #[test]
+    fn errors() {
+        let mut buffer = Vec::new();
+        create_matrix().write_binary(&mut buffer).unwrap();
+        let read = |bytes: &[u8]| SymmetricMatrix::read_binary(&mut &bytes[..]).err();
+
+        assert_eq!(read(b"GLST\x01"), Some(MatrixFileError::Magic));
+        assert_eq!(read(b"GLSM\x07"), Some(MatrixFileError::UnsupportedVersion(7)));
+        assert_eq!(read(&buffer[..buffer.len() - 1]), Some(MatrixFileError::Truncated));
+        assert_eq!(read(&[&buffer[..], &[0]].concat()), Some(MatrixFileError::Truncated));
+
+        let mut huge = b"GLSM\x01".to_vec();
+        huge.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(read(&huge), Some(MatrixFileError::Overflow));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::types::instance::Instance;
+use crate::types::point::Point;
+use crate::types::tsplib::{Tsplib, TsplibError};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// How `InstanceCache` reads a file.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub enum InstanceFormat {
+    /// See `Tsplib::read_file`.
+    #[default]
+    Tsplib,
+    /// One `x y` point per line, rounded down to integers, with Euclidean distances, as the
+    /// preprocessed `pcb3038` benchmark file.
+    Points,
+}
+
+/// Instances read from files, kept to skip parsing and building the distance matrix again when
+/// the same file is loaded with the same format, e.g. by repeated experiment runs or a long
+/// running service.
+///
+/// A file modified since it was cached is read again, on the platforms reporting modification
+/// times. The cache can be shared between threads.
+#[derive(Default)]
+pub struct InstanceCache {
+    entries: Mutex<HashMap<(PathBuf, InstanceFormat), Entry>>,
+}
+
+struct Entry {
+    modified: Option<SystemTime>,
+    instance: Arc<Instance>,
+}
+
+impl InstanceCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The instance stored at `path`, read in `format` unless cached. Errors are not cached.
+    ///
+    /// `Points` files report their unparsable lines as `TsplibError::Parse`.
+    pub fn load(&self, path: impl AsRef<Path>, format: InstanceFormat) -> Result<Arc<Instance>, TsplibError> {
+        let path = path.as_ref();
+        let modified = fs::metadata(path).map_err(|e| TsplibError::Io(e.kind()))?.modified().ok();
+        let key = (path.to_path_buf(), format);
+
+        if let Some(entry) = self.entries.lock().unwrap().get(&key) {
+            if entry.modified == modified {
+                return Ok(entry.instance.clone());
+            }
+        }
+
+        // Read without the lock, so other files load in the meantime
+        let instance = Arc::new(match format {
+            InstanceFormat::Tsplib => Tsplib::read_file(path)?.into_instance(),
+            InstanceFormat::Points => read_points(path)?,
+        });
+        self.entries.lock().unwrap().insert(key, Entry { modified, instance: instance.clone() });
+        Ok(instance)
+    }
+
+    /// Number of cached instances.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget the instances read from `path`, in any format.
+    pub fn remove(&self, path: impl AsRef<Path>) {
+        self.entries.lock().unwrap().retain(|(cached, _), _| cached != path.as_ref());
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+fn read_points(path: &Path) -> Result<Instance, TsplibError> {
+    let text = fs::read_to_string(path).map_err(|e| TsplibError::Io(e.kind()))?;
+    let points = text.lines()
+        .enumerate()
+        .filter(|(_, content)| !content.trim().is_empty())
+        .map(|(line, content)| {
+            let error = || TsplibError::Parse { line: line + 1 };
+            let mut fields = content.split_whitespace().map(|field| field.parse::<f64>().map_err(|_| error()));
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(x), Some(y), None) => Ok(Point::new(x? as i32, y? as i32)),
+                _ => Err(error()),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Instance::from_points(points))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::cache::{InstanceCache, InstanceFormat};
+    use crate::types::tsplib::TsplibError;
+    use std::sync::Arc;
+
+    #[test]
+    fn load() {
+        let cache = InstanceCache::new();
+        let burma14 = cache.load("data/tsplib/burma14.tsp", InstanceFormat::Tsplib).unwrap();
+        assert_eq!(burma14.size(), 14);
+
+        let again = cache.load("data/tsplib/burma14.tsp", InstanceFormat::Tsplib).unwrap();
+        assert!(Arc::ptr_eq(&burma14, &again));
+        assert_eq!(cache.len(), 1);
+
+        let pcb3038 = cache.load("data/pcb3038.preprocessed.tsp", InstanceFormat::Points).unwrap();
+        assert_eq!(pcb3038.size(), 3038);
+        assert_eq!(cache.len(), 2);
+
+        cache.remove("data/tsplib/burma14.tsp");
+        let reloaded = cache.load("data/tsplib/burma14.tsp", InstanceFormat::Tsplib).unwrap();
+        assert!(!Arc::ptr_eq(&burma14, &reloaded));
+        assert!(*burma14 == *reloaded);
+    }
+
+    #[test]
+    fn errors() {
+        let cache = InstanceCache::new();
+        let missing = cache.load("data/tsplib/missing.tsp", InstanceFormat::Tsplib);
+        assert_eq!(missing.err(), Some(TsplibError::Io(std::io::ErrorKind::NotFound)));
+
+        let points = cache.load("data/tsplib/burma14.tsp", InstanceFormat::Points);
+        assert_eq!(points.err(), Some(TsplibError::Parse { line: 1 }));
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use std::hash::Hasher;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hasher. Unlike `DefaultHasher` its output is specified, so the hashes can be
+/// stored and compared across builds. Integers are hashed little-endian.
+pub struct Fnv64(u64);
+
+impl Fnv64 {
+    pub fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl Default for Fnv64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Fnv64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+impl SymmetricMatrix {
+    /// Hash of the size and of the costs above the diagonal, identifying the instance solved.
+    ///
+    /// The costs are mixed in one FNV-1a round per 32-bit value rather than per byte, which
+    /// keeps it fast enough to compute for every solve.
+    pub fn checksum(&self) -> u64 {
+        let size = self.size();
+        let mut hash = Fnv64::new();
+        hash.write_usize(size);
+
+        let mut res = hash.finish();
+        for i in 0..size {
+            for &cost in &self.row(i)[i + 1..] {
+                res ^= cost as u32 as u64;
+                res = res.wrapping_mul(FNV_PRIME);
+            }
+        }
+        res
+    }
+}
+
+impl Path {
+    /// Hash of the tour whatever its starting vertex and direction, equal for tours with the
+    /// same edges, e.g. to spot a local optimum already found by another run.
+    ///
+    /// The tour is read from its smallest vertex toward the smaller of its two neighbors,
+    /// mixing one vertex per FNV-1a round as `SymmetricMatrix::checksum`.
+    pub fn tour_hash(&self) -> u64 {
+        let len = self.len();
+        let mut hash = Fnv64::new();
+        hash.write_usize(len);
+        let mut res = hash.finish();
+        if len == 0 {
+            return res;
+        }
+
+        let (start, _) = self.0.iter().enumerate().min_by_key(|&(_, &v)| v).unwrap();
+        let forward = self[(start + 1) % len] <= self[(start + len - 1) % len];
+        for i in 0..len {
+            let pos = if forward { (start + i) % len } else { (start + len - i) % len };
+            res ^= self[pos] as u32 as u64;
+            res = res.wrapping_mul(FNV_PRIME);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::checksum::Fnv64;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use std::hash::Hasher;
+
+    #[test]
+    fn fnv() {
+        // Reference values of the FNV-1a specification
+        assert_eq!(Fnv64::new().finish(), 0xcbf29ce484222325);
+        let mut hash = Fnv64::new();
+        hash.write(b"a");
+        assert_eq!(hash.finish(), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn checksum() {
+        let matrix = SymmetricMatrix::from_fn(5, |i, j| (i * 10 + j) as i32);
+        assert_eq!(matrix.checksum(), matrix.clone().checksum());
+
+        let mut changed = matrix.clone();
+        changed.set(3, 1, 0);
+        assert_ne!(changed.checksum(), matrix.checksum());
+        assert_ne!(SymmetricMatrix::from_size(4).checksum(), SymmetricMatrix::from_size(5).checksum());
+    }
+
+    #[test]
+    fn tour_hash() {
+        let path = Path::new(vec![3, 1, 4, 0, 2, 5]);
+        // Rotated, reversed, both
+        assert_eq!(path.tour_hash(), Path::new(vec![0, 2, 5, 3, 1, 4]).tour_hash());
+        assert_eq!(path.tour_hash(), Path::new(vec![5, 2, 0, 4, 1, 3]).tour_hash());
+        assert_eq!(path.tour_hash(), Path::new(vec![1, 3, 5, 2, 0, 4]).tour_hash());
+
+        // One 2-opt move away
+        assert_ne!(path.tour_hash(), Path::new(vec![3, 1, 0, 4, 2, 5]).tour_hash());
+        assert_ne!(Path::new(vec![0, 1]).tour_hash(), Path::new(vec![0, 1, 2]).tour_hash());
+    }
+}
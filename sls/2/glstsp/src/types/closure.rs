@@ -0,0 +1,133 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use rayon::prelude::*;
+
+/// Routing table of a metric closure, used to expand a tour over the closure back into a walk
+/// over the original graph.
+pub struct MetricClosure {
+    size: usize,
+    /// `next[i * size + j]` is the vertex following `i` in the shortest path from `i` to `j`.
+    next: Vec<usize>,
+}
+
+impl SymmetricMatrix {
+    /// Replace every distance by the shortest path between its vertices (all-pairs shortest
+    /// paths, parallel Floyd-Warshall), making the matrix satisfy the triangle inequality.
+    pub fn metric_closure(&mut self) -> MetricClosure {
+        let size = self.size;
+        let mut next: Vec<_> = (0..size * size).map(|index| index % size).collect();
+
+        for k in 0..size {
+            // Row k doesn't change during the iteration k since d(k, k) = 0
+            let row_k = self.row(k).to_vec();
+
+            self.data.par_chunks_mut(size)
+                .zip(next.par_chunks_mut(size))
+                .for_each(|(row_i, next_i)| {
+                    let d_ik = row_i[k] as i64;
+                    let next_ik = next_i[k];
+
+                    for j in 0..size {
+                        let d = d_ik + row_k[j] as i64;
+                        if d < row_i[j] as i64 {
+                            row_i[j] = d as i32;
+                            next_i[j] = next_ik;
+                        }
+                    }
+                });
+        }
+
+        MetricClosure { size, next }
+    }
+}
+
+impl MetricClosure {
+    /// Shortest path from `i` to `j`, both inclusive.
+    pub fn shortest_path(&self, i: usize, j: usize) -> Vec<usize> {
+        let mut res = vec![i];
+        let mut current = i;
+        while current != j {
+            current = self.next[current * self.size + j];
+            res.push(current);
+        }
+        res
+    }
+
+    /// Expand a tour over the closure into the closed walk over the original graph, listing the
+    /// intermediate vertices of every edge. The walk returns to `path[0]` after its last vertex.
+    ///
+    /// Vertices may appear more than once since shortest paths can share intermediate vertices.
+    pub fn expand(&self, path: &Path) -> Vec<usize> {
+        let mut res = Vec::with_capacity(path.len());
+        for (a, b) in path.edges() {
+            let shortest = self.shortest_path(a, b);
+            res.extend_from_slice(&shortest[..shortest.len() - 1]);
+        }
+        res
+    }
+}
+
+impl GuidedLocalSearch {
+    /// Create the solver over the metric closure of `distances`, for instances violating the
+    /// triangle inequality. Use the returned closure to expand the solution tours.
+    pub fn with_metric_closure(mut distances: SymmetricMatrix) -> (Self, MetricClosure) {
+        let closure = distances.metric_closure();
+        (Self::new(distances), closure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::{SymmetricMatrix, TriangleCheck};
+    use crate::types::path::Path;
+
+    fn create_matrix() -> SymmetricMatrix {
+        crate::matrix! { 0-1: 1, 0-2: 2, 0-3: 5, 1-2: 7, 1-3: 4, 2-3: 1 }
+    }
+
+    #[test]
+    fn distances() {
+        let mut actual = create_matrix();
+        actual.metric_closure();
+
+        assert_eq!(actual.validate(TriangleCheck::Exhaustive), Ok(()));
+        assert_eq!(actual.row(0), &[0, 1, 2, 3]);
+        assert_eq!(actual.row(1), &[1, 0, 3, 4]);
+        assert_eq!(actual.row(2), &[2, 3, 0, 1]);
+        assert_eq!(actual.row(3), &[3, 4, 1, 0]);
+    }
+
+    #[test]
+    fn shortest_path() {
+        let closure = create_matrix().metric_closure();
+
+        assert_eq!(closure.shortest_path(1, 2), vec![1, 0, 2]);
+        assert_eq!(closure.shortest_path(0, 3), vec![0, 2, 3]);
+        assert_eq!(closure.shortest_path(1, 3), vec![1, 3]);
+        assert_eq!(closure.shortest_path(2, 2), vec![2]);
+    }
+
+    #[test]
+    fn expand() {
+        let closure = create_matrix().metric_closure();
+        let path = Path::new(vec![0, 1, 2, 3]);
+
+        assert_eq!(closure.expand(&path), vec![0, 1, 0, 2, 3, 2]);
+    }
+
+    #[test]
+    fn solve() {
+        let (gls, closure) = GuidedLocalSearch::with_metric_closure(create_matrix());
+        let route = gls.sequential();
+        let walk = closure.expand(&route.path);
+
+        let original = create_matrix();
+        let walk_cost: i32 = walk.iter().copied()
+            .zip(walk.iter().copied().cycle().skip(1))
+            .map(|e| original[e])
+            .sum();
+        assert_eq!(walk_cost, route.cost);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::types::checksum::Fnv64;
+use std::fmt;
+use std::hash::Hasher;
+
+/// Parameters of a GLS run.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct GlsConfig {
+    pub seed: u64,
+    /// Number of penalization steps.
+    pub steps: usize,
+    pub acceptance: Acceptance,
+    pub utility: UtilityComparison,
+    pub tie_break: TieBreak,
+    pub arithmetic: Arithmetic,
+    /// Weight of the penalties in the augmented cost, in percent of the mean edge length of the
+    /// first local optimum: the `α` of GLS, 30 by default.
+    pub alpha_percent: u32,
+    /// Smallest decrease of the augmented cost for the local search to apply a move, at least 1.
+    pub min_gain: i32,
+    /// Vertex the returned tours start at, e.g. the depot of a driver itinerary.
+    pub depot: Option<usize>,
+    /// Direction of the returned tours, applied by the entry points knowing the coordinates
+    /// (`solve_points`, `solve_file`, `Instance::improve`) when the instance has some.
+    pub orientation: Orientation,
+    pub overflow: Overflow,
+    pub step_unit: StepUnit,
+}
+
+/// What to do with the local optimum reached after each penalization step.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum Acceptance {
+    /// Always continue from it, as in plain GLS.
+    #[default]
+    Always,
+    /// Roll back to the best tour found so far before penalizing again when the local optimum
+    /// costs more than `percent` percent above it.
+    Threshold { percent: u32 },
+}
+
+impl Acceptance {
+    /// Whether to continue from a local optimum of cost `cost`, the best tour costing `best`.
+    pub fn accepts(self, cost: i32, best: i32) -> bool {
+        match self {
+            Acceptance::Always => true,
+            Acceptance::Threshold { percent } => {
+                cost as i64 * 100 <= best as i64 * (100 + percent as i64)
+            },
+        }
+    }
+}
+
+/// How the penalization compares the utilities `distance / (1 + penalty)` of the tour edges.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum UtilityComparison {
+    /// Truncate the utilities to integers: every edge with the same integer part as the
+    /// maximum is penalized.
+    #[default]
+    Truncated,
+    /// Compare the fractions exactly, by cross-multiplication on `i64`, so only the true maxima
+    /// are penalized as in the canonical GLS.
+    Exact,
+}
+
+/// Which edges the penalization penalizes when several tie for the maximum utility.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum TieBreak {
+    /// All of them, as in plain GLS.
+    #[default]
+    All,
+    /// One of them drawn with the search RNG, so runs stay reproducible.
+    Random,
+    /// The longest one, the first in tour order among equally long ones.
+    Longest,
+}
+
+/// How the penalty factor and the truncated utilities are computed.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum Arithmetic {
+    /// `(alpha_percent as f64 / 100.0 * (cost as f64 / size as f64)) as i32` and
+    /// `(distance as f64 / (1.0 + penalty as f64)) as i32`.
+    #[default]
+    Float,
+    /// `alpha_percent * cost / (100 * size)` and `distance / (1 + penalty)` in integer
+    /// division, the same values up to rounding. No floating point is left in the search, so a
+    /// seed gives the same tour on every platform and compiler.
+    Integer,
+}
+
+/// What the search does when its `i32` arithmetic would overflow: the tour costs on instances
+/// with very large distances, or the augmented costs once `penalty_factor * penalty` grows over
+/// a long run.
+///
+/// `Unchecked` by default in every build, so a configuration gives the same tours in debug and
+/// release builds. The checked modes are opt-in; they give the same tours as `Unchecked` until
+/// an overflow.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum Overflow {
+    /// No check, as plain integer arithmetic: a panic in debug builds, wrapping in release ones.
+    #[default]
+    Unchecked,
+    /// Clamp the tour costs to the `i32` range and stop penalizing the edges whose augmented
+    /// cost would no longer fit the local search.
+    Saturate,
+    /// Stop the search, `GuidedLocalSearch::try_solve` then returning an `OverflowError`.
+    Error,
+}
+
+/// What one of the `steps` of a run is.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum StepUnit {
+    /// A penalization followed by a local search, as in plain GLS. The work of a step grows
+    /// with the instance, a local search evaluating more moves on more vertices.
+    #[default]
+    Penalization,
+    /// This many evaluations of 2-opt moves by the local searches after the first one, so a
+    /// number of steps is about the same work on any instance. The search stops at the end of
+    /// the penalization step spending the budget, `RunInfo::iterations` counting these steps
+    /// and `RunInfo::evaluations` the evaluations.
+    Evaluations(u64),
+}
+
+/// Direction in which a tour is driven, the y axis pointing up as in TSPLIB.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub enum Orientation {
+    /// Whichever the search ended with.
+    #[default]
+    Any,
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Invalid configuration, reported by `GlsConfig::validate` and `Budget::validate`.
+#[derive(Eq, PartialEq, Debug)]
+pub enum ConfigError {
+    /// No penalization step: the run would only be a local search.
+    ZeroSteps,
+    /// A time budget of zero stops the search before its first step.
+    ZeroTimeLimit,
+    /// `min_gain` below 1 would apply moves that don't improve the tour, possibly forever.
+    MinGain,
+    /// `alpha_percent` of 0 gives the penalties no weight: the steps would never leave the
+    /// first local optimum.
+    ZeroAlpha,
+    /// `StepUnit::Evaluations(0)` makes every step empty.
+    ZeroEvaluations,
+    /// The depot is not a vertex of the instance, see `GlsConfig::validate_for`.
+    DepotOutOfRange { depot: usize, size: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ZeroSteps => write!(f, "no penalization step"),
+            ConfigError::ZeroTimeLimit => write!(f, "time budget of zero"),
+            ConfigError::MinGain => write!(f, "minimum gain below 1"),
+            ConfigError::ZeroAlpha => write!(f, "penalty weight of zero"),
+            ConfigError::ZeroEvaluations => write!(f, "steps of zero evaluations"),
+            ConfigError::DepotOutOfRange { depot, size } => write!(f, "depot {} out of the {} vertices", depot, size),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Presets of increasing budgets, tuned on the bundled instances with their default seed. The
+/// gaps are above the optima of burma14 and of the original pcb3038 (137694), the times on one
+/// core in release builds.
+///
+/// They roll back to the best tour when a local optimum is too far above it (see
+/// `Acceptance::Threshold`), which always helped the small instances. Penalizing a single edge
+/// per step (`TieBreak`) or skipping the moves gaining less than 2 to 20 (`min_gain`) never
+/// did better, costing up to 1.3% on pcb3038, so these keep their defaults.
+impl GlsConfig {
+    /// 20 steps with a light penalty weight (`alpha_percent` 20), rolling back beyond 2% above
+    /// the best tour: 7.3% on pcb3038 in 0.8 s, the optimum of burma14.
+    pub fn fast() -> Self {
+        Self::default()
+            .with_steps(20)
+            .with_alpha_percent(20)
+            .with_acceptance(Acceptance::Threshold { percent: 2 })
+    }
+
+    /// 100 steps with the default penalty weight (`alpha_percent` 30), rolling back beyond 5%
+    /// above the best tour: 6.5% on pcb3038 in 5 s, 0.4% on burma14.
+    pub fn balanced() -> Self {
+        Self::default()
+            .with_steps(100)
+            .with_alpha_percent(30)
+            .with_acceptance(Acceptance::Threshold { percent: 5 })
+    }
+
+    /// 500 steps with a light penalty weight (`alpha_percent` 20), rolling back beyond 5% above
+    /// the best tour: 5.0% on pcb3038 in 19 s, the optimum of every small bundled instance.
+    pub fn quality() -> Self {
+        Self::default()
+            .with_steps(500)
+            .with_alpha_percent(20)
+            .with_acceptance(Acceptance::Threshold { percent: 5 })
+    }
+}
+
+impl GlsConfig {
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    pub fn with_acceptance(mut self, acceptance: Acceptance) -> Self {
+        self.acceptance = acceptance;
+        self
+    }
+
+    pub fn with_utility(mut self, utility: UtilityComparison) -> Self {
+        self.utility = utility;
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn with_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    pub fn with_alpha_percent(mut self, alpha_percent: u32) -> Self {
+        self.alpha_percent = alpha_percent;
+        self
+    }
+
+    pub fn with_min_gain(mut self, min_gain: i32) -> Self {
+        self.min_gain = min_gain;
+        self
+    }
+
+    pub fn with_depot(mut self, depot: usize) -> Self {
+        self.depot = Some(depot);
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn with_step_unit(mut self, step_unit: StepUnit) -> Self {
+        self.step_unit = step_unit;
+        self
+    }
+
+    /// Stable hash of the options, the seed excepted, so runs of the same configuration with
+    /// several seeds share it (see `RunInfo`).
+    ///
+    /// `depot` and `orientation` are left out too: they only rotate or reverse the final tour.
+    /// So is `overflow`, only changing the runs the unchecked arithmetic gets wrong.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv64::new();
+        hash.write_usize(self.steps);
+        match self.acceptance {
+            Acceptance::Always => hash.write_u8(0),
+            Acceptance::Threshold { percent } => {
+                hash.write_u8(1);
+                hash.write_u32(percent);
+            },
+        }
+        hash.write_u8(self.utility as u8);
+        hash.write_u8(self.tie_break as u8);
+        hash.write_u8(self.arithmetic as u8);
+        hash.write_u32(self.alpha_percent);
+        hash.write_i32(self.min_gain);
+        // Nothing for the default unit, keeping the fingerprints from before it existed
+        if let StepUnit::Evaluations(evaluations) = self.step_unit {
+            hash.write_u8(1);
+            hash.write_u64(evaluations);
+        }
+        hash.finish()
+    }
+
+    /// Check the configuration at the end of a builder chain, e.g.
+    /// `GlsConfig::default().with_steps(steps).validate()?`.
+    pub fn validate(self) -> Result<Self, ConfigError> {
+        if self.steps == 0 {
+            return Err(ConfigError::ZeroSteps);
+        }
+        if self.min_gain < 1 {
+            return Err(ConfigError::MinGain);
+        }
+        if self.alpha_percent == 0 {
+            return Err(ConfigError::ZeroAlpha);
+        }
+        if self.step_unit == StepUnit::Evaluations(0) {
+            return Err(ConfigError::ZeroEvaluations);
+        }
+        Ok(self)
+    }
+
+    /// Same as `validate`, also checking the options against an instance of `size` vertices,
+    /// e.g. the depot of a configuration read by `from_env`.
+    pub fn validate_for(self, size: usize) -> Result<Self, ConfigError> {
+        let config = self.validate()?;
+        match config.depot {
+            Some(depot) if depot >= size => Err(ConfigError::DepotOutOfRange { depot, size }),
+            _ => Ok(config),
+        }
+    }
+}
+
+impl Default for GlsConfig {
+    fn default() -> Self {
+        Self {
+            seed: 666,
+            steps: 100,
+            acceptance: Acceptance::default(),
+            utility: UtilityComparison::default(),
+            tie_break: TieBreak::default(),
+            arithmetic: Arithmetic::default(),
+            alpha_percent: 30,
+            min_gain: 1,
+            depot: None,
+            orientation: Orientation::default(),
+            overflow: Overflow::default(),
+            step_unit: StepUnit::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::config::{Acceptance, Arithmetic, ConfigError, GlsConfig, Orientation, Overflow, StepUnit, TieBreak};
+
+    #[test]
+    fn fingerprint() {
+        let config = GlsConfig::balanced();
+        assert_eq!(config.fingerprint(), config.with_seed(1).fingerprint());
+        assert_ne!(config.fingerprint(), config.with_steps(10).fingerprint());
+        assert_ne!(config.fingerprint(), config.with_acceptance(Acceptance::Threshold { percent: 4 }).fingerprint());
+        assert_ne!(config.fingerprint(), config.with_tie_break(TieBreak::Longest).fingerprint());
+        assert_ne!(config.fingerprint(), config.with_arithmetic(Arithmetic::Integer).fingerprint());
+        assert_ne!(config.fingerprint(), config.with_alpha_percent(20).fingerprint());
+        assert_ne!(config.fingerprint(), config.with_step_unit(StepUnit::Evaluations(1000)).fingerprint());
+        let pinned = config.with_depot(3).with_orientation(Orientation::Clockwise).with_overflow(Overflow::Error);
+        assert_eq!(config.fingerprint(), pinned.fingerprint());
+    }
+
+    #[test]
+    fn validate() {
+        let config = GlsConfig::default().with_acceptance(Acceptance::Threshold { percent: 5 });
+        assert_eq!(config.validate(), Ok(config));
+        assert_eq!(config.with_steps(0).validate(), Err(ConfigError::ZeroSteps));
+        assert_eq!(config.with_min_gain(0).validate(), Err(ConfigError::MinGain));
+        assert_eq!(config.with_alpha_percent(0).validate(), Err(ConfigError::ZeroAlpha));
+        assert_eq!(config.with_step_unit(StepUnit::Evaluations(0)).validate(), Err(ConfigError::ZeroEvaluations));
+        assert_eq!(config.with_depot(9).validate_for(10), Ok(config.with_depot(9)));
+        assert_eq!(config.with_depot(10).validate_for(10), Err(ConfigError::DepotOutOfRange { depot: 10, size: 10 }));
+        assert_eq!(ConfigError::DepotOutOfRange { depot: 10, size: 10 }.to_string(), "depot 10 out of the 10 vertices");
+    }
+}
@@ -0,0 +1,423 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::point::Point;
+use crate::types::route::{insert_cheapest, Route};
+use rand_mt::Mt64;
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use std::cmp::Reverse;
+use std::iter;
+
+/// Initial tour built by `GuidedLocalSearch::construct`. The constructions needing more than
+/// the distances, `convex_hull_insertion` and `savings`, are called directly.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Construction {
+    /// `nearest_neighbor`, the start of `solve`.
+    NearestNeighbor,
+    /// `cheapest_insertion` from a vertex drawn with the seed.
+    CheapestInsertion,
+    /// `farthest_insertion` from a vertex drawn with the seed.
+    FarthestInsertion,
+    /// Vertices in an order drawn with the seed, a baseline for the other ones.
+    Random,
+}
+
+impl GuidedLocalSearch {
+    /// First phase of `solve`, building the tour to give to `improve` so it can be inspected or
+    /// changed in between. The seed is only used by the randomized constructions.
+    pub fn construct(&self, construction: Construction, seed: u64) -> Route {
+        if let Some(route) = self.trivial_route() {
+            return route;
+        }
+        match construction {
+            Construction::NearestNeighbor => self.nearest_neighbor(),
+            Construction::CheapestInsertion => self.cheapest_insertion(Some(seed)),
+            Construction::FarthestInsertion => self.farthest_insertion(Some(seed)),
+            Construction::Random => {
+                let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+                let mut path = Path::sequential(self.distances().size());
+                path.0.shuffle(&mut rng);
+                Route::new(self.cost(&path), path)
+            },
+        }
+    }
+
+    /// Tour grown from a first vertex and its nearest neighbor, inserting at each step the
+    /// vertex and position adding the least cost. An initial tour other than `nearest_neighbor`,
+    /// e.g. to give to `Instance::improve`.
+    ///
+    /// The first vertex is drawn with `seed`, vertex 0 without one. Ties go to the smallest
+    /// vertex.
+    pub fn cheapest_insertion(&self, seed: Option<u64>) -> Route {
+        if let Some(route) = self.trivial_route() {
+            return route;
+        }
+        let distances = self.distances();
+        let size = distances.size();
+        let first = self.start_vertex(seed);
+        let row = distances.row(first);
+        let second = (0..size).filter(|&v| v != first).min_by_key(|&v| (row[v], v)).unwrap();
+
+        self.cheapest_insertion_from(&[first, second])
+    }
+
+    /// Tour of Euclidean vertices at `points` starting from their convex hull, the vertices
+    /// inside it then being inserted as by `cheapest_insertion`. Often the best of the
+    /// constructions on geometric instances, e.g. drilling holes of a circuit board.
+    ///
+    /// Panics unless there is one point per vertex.
+    pub fn convex_hull_insertion(&self, points: &[Point]) -> Route {
+        assert_eq!(points.len(), self.distances().size());
+        let hull = convex_hull(points);
+        if hull.len() < 2 {
+            // All the points are the same
+            return self.cheapest_insertion(None);
+        }
+        self.cheapest_insertion_from(&hull)
+    }
+
+    /// Cheapest insertion of the vertices out of the closed tour `start`.
+    pub(crate) fn cheapest_insertion_from(&self, start: &[usize]) -> Route {
+        let distances = self.distances();
+        let size = distances.size();
+        let first = start[0];
+
+        // The tour as the successor of each vertex, to insert in O(1)
+        let mut next = vec![usize::MAX; size];
+        for (i, &v) in start.iter().enumerate() {
+            next[v] = start[(i + 1) % start.len()];
+        }
+
+        // Cheapest position of each vertex out of the tour, as `(cost, a)` to insert after `a`
+        let cost = |v: usize, a: usize, b: usize| distances[(a, v)] + distances[(v, b)] - distances[(a, b)];
+        let cheapest = |v: usize, next: &[usize]| {
+            let mut a = first;
+            let mut res = (cost(v, a, next[a]), a);
+            while next[a] != first {
+                a = next[a];
+                res = res.min((cost(v, a, next[a]), a));
+            }
+            res
+        };
+        let mut best: Vec<(i32, usize)> = (0..size)
+            .map(|v| if next[v] == usize::MAX { cheapest(v, &next) } else { (0, first) })
+            .collect();
+
+        for _ in start.len()..size {
+            let (_, vertex) = (0..size)
+                .filter(|&v| next[v] == usize::MAX)
+                .map(|v| (best[v].0, v))
+                .min()
+                .unwrap();
+            let a = best[vertex].1;
+            let b = next[a];
+            next[a] = vertex;
+            next[vertex] = b;
+
+            // Only the vertices whose position was the replaced edge need a full scan
+            for v in (0..size).filter(|&v| next[v] == usize::MAX) {
+                best[v] = if best[v].1 == a {
+                    cheapest(v, &next)
+                } else {
+                    best[v].min((cost(v, a, vertex), a)).min((cost(v, vertex, b), vertex))
+                };
+            }
+        }
+
+        let mut tour = Vec::with_capacity(size);
+        let mut v = first;
+        for _ in 0..size {
+            tour.push(v);
+            v = next[v];
+        }
+        let path = Path::new(tour);
+        Route::new(self.cost(&path), path)
+    }
+
+    /// Tour grown from a first vertex and the one farthest from it, inserting at each step the
+    /// vertex farthest from the tour where it adds the least cost. The tour spans the instance
+    /// early, often ending shorter than with `cheapest_insertion`.
+    ///
+    /// The first vertex is chosen as by `cheapest_insertion`.
+    pub fn farthest_insertion(&self, seed: Option<u64>) -> Route {
+        if let Some(route) = self.trivial_route() {
+            return route;
+        }
+        let distances = self.distances();
+        let size = distances.size();
+        let first = self.start_vertex(seed);
+        let row = distances.row(first);
+        let second = (0..size).filter(|&v| v != first).max_by_key(|&v| (row[v], Reverse(v))).unwrap();
+
+        let mut tour = vec![first, second];
+        let mut inserted = vec![false; size];
+        inserted[first] = true;
+        inserted[second] = true;
+        // Distance from each vertex to the closest vertex of the tour
+        let mut to_tour: Vec<i32> = row.iter().zip(distances.row(second)).map(|(&a, &b)| a.min(b)).collect();
+
+        for _ in 2..size {
+            let (_, Reverse(vertex)) = (0..size)
+                .filter(|&v| !inserted[v])
+                .map(|v| (to_tour[v], Reverse(v)))
+                .max()
+                .unwrap();
+            inserted[vertex] = true;
+            insert_cheapest(distances, &mut tour, iter::once(vertex));
+
+            for (distance, &to_vertex) in to_tour.iter_mut().zip(distances.row(vertex)) {
+                *distance = (*distance).min(to_vertex);
+            }
+        }
+
+        let path = Path::new(tour);
+        Route::new(self.cost(&path), path)
+    }
+
+    /// Routes from `depot` built by the savings algorithm of Clarke and Wright, each starting
+    /// at the depot, e.g. the initial tours of vehicles with a range of `max_length`.
+    ///
+    /// Starting from a route to each vertex and back, the route ends `a` and `b` are joined by
+    /// decreasing saving `d(depot, a) + d(depot, b) - d(a, b)`, while the saving is not
+    /// negative and the joined route is no longer than `max_length`. Without a limit, metric
+    /// instances end with a single route.
+    pub fn savings_routes(&self, depot: usize, max_length: Option<i32>) -> Vec<Route> {
+        let distances = self.distances();
+        let size = distances.size();
+        assert!(depot < size);
+        let saving = |a: usize, b: usize| distances[(depot, a)] + distances[(depot, b)] - distances[(a, b)];
+
+        // Routes without the depot, with their length, `None` once merged into another
+        let mut routes: Vec<Option<(Vec<usize>, i32)>> = (0..size)
+            .map(|v| Some((vec![v], 2 * distances[(depot, v)])).filter(|_| v != depot))
+            .collect();
+        let mut route_of: Vec<usize> = (0..size).collect();
+
+        let mut pairs: Vec<(i32, usize, usize)> = (0..size)
+            .filter(|&a| a != depot)
+            .flat_map(|a| (a + 1..size).filter(|&b| b != depot).map(move |b| (a, b)))
+            .map(|(a, b)| (saving(a, b), a, b))
+            .filter(|&(saving, _, _)| saving >= 0)
+            .collect();
+        pairs.sort_by_key(|&(saving, a, b)| (Reverse(saving), a, b));
+
+        for (saving, a, b) in pairs {
+            let (route_a, route_b) = (route_of[a], route_of[b]);
+            if route_a == route_b {
+                continue;
+            }
+            let (tour_a, length_a) = routes[route_a].as_ref().unwrap();
+            let (tour_b, length_b) = routes[route_b].as_ref().unwrap();
+            let is_end = |tour: &Vec<usize>, v: usize| tour[0] == v || tour[tour.len() - 1] == v;
+            let length = length_a + length_b - saving;
+            if !is_end(tour_a, a) || !is_end(tour_b, b) || max_length.is_some_and(|max| length > max) {
+                continue;
+            }
+
+            // Join as `... a` then `b ...`
+            let (mut tour_a, _) = routes[route_a].take().unwrap();
+            let (mut tour_b, _) = routes[route_b].take().unwrap();
+            if tour_a[0] == a {
+                tour_a.reverse();
+            }
+            if tour_b[0] != b {
+                tour_b.reverse();
+            }
+            for &v in &tour_b {
+                route_of[v] = route_a;
+            }
+            tour_a.extend(tour_b);
+            routes[route_a] = Some((tour_a, length));
+        }
+
+        routes.into_iter()
+            .flatten()
+            .map(|(tour, length)| Route::new(length, Path::new(iter::once(depot).chain(tour).collect())))
+            .collect()
+    }
+
+    /// Tour from `depot` joining the routes of `savings_routes` without a length limit.
+    pub fn savings(&self, depot: usize) -> Route {
+        let mut tour = vec![depot];
+        for route in self.savings_routes(depot, None) {
+            tour.extend_from_slice(&route.path.as_slice()[1..]);
+        }
+        let path = Path::new(tour);
+        Route::new(self.cost(&path), path)
+    }
+
+    /// The only tour of an instance of fewer than 2 vertices, which the constructions have no
+    /// edge to start from.
+    fn trivial_route(&self) -> Option<Route> {
+        let size = self.distances().size();
+        if size >= 2 {
+            return None;
+        }
+        Some(Route::new(0, Path((0..size).collect())))
+    }
+
+    /// Vertex drawn with `seed`, 0 without one.
+    fn start_vertex(&self, seed: Option<u64>) -> usize {
+        match seed {
+            Some(seed) => {
+                let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+                rng.gen_range(0..self.distances().size())
+            },
+            None => 0,
+        }
+    }
+}
+
+/// Indices of the corners of the convex hull of `points`, counterclockwise from the lowest
+/// leftmost one, without the points in the middle of its sides. Duplicate points appear once.
+pub(crate) fn convex_hull(points: &[Point]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by_key(|&i| (points[i].x(), points[i].y(), i));
+    order.dedup_by_key(|&mut i| (points[i].x(), points[i].y()));
+    if order.len() < 3 {
+        return order;
+    }
+
+    let cross = |o: usize, a: usize, b: usize| {
+        let (o, a, b) = (points[o], points[a], points[b]);
+        (a.x() - o.x()) as i64 * (b.y() - o.y()) as i64 - (a.y() - o.y()) as i64 * (b.x() - o.x()) as i64
+    };
+
+    // Andrew's monotone chain: the lower hull left to right, then the upper one back
+    let mut hull: Vec<usize> = Vec::with_capacity(order.len() + 1);
+    for &i in &order {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], i) <= 0 {
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    let lower = hull.len();
+    for &i in order.iter().rev().skip(1) {
+        while hull.len() > lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], i) <= 0 {
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    // Back to the first point
+    hull.pop();
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use crate::types::point::Point;
+    use crate::types::construction::{convex_hull, Construction};
+    use crate::types::route::Route;
+
+    fn create_gls() -> GuidedLocalSearch {
+        GuidedLocalSearch::from_instance(&crate::instance! { 0-1: 2, 0-2: 7, 0-3: 3, 1-2: 4, 1-3: 1, 2-3: 9 })
+    }
+
+    #[test]
+    fn cheapest_insertion() {
+        // From {0, 1}: 3 costs 2 between them, then 2 costs 9 between 1 and 0
+        let actual = create_gls().cheapest_insertion(None);
+        assert_eq!(actual, Route::new(15, Path::new(vec![0, 3, 1, 2])));
+    }
+
+    #[test]
+    fn tiny() {
+        for size in 0..2 {
+            let gls = GuidedLocalSearch::new(SymmetricMatrix::from_size(size));
+            let expected = Route::new(0, Path((0..size).collect()));
+            assert_eq!(gls.cheapest_insertion(Some(666)), expected);
+            assert_eq!(gls.farthest_insertion(None), expected);
+            for construction in [Construction::NearestNeighbor, Construction::CheapestInsertion, Construction::Random] {
+                assert_eq!(gls.construct(construction, 666), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn farthest_insertion() {
+        // From {0, 2}: 3 is 3 away from the tour and 1 only 2, then 1 goes between 3 and 2
+        let actual = create_gls().farthest_insertion(None);
+        assert_eq!(actual, Route::new(15, Path::new(vec![0, 3, 1, 2])));
+    }
+
+    #[test]
+    fn convex() {
+        // Points in convex position, where both find the polygon, the optimal tour
+        let points: Vec<_> = (0..12)
+            .map(|i| {
+                let angle = (i * 7 % 12) as f64 * std::f64::consts::PI / 6.0;
+                Point::new((1000.0 * angle.cos()) as i32, (1000.0 * angle.sin()) as i32)
+            })
+            .collect();
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+        let polygon = gls.cost(&Path::new((0..12).map(|k| k * 7 % 12).collect()));
+
+        for seed in [None, Some(1), Some(2)] {
+            let cheapest = gls.cheapest_insertion(seed);
+            assert!(cheapest.path.is_hamiltonian());
+            assert_eq!(cheapest.cost, polygon);
+
+            let farthest = gls.farthest_insertion(seed);
+            assert!(farthest.path.is_hamiltonian());
+            assert_eq!(farthest.cost, polygon);
+        }
+    }
+
+    #[test]
+    fn savings() {
+        // Two pairs of vertices on either side of the depot
+        let points: Vec<_> = [(0, 0), (10, 0), (11, 0), (-10, 0), (-11, 0)].iter()
+            .map(|&(x, y)| Point::new(x, y))
+            .collect();
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+
+        let limited = gls.savings_routes(0, Some(25));
+        assert_eq!(limited, vec![Route::new(22, Path::new(vec![0, 1, 2])), Route::new(22, Path::new(vec![0, 3, 4]))]);
+        for route in &limited {
+            assert_eq!(route.cost, gls.cost(&route.path));
+        }
+
+        assert_eq!(gls.savings_routes(0, None).len(), 1);
+        let tour = gls.savings(0);
+        assert_eq!(tour.path[0], 0);
+        assert!(tour.path.is_hamiltonian());
+        assert_eq!(tour.cost, 44);
+    }
+
+    #[test]
+    fn hull() {
+        // A square with a point inside, one in the middle of a side and a duplicate corner
+        let points: Vec<_> = [(0, 0), (10, 10), (5, 5), (10, 0), (0, 10), (5, 0), (10, 10)].iter()
+            .map(|&(x, y)| Point::new(x, y))
+            .collect();
+        assert_eq!(convex_hull(&points), vec![0, 3, 1, 4]);
+
+        let line = vec![Point::new(0, 0), Point::new(2, 2), Point::new(1, 1)];
+        assert_eq!(convex_hull(&line), vec![0, 1]);
+        assert_eq!(convex_hull(&[Point::new(3, 3), Point::new(3, 3)]), vec![0]);
+    }
+
+    #[test]
+    fn convex_hull_insertion() {
+        let points = random_points(200);
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+
+        let actual = gls.convex_hull_insertion(&points);
+        assert!(actual.path.is_hamiltonian());
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+        assert!(actual.cost < gls.nearest_neighbor().cost);
+
+        // The hull corners stay in order
+        let hull = convex_hull(&points);
+        let corners: Vec<_> = actual.path.as_slice().iter().copied().filter(|v| hull.contains(v)).collect();
+        assert_eq!(corners, hull);
+
+        let same = vec![Point::new(1, 1); 3];
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&same));
+        assert_eq!(gls.convex_hull_insertion(&same).cost, 0);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+
+/// Best route found so far, shared between concurrent workers.
+///
+/// The cost can be read without locking, so workers only take the lock when they actually
+/// have something better to publish or to adopt.
+pub struct SharedIncumbent {
+    cost: AtomicI32,
+    path: Mutex<Option<Path>>,
+}
+
+impl SharedIncumbent {
+    pub fn new() -> Self {
+        Self { cost: AtomicI32::new(i32::MAX), path: Mutex::new(None) }
+    }
+
+    /// Cost of the incumbent, `i32::MAX` if nothing was published yet.
+    pub fn cost(&self) -> i32 {
+        self.cost.load(Ordering::Acquire)
+    }
+
+    /// Publish `route` if it is better than the incumbent, returns whether it was accepted.
+    pub fn publish(&self, route: &Route) -> bool {
+        if route.cost >= self.cost() {
+            return false;
+        }
+
+        let mut path = self.path.lock().unwrap();
+
+        // Someone else may have published while we were waiting for the lock
+        if route.cost >= self.cost() {
+            return false;
+        }
+
+        *path = Some(route.path.clone());
+        self.cost.store(route.cost, Ordering::Release);
+        true
+    }
+
+    /// Replace `route` with the incumbent if the latter is better, returns whether it was.
+    pub fn adopt(&self, route: &mut Route) -> bool {
+        if self.cost() >= route.cost {
+            return false;
+        }
+
+        let path = self.path.lock().unwrap();
+        let cost = self.cost();
+        match path.as_ref() {
+            Some(path) if cost < route.cost => {
+                route.path.0.clone_from(&path.0);
+                route.cost = cost;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn snapshot(&self) -> Option<Route> {
+        let path = self.path.lock().unwrap();
+        path.as_ref().map(|path| Route::new(self.cost(), path.clone()))
+    }
+}
+
+impl Default for SharedIncumbent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GuidedLocalSearch {
+    /// Run one GLS worker per seed concurrently, cooperating through a shared incumbent.
+    ///
+    /// Every `sync_interval` steps each worker publishes its current route if it improves the
+    /// incumbent and adopts the incumbent if it is better than its own route, keeping its own
+    /// penalties and neighborhood order. The result is the best route published by any worker.
+    ///
+    /// Unlike `solve`, the result depends on thread scheduling and is not reproducible.
+    pub fn solve_cooperative(&self, seeds: &[u64], steps: usize, sync_interval: usize) -> Route {
+        assert!(!seeds.is_empty());
+        assert!(sync_interval > 0);
+
+        let incumbent = SharedIncumbent::new();
+
+        thread::scope(|scope| {
+            for &seed in seeds {
+                let incumbent = &incumbent;
+                scope.spawn(move || self.cooperative_worker(seed, steps, sync_interval, incumbent));
+            }
+        });
+
+        incumbent.snapshot().unwrap()
+    }
+
+    fn cooperative_worker(&self, seed: u64, steps: usize, sync_interval: usize, incumbent: &SharedIncumbent) {
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = &self.neighborhood(&mut rng);
+
+        let mut route = self.nearest_neighbor();
+        let mut penalties = Penalties::from_size(route.path.len());
+        self.local_search(&mut route.path, neighborhood, 0, &mut penalties);
+        route.cost = self.cost(&route.path);
+        incumbent.publish(&route);
+
+        let penalty_factor = self.penalty_factor(route.cost);
+
+        for step in 1..=steps {
+            self.penalize(&route.path, &mut penalties, &mut rng);
+            self.local_search(&mut route.path, neighborhood, penalty_factor, &mut penalties);
+
+            if step % sync_interval == 0 {
+                route.cost = self.cost(&route.path);
+                if !incumbent.publish(&route) {
+                    incumbent.adopt(&mut route);
+                }
+            }
+        }
+
+        self.local_search(&mut route.path, neighborhood, 0, &mut penalties);
+        route.cost = self.cost(&route.path);
+        incumbent.publish(&route);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::cooperative::SharedIncumbent;
+    use crate::types::route::Route;
+    use crate::types::path::Path;
+
+    #[test]
+    fn publish_and_adopt() {
+        let incumbent = SharedIncumbent::new();
+        assert_eq!(incumbent.snapshot(), None);
+
+        assert!(incumbent.publish(&Route::new(10, Path::new(vec![0, 1, 2]))));
+        assert!(!incumbent.publish(&Route::new(10, Path::new(vec![0, 2, 1]))));
+        assert!(incumbent.publish(&Route::new(8, Path::new(vec![2, 1, 0]))));
+        assert_eq!(incumbent.cost(), 8);
+
+        let mut worse = Route::new(9, Path::new(vec![1, 0, 2]));
+        assert!(incumbent.adopt(&mut worse));
+        assert_eq!(worse, Route::new(8, Path::new(vec![2, 1, 0])));
+
+        let mut better = Route::new(7, Path::new(vec![1, 0, 2]));
+        assert!(!incumbent.adopt(&mut better));
+        assert_eq!(better, Route::new(7, Path::new(vec![1, 0, 2])));
+    }
+
+    #[cfg(test)]
+    mod solve_cooperative {
+        use crate::testing::random_gls;
+
+        #[test]
+        fn valid_route() {
+            let gls = random_gls(150);
+            let actual = gls.solve_cooperative(&[1, 2, 3, 4], 20, 5);
+
+            assert!(actual.path.is_hamiltonian());
+            assert_eq!(actual.cost, gls.cost(&actual.path));
+            assert!(actual.cost <= gls.nearest_neighbor().cost);
+        }
+
+        #[test]
+        fn single_worker() {
+            let gls = random_gls(150);
+            let actual = gls.solve_cooperative(&[666], 20, 1000);
+            let expected = gls.solve(666, 20);
+
+            // Without synchronization a lone worker follows the same trajectory as a regular
+            // run, but also keeps the first local minimum if it was better.
+            assert!(actual.cost <= expected.cost);
+        }
+    }
+}
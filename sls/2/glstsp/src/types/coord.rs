@@ -0,0 +1,129 @@
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::point::Point;
+use std::str::FromStr;
+
+/// Point with `D` floating point coordinates.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Coord<const D: usize>(pub [f64; D]);
+
+pub type Coord2 = Coord<2>;
+pub type Coord3 = Coord<3>;
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum ParseCoordError {
+    Dimension { expected: usize, found: usize },
+    /// The component at this index is not a number.
+    Component(usize),
+}
+
+impl<const D: usize> Coord<D> {
+    pub fn new(components: [f64; D]) -> Self {
+        Self(components)
+    }
+
+    fn deltas(self, other: Self) -> impl Iterator<Item=f64> {
+        (0..D).map(move |i| (self.0[i] - other.0[i]).abs())
+    }
+
+    pub fn squared_euclidean(self, other: Self) -> f64 {
+        self.deltas(other).map(|d| d * d).sum()
+    }
+
+    pub fn euclidean(self, other: Self) -> f64 {
+        self.squared_euclidean(other).sqrt()
+    }
+
+    pub fn manhattan(self, other: Self) -> f64 {
+        self.deltas(other).sum()
+    }
+
+    pub fn chebyshev(self, other: Self) -> f64 {
+        self.deltas(other).fold(0.0, f64::max)
+    }
+}
+
+impl From<Point> for Coord2 {
+    fn from(point: Point) -> Self {
+        Self([point.x() as f64, point.y() as f64])
+    }
+}
+
+/// Parse whitespace separated components, e.g. `1.5 -2e3 0`.
+impl<const D: usize> FromStr for Coord<D> {
+    type Err = ParseCoordError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = str.split_whitespace().collect();
+        if fields.len() != D {
+            return Err(ParseCoordError::Dimension { expected: D, found: fields.len() });
+        }
+
+        let mut res = [0.0; D];
+        for (i, field) in fields.into_iter().enumerate() {
+            res[i] = field.parse().map_err(|_| ParseCoordError::Component(i))?;
+        }
+        Ok(Self(res))
+    }
+}
+
+impl SymmetricMatrix {
+    /// Euclidean distances multiplied by `scale` and rounded, a scale of 100 keeping two
+    /// decimals of precision.
+    pub fn from_coords<const D: usize>(points: &[Coord<D>], scale: f64) -> Self {
+        let size = points.len();
+        assert!(size > 0);
+
+        let mut res = Self::from_size(size);
+
+        for (i, point) in points.iter().copied().enumerate() {
+            for (j, neighbor) in points.iter().copied().enumerate().skip(i + 1) {
+                let dist = (point.euclidean(neighbor) * scale).round();
+                res.set(i, j, dist as i32);
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::coord::{Coord, Coord2, Coord3, ParseCoordError};
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::point::Point;
+
+    #[test]
+    fn distances() {
+        let a = Coord3::new([1.0, 2.0, 3.0]);
+        let b = Coord3::new([4.0, -2.0, 3.0]);
+
+        assert_eq!(a.squared_euclidean(b), 25.0);
+        assert_eq!(a.euclidean(b), 5.0);
+        assert_eq!(a.manhattan(b), 7.0);
+        assert_eq!(a.chebyshev(b), 4.0);
+        assert_eq!(a.euclidean(a), 0.0);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("1.5 -2e3".parse(), Ok(Coord2::new([1.5, -2000.0])));
+        assert_eq!("1 2 3".parse(), Ok(Coord3::new([1.0, 2.0, 3.0])));
+        assert_eq!("1 2".parse::<Coord3>(), Err(ParseCoordError::Dimension { expected: 3, found: 2 }));
+        assert_eq!("1 x".parse::<Coord2>(), Err(ParseCoordError::Component(1)));
+    }
+
+    #[test]
+    fn from_point() {
+        assert_eq!(Coord::from(Point::new(3, -4)), Coord2::new([3.0, -4.0]));
+    }
+
+    #[test]
+    fn from_coords() {
+        let points = [Coord2::new([0.0, 0.0]), Coord2::new([0.3, 0.4]), Coord2::new([1.0, 1.0])];
+        let actual = SymmetricMatrix::from_coords(&points, 100.0);
+
+        assert_eq!(actual.row(0), &[0, 50, 141]);
+        assert_eq!(actual.row(1), &[50, 0, 92]);
+        assert_eq!(actual.row(2), &[141, 92, 0]);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::types::edge::Edge;
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::memory::SearchMemory;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+impl GuidedLocalSearch {
+    /// Child of two tours of the instance, e.g. for a genetic algorithm, guided by a
+    /// `memory` of the instance whose values count against the edges, such as the penalties of
+    /// a GLS run on it (see `SolverWorkspace::penalties`).
+    ///
+    /// The child is built from vertex 0 by following an edge of both parents whenever one
+    /// leads to an unvisited vertex, else the edge of either parent, else any edge. Among
+    /// several, the one with the least `distance * (1 + value)` is taken: GLS penalizes the
+    /// long edges of its local optima again and again, so these are the ones to give up first.
+    /// The child is then improved by a local search without penalties.
+    pub fn penalized_crossover(&self, first: &Path, second: &Path, memory: &impl SearchMemory, seed: u64) -> Route {
+        let mut path = self.recombine(first, second, memory);
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = self.neighborhood(&mut rng);
+        self.local_search(&mut path, &neighborhood, 0, &mut Penalties::from_size(first.len()));
+        Route::new(self.cost(&path), path)
+    }
+
+    fn recombine(&self, first: &Path, second: &Path, memory: &impl SearchMemory) -> Path {
+        let size = first.len();
+        assert_eq!(second.len(), size);
+        assert_eq!(memory.size(), size);
+        let distances = self.distances();
+        let weight = |a: usize, b: usize| distances[(a, b)] as i64 * (1 + memory.value(Edge::new(a, b)) as i64);
+
+        // The two neighbors of each vertex in each parent
+        let mut neighbors = vec![[0; 4]; size];
+        for (parent, path) in [first, second].iter().enumerate() {
+            for (a, b) in path.edges() {
+                neighbors[a][2 * parent + 1] = b;
+                neighbors[b][2 * parent] = a;
+            }
+        }
+
+        let mut visited = vec![false; size];
+        let mut res = Vec::with_capacity(size);
+        let mut current = 0;
+        visited[0] = true;
+        res.push(0);
+
+        while res.len() < size {
+            let parents = &neighbors[current];
+            let unvisited = parents.iter().copied().filter(|&v| !visited[v]);
+            let common = unvisited.clone()
+                .filter(|&v| parents[..2].contains(&v) && parents[2..].contains(&v))
+                .min_by_key(|&v| weight(current, v));
+            let next = common
+                .or_else(|| unvisited.min_by_key(|&v| weight(current, v)))
+                .unwrap_or_else(|| (0..size).filter(|&v| !visited[v]).min_by_key(|&v| weight(current, v)).unwrap());
+
+            visited[next] = true;
+            res.push(next);
+            current = next;
+        }
+
+        let res = Path(res);
+        debug_assert!(res.is_hamiltonian());
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::gls::SolverWorkspace;
+    use crate::types::observer::Progress;
+    use crate::types::penalties::Penalties;
+
+    #[test]
+    fn recombine() {
+        let gls = random_gls(100);
+        let parent = gls.solve(666, 10);
+
+        // The only edges to follow are those of the parent
+        let child = gls.recombine(&parent.path, &parent.path, &Penalties::from_size(100));
+        assert_eq!(child.common_edges(&parent.path), 100);
+    }
+
+    #[test]
+    fn penalized_crossover() {
+        let gls = random_gls(100);
+        let mut workspace = SolverWorkspace::new();
+        let first = gls.search_in(1, 20, None, &mut |_: &Progress| {}, &mut workspace);
+        let penalties = workspace.penalties().clone();
+        let second = gls.solve(2, 20);
+
+        let child = gls.penalized_crossover(&first.path, &second.path, &penalties, 666);
+        assert!(child.path.is_hamiltonian());
+        assert_eq!(child.cost, gls.cost(&child.path));
+        assert!(child.cost <= gls.cost(&gls.recombine(&first.path, &second.path, &penalties)));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::types::config::GlsConfig;
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use crate::types::point::Point;
+use crate::types::route::Route;
+use rayon::prelude::*;
+
+/// Split the points in clusters of at most `cluster_size` points, returned as indexes into
+/// `points`, on a grid with about the same number of points per cell.
+///
+/// The points are cut in vertical strips, then each strip in cells. The cells are ordered in a
+/// serpentine (up the first strip, down the second...) so consecutive clusters are neighbors.
+pub fn grid_clusters(points: &[Point], cluster_size: usize) -> Vec<Vec<usize>> {
+    assert!(cluster_size > 0);
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let clusters = points.len().div_ceil(cluster_size);
+    let strips = (clusters as f64).sqrt().ceil() as usize;
+
+    let mut indexes: Vec<_> = (0..points.len()).collect();
+    indexes.sort_by_key(|&i| (points[i].x(), points[i].y()));
+
+    let mut res = Vec::with_capacity(clusters);
+    for (s, strip) in indexes.chunks_mut(points.len().div_ceil(strips)).enumerate() {
+        if s % 2 == 0 {
+            strip.sort_by_key(|&i| (points[i].y(), points[i].x()));
+        } else {
+            strip.sort_by_key(|&i| (std::cmp::Reverse(points[i].y()), points[i].x()));
+        }
+
+        let cells = strip.len().div_ceil(cluster_size);
+        res.extend(strip.chunks(strip.len().div_ceil(cells)).map(|cell| cell.to_vec()));
+    }
+
+    res
+}
+
+/// Cluster-first heuristic for instances too large for a distance matrix (50k+ points).
+///
+/// Solves every `grid_clusters` cluster with GLS in parallel, each one with its own small
+/// distance matrix, then opens the cluster tours and chains them in cluster order. Finally a
+/// 2-opt local search repairs the tour in a window of `cluster_size` positions around each
+/// junction between two clusters. Distances are `Point::dist`, as in `from_euclidean_coords`.
+pub fn solve_decomposed(points: &[Point], cluster_size: usize, config: &GlsConfig) -> Route {
+    assert!(points.len() > 1);
+
+    let tours: Vec<Vec<usize>> = grid_clusters(points, cluster_size)
+        .par_iter()
+        .map(|cluster| solve_cluster(points, cluster, config))
+        .collect();
+
+    let mut tour = Vec::with_capacity(points.len());
+    let mut junctions = Vec::with_capacity(tours.len());
+    for cluster in &tours {
+        junctions.push(tour.len());
+        append_opened(points, &mut tour, cluster);
+    }
+
+    let half = (cluster_size / 2).max(2);
+    for &junction in junctions.iter().skip(1) {
+        let from = junction.saturating_sub(half);
+        let to = (junction + half).min(tour.len());
+        two_opt_fixed_ends(points, &mut tour[from..to]);
+    }
+
+    let path = Path::new(tour);
+    let cost = path.edges().map(|(a, b)| points[a].dist(points[b])).sum();
+    Route::new(cost, path)
+}
+
+/// GLS tour of the points of `cluster`, as indexes into `points`.
+fn solve_cluster(points: &[Point], cluster: &[usize], config: &GlsConfig) -> Vec<usize> {
+    // Every order is optimal
+    if cluster.len() <= 3 {
+        return cluster.to_vec();
+    }
+
+    let coords: Vec<_> = cluster.iter().map(|&i| points[i]).collect();
+    let route = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&coords))
+        .with_config(config)
+        .solve(config.seed, config.steps);
+    route.path.0.iter().map(|&v| cluster[v]).collect()
+}
+
+/// Append the closed tour `cluster` to `tour`, removing the edge that makes the cheapest
+/// connection from the end of `tour`.
+fn append_opened(points: &[Point], tour: &mut Vec<usize>, cluster: &[usize]) {
+    let size = cluster.len();
+    let dist = |a: usize, b: usize| points[a].dist(points[b]);
+
+    // Candidates: start at `k` then go forward or backward, removing the edge to the other
+    // neighbor of `k`; without a previous vertex, remove the longest edge
+    let mut best = (i32::MAX, 0, true);
+    for k in 0..size {
+        let next = cluster[(k + 1) % size];
+        let prev = cluster[(k + size - 1) % size];
+        for (forward, removed) in [(true, prev), (false, next)] {
+            let cost = match tour.last() {
+                Some(&last) => dist(last, cluster[k]) - dist(cluster[k], removed),
+                None => -dist(cluster[k], removed),
+            };
+            if cost < best.0 {
+                best = (cost, k, forward);
+            }
+        }
+    }
+
+    let (_, k, forward) = best;
+    if forward {
+        tour.extend((0..size).map(|i| cluster[(k + i) % size]));
+    } else {
+        tour.extend((0..size).map(|i| cluster[(k + size - i) % size]));
+    }
+}
+
+/// 2-opt on a path whose first and last vertices stay in place, until no move improves it.
+fn two_opt_fixed_ends(points: &[Point], path: &mut [usize]) {
+    let dist = |a: usize, b: usize| points[a].dist(points[b]);
+    let len = path.len();
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..len.saturating_sub(3) {
+            for j in i + 2..len - 1 {
+                let (a, b, c, d) = (path[i], path[i + 1], path[j], path[j + 1]);
+                if dist(a, c) + dist(b, d) < dist(a, b) + dist(c, d) {
+                    path[i + 1..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::config::GlsConfig;
+    use crate::types::decomposition::{grid_clusters, solve_decomposed, two_opt_fixed_ends};
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::point::Point;
+
+    #[test]
+    fn clusters() {
+        let points = random_points(1000);
+        let clusters = grid_clusters(&points, 90);
+
+        assert!(clusters.iter().all(|cluster| !cluster.is_empty() && cluster.len() <= 90));
+        let mut all: Vec<_> = clusters.concat();
+        all.sort_unstable();
+        assert_eq!(all, (0..1000).collect::<Vec<_>>());
+
+        assert_eq!(grid_clusters(&points[..5], 90).len(), 1);
+        assert!(grid_clusters(&[], 90).is_empty());
+    }
+
+    #[test]
+    fn fixed_ends() {
+        let points: Vec<_> = (0..6).map(|i| Point::new(i * 10, 0)).collect();
+        let mut path = vec![0, 3, 2, 1, 4, 5];
+
+        two_opt_fixed_ends(&points, &mut path);
+        assert_eq!(path, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn solve() {
+        let points = random_points(2000);
+        let config = GlsConfig::default().with_steps(20);
+        let actual = solve_decomposed(&points, 200, &config);
+
+        assert!(actual.path.is_hamiltonian());
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+
+        // Not far from solving the whole instance
+        let full = gls.solve(config.seed, config.steps);
+        assert!((actual.cost as f64) < 1.1 * full.cost as f64, "{} vs {}", actual.cost, full.cost);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::types::config::GlsConfig;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::instance::Instance;
+use crate::types::observer::Progress;
+use crate::types::point::Point;
+use crate::types::route::Route;
+use std::cell::Cell;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+
+/// GLS steps run between two checks of the event queue.
+const ROUND_STEPS: usize = 10;
+/// Rounds without improvement after which the solver waits for the next event.
+const IDLE_ROUNDS: usize = 3;
+
+/// Change of the problem solved by a `DynamicSolver`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Event {
+    /// New stop, numbered after the existing ones.
+    AddStop(Point),
+    /// Remove a stop, the following ones being renumbered one lower (see
+    /// `Instance::remove_vertex`).
+    CancelStop(usize),
+    /// New position of a stop, keeping its number.
+    MoveStop { vertex: usize, point: Point },
+    /// New cost of the edge between two stops.
+    CostChange { a: usize, b: usize, cost: i32 },
+}
+
+/// Event `DynamicSolver::send` can't apply, the solver being left as it was.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EventError {
+    /// No stop of this number once the previous events are applied.
+    NoStop(usize),
+    /// Removing or moving a stop of an instance of 2 stops or fewer.
+    TooFewStops,
+    /// Cost of an edge from a stop to itself.
+    Loop(usize),
+    /// The instance was not built from points, so stops can't be placed.
+    NoCoordinates,
+    /// The background thread stopped after a panic.
+    Stopped,
+}
+
+/// Read access to the best route of a `DynamicSolver`, a tour of its current instance.
+#[derive(Clone)]
+pub struct RouteHandle(Arc<RwLock<Route>>);
+
+impl RouteHandle {
+    pub fn get(&self) -> Route {
+        self.0.read().unwrap().clone()
+    }
+
+    pub fn cost(&self) -> i32 {
+        self.0.read().unwrap().cost
+    }
+
+    fn set(&self, route: &Route) {
+        self.0.write().unwrap().clone_from(route);
+    }
+}
+
+/// Keeps improving a route in a background thread while the instance changes.
+///
+/// Events are applied in order as soon as the current round of GLS steps ends: the route is
+/// repaired locally, published, then improved again. When the route stops improving the thread
+/// sleeps until the next event.
+pub struct DynamicSolver {
+    events: Sender<Event>,
+    /// Size of the instance once the sent events are applied, to check the next ones.
+    size: Cell<usize>,
+    has_points: bool,
+    route: RouteHandle,
+    worker: JoinHandle<(Instance, Route)>,
+}
+
+impl DynamicSolver {
+    /// Solve `instance` with `config` then start the background thread.
+    pub fn start(instance: Instance, config: GlsConfig) -> Self {
+        let route = GuidedLocalSearch::from_instance(&instance).solve(config.seed, config.steps);
+        let (size, has_points) = (Cell::new(instance.size()), instance.points().is_some());
+        let handle = RouteHandle(Arc::new(RwLock::new(route.clone())));
+
+        let (events, receiver) = mpsc::channel();
+        let worker = {
+            let handle = handle.clone();
+            thread::spawn(move || run(instance, route, config, receiver, handle))
+        };
+
+        Self { events, size, has_points, route: handle, worker }
+    }
+
+    /// Queue `event`, checked against the instance as left by the events sent before it.
+    pub fn send(&self, event: Event) -> Result<(), EventError> {
+        let size = self.size.get();
+        let stop = |vertex: usize| if vertex < size { Ok(()) } else { Err(EventError::NoStop(vertex)) };
+        let new_size = match event {
+            Event::AddStop(_) if !self.has_points => return Err(EventError::NoCoordinates),
+            Event::AddStop(_) => size + 1,
+            Event::CancelStop(vertex) => {
+                stop(vertex)?;
+                if size <= 2 {
+                    return Err(EventError::TooFewStops);
+                }
+                size - 1
+            },
+            Event::MoveStop { .. } if !self.has_points => return Err(EventError::NoCoordinates),
+            Event::MoveStop { vertex, .. } => {
+                stop(vertex)?;
+                if size <= 2 {
+                    return Err(EventError::TooFewStops);
+                }
+                size
+            },
+            Event::CostChange { a, b, .. } => {
+                stop(a)?;
+                stop(b)?;
+                if a == b {
+                    return Err(EventError::Loop(a));
+                }
+                size
+            },
+        };
+
+        // The worker runs until `stop`, unless it panicked
+        self.events.send(event).map_err(|_| EventError::Stopped)?;
+        self.size.set(new_size);
+        Ok(())
+    }
+
+    pub fn route(&self) -> RouteHandle {
+        self.route.clone()
+    }
+
+    /// Apply the pending events, stop the thread and return the final instance and best route.
+    pub fn stop(self) -> (Instance, Route) {
+        drop(self.events);
+        self.worker.join().unwrap()
+    }
+}
+
+fn run(
+    mut instance: Instance,
+    mut best: Route,
+    config: GlsConfig,
+    events: Receiver<Event>,
+    handle: RouteHandle) -> (Instance, Route)
+{
+    let mut workspace = SolverWorkspace::new();
+    let mut seed = config.seed;
+    let mut idle = 0;
+
+    loop {
+        let event = if idle < IDLE_ROUNDS {
+            match events.try_recv() {
+                Ok(event) => Some(event),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        } else {
+            match events.recv() {
+                Ok(event) => Some(event),
+                Err(_) => break,
+            }
+        };
+
+        match event {
+            Some(event) => {
+                apply(&mut instance, &mut best, event);
+                handle.set(&best);
+                idle = 0;
+            },
+            None => {
+                seed = seed.wrapping_add(1);
+                let gls = GuidedLocalSearch::from_instance(&instance).with_config(&config);
+                let observer = &mut |_: &Progress| {};
+                let route = gls.search_from(best.clone(), seed, ROUND_STEPS, None, observer, &mut workspace);
+
+                if route.cost < best.cost {
+                    best = route;
+                    handle.set(&best);
+                    idle = 0;
+                } else {
+                    idle += 1;
+                }
+            },
+        }
+    }
+
+    (instance, best)
+}
+
+fn apply(instance: &mut Instance, route: &mut Route, event: Event) {
+    match event {
+        Event::AddStop(point) => {
+            instance.insert_point(point, route);
+        },
+        Event::CancelStop(vertex) => instance.remove_vertex(vertex, route),
+        Event::MoveStop { vertex, point } => instance.move_point(vertex, point, route),
+        Event::CostChange { a, b, cost } => {
+            instance.set_distance(a, b, cost);
+            route.cost = GuidedLocalSearch::from_instance(instance).cost(&route.path);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scattered_points;
+    use crate::types::config::GlsConfig;
+    use crate::types::dynamic::{DynamicSolver, Event, EventError};
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::instance::Instance;
+    use crate::types::point::Point;
+
+    fn create_instance() -> Instance {
+        Instance::from_points(scattered_points(40))
+    }
+
+    #[test]
+    fn events() {
+        let solver = DynamicSolver::start(create_instance(), GlsConfig::default().with_steps(10));
+        let handle = solver.route();
+        assert_eq!(handle.get().path.len(), 40);
+
+        solver.send(Event::AddStop(Point::new(50, 50))).unwrap();
+        solver.send(Event::AddStop(Point::new(10, 90))).unwrap();
+        solver.send(Event::CancelStop(3)).unwrap();
+        solver.send(Event::MoveStop { vertex: 5, point: Point::new(0, 0) }).unwrap();
+        solver.send(Event::CostChange { a: 0, b: 1, cost: 10_000 }).unwrap();
+
+        // Rejected without stopping the worker
+        assert_eq!(solver.send(Event::CancelStop(41)), Err(EventError::NoStop(41)));
+        assert_eq!(solver.send(Event::CostChange { a: 2, b: 2, cost: 1 }), Err(EventError::Loop(2)));
+
+        let (instance, route) = solver.stop();
+        assert_eq!(instance.size(), 41);
+        assert_eq!(instance.distances()[(1, 0)], 10_000);
+        assert_eq!(instance.points().unwrap()[5], Point::new(0, 0));
+
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.path.len(), 41);
+        assert_eq!(route.cost, GuidedLocalSearch::from_instance(&instance).cost(&route.path));
+        assert_eq!(handle.get(), route);
+    }
+
+    #[test]
+    fn invalid_events() {
+        let matrix = GuidedLocalSearch::from_instance(&create_instance()).distances().clone();
+        let solver = DynamicSolver::start(Instance::from_matrix(matrix), GlsConfig::default().with_steps(10));
+        assert_eq!(solver.send(Event::AddStop(Point::new(1, 1))), Err(EventError::NoCoordinates));
+
+        let small = Instance::from_points(vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)]);
+        let solver = DynamicSolver::start(small, GlsConfig::default().with_steps(10));
+        assert_eq!(solver.send(Event::CancelStop(0)), Ok(()));
+        assert_eq!(solver.send(Event::CancelStop(0)), Err(EventError::TooFewStops));
+        assert_eq!(solver.send(Event::CancelStop(2)), Err(EventError::NoStop(2)));
+        assert_eq!(solver.stop().0.size(), 2);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::types::ids::VertexId;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use std::collections::HashSet;
+use std::ops::Index;
+
+/// Undirected edge, stored as `(min, max)` so both directions compare and hash the same.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Edge(usize, usize);
+
+impl Edge {
+    pub fn new(a: usize, b: usize) -> Self {
+        if a <= b { Self(a, b) } else { Self(b, a) }
+    }
+
+    /// The two vertices, the smallest first.
+    pub fn endpoints(self) -> (usize, usize) {
+        (self.0, self.1)
+    }
+
+    pub fn contains(self, vertex: usize) -> bool {
+        self.0 == vertex || self.1 == vertex
+    }
+
+    /// The endpoint other than `vertex`, `None` if `vertex` is not an endpoint.
+    pub fn other(self, vertex: usize) -> Option<usize> {
+        match vertex {
+            _ if vertex == self.0 => Some(self.1),
+            _ if vertex == self.1 => Some(self.0),
+            _ => None,
+        }
+    }
+}
+
+impl From<(usize, usize)> for Edge {
+    fn from((a, b): (usize, usize)) -> Self {
+        Self::new(a, b)
+    }
+}
+
+impl From<(VertexId, VertexId)> for Edge {
+    fn from((a, b): (VertexId, VertexId)) -> Self {
+        Self::new(a.index(), b.index())
+    }
+}
+
+impl Index<Edge> for SymmetricMatrix {
+    type Output = i32;
+
+    fn index(&self, edge: Edge) -> &Self::Output {
+        &self[edge.endpoints()]
+    }
+}
+
+impl Path {
+    /// Same as `edges`, ignoring the direction of travel.
+    pub fn undirected_edges(&self) -> impl Iterator<Item=Edge> + '_ {
+        self.edges().map(Edge::from)
+    }
+
+    pub fn edge_set(&self) -> HashSet<Edge> {
+        self.undirected_edges().collect()
+    }
+
+    /// Number of edges of `self` also in `other`, whatever their direction or starting vertex.
+    pub fn common_edges(&self, other: &Path) -> usize {
+        let successors = other.to_successor_vec();
+        self.edges().filter(|&(a, b)| successors[a] == b || successors[b] == a).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::edge::Edge;
+    use crate::types::ids::VertexId;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+
+    #[test]
+    fn normalized() {
+        assert_eq!(Edge::new(3, 1), Edge::new(1, 3));
+        assert_eq!(Edge::new(3, 1).endpoints(), (1, 3));
+        assert_eq!(Edge::from((VertexId(4), VertexId(2))), Edge::from((2, 4)));
+        assert!(Edge::new(0, 5) < Edge::new(1, 2));
+    }
+
+    #[test]
+    fn endpoints() {
+        let edge = Edge::new(4, 2);
+        assert!(edge.contains(4));
+        assert!(!edge.contains(3));
+        assert_eq!(edge.other(4), Some(2));
+        assert_eq!(edge.other(2), Some(4));
+        assert_eq!(edge.other(3), None);
+    }
+
+    #[test]
+    fn matrix_index() {
+        let mut matrix = SymmetricMatrix::from_size(3);
+        matrix.set(0, 2, 5);
+        assert_eq!(matrix[Edge::new(2, 0)], 5);
+    }
+
+    #[test]
+    fn common_edges() {
+        let path = Path::new(vec![0, 1, 2, 3, 4]);
+
+        // Same tour reversed and rotated
+        assert_eq!(path.common_edges(&Path::new(vec![2, 1, 0, 4, 3])), 5);
+        // 2-opt move: replaces (0, 1) and (2, 3) by (0, 2) and (1, 3)
+        assert_eq!(path.common_edges(&Path::new(vec![0, 2, 1, 3, 4])), 3);
+        assert_eq!(path.edge_set().len(), 5);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::types::anytime::SolveReport;
+use crate::types::instance::Instance;
+use crate::types::penalties::Penalties;
+use crate::types::point::Point;
+use crate::types::route::{Leg, Route};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io;
+use std::io::Write;
+
+const SVG_WIDTH: f64 = 1000.0;
+const SVG_MARGIN: f64 = 10.0;
+
+/// Penalized edges `(i, j, penalty)` with `i < j`.
+fn penalized_edges(penalties: &Penalties) -> impl Iterator<Item=(usize, usize, i32)> + '_ {
+    penalties.nonzero().map(|(edge, p)| {
+        let (i, j) = edge.endpoints();
+        (i, j, p)
+    })
+}
+
+/// Write the penalized edges as CSV with an `i,j,penalty` header.
+pub fn write_penalties_csv(penalties: &Penalties, writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, "i,j,penalty")?;
+    for (i, j, p) in penalized_edges(penalties) {
+        writeln!(writer, "{},{},{}", i, j, p)?;
+    }
+    Ok(())
+}
+
+/// Write the per-step statistics of `report` as CSV, one line per step starting with the first
+/// local search, with a `step,cost,penalized,max_penalty,penalty_mass,moves` header.
+pub fn write_report_csv(report: &SolveReport, writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, "step,cost,penalized,max_penalty,penalty_mass,moves")?;
+    for step in 0..report.history.len() {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            step,
+            report.history[step],
+            report.penalized[step],
+            report.max_penalty[step],
+            report.penalty_mass[step],
+            report.moves[step])?;
+    }
+    Ok(())
+}
+
+impl Route {
+    /// Write the tour as CSV for a spreadsheet, with an `order,vertex,label,distance,cumulative`
+    /// header: one line per stop in driving order with the distance from the previous stop and
+    /// from the start, and a last line back at the first stop holding the total. The labels are
+    /// empty when the vertices of `instance` have none, and an empty tour is only the header.
+    pub fn write_csv(&self, instance: &Instance, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "order,vertex,label,distance,cumulative")?;
+        let label = |vertex: usize| instance.label(vertex).map_or(Cow::Borrowed(""), csv_field);
+        let first = match self.path.as_slice().first() {
+            Some(&first) => first,
+            None => return Ok(()),
+        };
+        writeln!(writer, "0,{},{},0,0", first, label(first))?;
+        for (order, leg) in self.legs(instance).iter().enumerate() {
+            writeln!(writer, "{},{},{},{},{}", order + 1, leg.to, label(leg.to), leg.distance, leg.cumulative)?;
+        }
+        Ok(())
+    }
+}
+
+/// `text` as a CSV field, quoted when it contains a separator, a quote or a line break.
+fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// `text` as a quoted JSON string.
+fn json_string(text: &str) -> String {
+    let mut res = String::with_capacity(text.len() + 2);
+    res.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            c if c.is_control() => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// Write an SVG heatmap drawing the penalized edges over the vertex coordinates.
+///
+/// Edges go from yellow to red and get thicker as their penalty approaches the maximum one.
+pub fn write_penalties_svg(penalties: &Penalties, points: &[Point], writer: &mut impl Write) -> io::Result<()> {
+    assert_eq!(penalties.size(), points.len());
+
+    let min_x = points.iter().map(|p| p.x()).min().unwrap_or(0) as f64;
+    let max_x = points.iter().map(|p| p.x()).max().unwrap_or(0) as f64;
+    let min_y = points.iter().map(|p| p.y()).min().unwrap_or(0) as f64;
+    let max_y = points.iter().map(|p| p.y()).max().unwrap_or(0) as f64;
+
+    let scale = SVG_WIDTH / (max_x - min_x).max(max_y - min_y).max(1.0);
+    let width = (max_x - min_x) * scale + 2.0 * SVG_MARGIN;
+    let height = (max_y - min_y) * scale + 2.0 * SVG_MARGIN;
+
+    // SVG has the y axis pointing down
+    let project = |p: Point| (
+        (p.x() as f64 - min_x) * scale + SVG_MARGIN,
+        (max_y - p.y() as f64) * scale + SVG_MARGIN,
+    );
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.1} {:.1}">"#,
+        width, height, width, height,
+    )?;
+    writeln!(writer, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+
+    for &p in points {
+        let (x, y) = project(p);
+        writeln!(writer, r#"<circle cx="{:.1}" cy="{:.1}" r="1" fill="gray"/>"#, x, y)?;
+    }
+
+    let max_penalty = penalized_edges(penalties).map(|(_, _, p)| p).max().unwrap_or(1);
+
+    for (i, j, p) in penalized_edges(penalties) {
+        let intensity = p as f64 / max_penalty as f64;
+        let green = (255.0 * (1.0 - intensity)) as u8;
+        let (x1, y1) = project(points[i]);
+        let (x2, y2) = project(points[j]);
+
+        writeln!(
+            writer,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="rgb(255,{},0)" stroke-width="{:.2}"/>"#,
+            x1, y1, x2, y2, green, 0.5 + 2.5 * intensity,
+        )?;
+    }
+
+    writeln!(writer, "</svg>")
+}
+
+/// Write the legs as a JSON array of `{"from", "to", "distance", "cumulative"}` objects, with
+/// `"from_coords"` and `"to_coords"` `[x, y]` arrays and `"from_label"` and `"to_label"`
+/// strings when known.
+pub fn write_legs_json(legs: &[Leg], writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, "[")?;
+    for (i, leg) in legs.iter().enumerate() {
+        write!(
+            writer,
+            r#"  {{"from": {}, "to": {}, "distance": {}, "cumulative": {}"#,
+            leg.from, leg.to, leg.distance, leg.cumulative,
+        )?;
+        if let Some((from, to)) = leg.coords {
+            write!(
+                writer,
+                r#", "from_coords": [{}, {}], "to_coords": [{}, {}]"#,
+                from.x(), from.y(), to.x(), to.y(),
+            )?;
+        }
+        if let Some((from, to)) = &leg.labels {
+            write!(writer, r#", "from_label": {}, "to_label": {}"#, json_string(from), json_string(to))?;
+        }
+        writeln!(writer, "}}{}", if i + 1 < legs.len() { "," } else { "" })?;
+    }
+    writeln!(writer, "]")
+}
+
+/// Write the legs as a GeoJSON `FeatureCollection`, one `LineString` feature per leg with the
+/// other fields, labels included, as properties. Legs without coordinates get a `null` geometry.
+///
+/// Coordinates are written as they are, GeoJSON expecting `[longitude, latitude]`.
+pub fn write_legs_geojson(legs: &[Leg], writer: &mut impl Write) -> io::Result<()> {
+    writeln!(writer, r#"{{"type": "FeatureCollection", "features": ["#)?;
+    for (i, leg) in legs.iter().enumerate() {
+        let geometry = match leg.coords {
+            Some((from, to)) => format!(
+                r#"{{"type": "LineString", "coordinates": [[{}, {}], [{}, {}]]}}"#,
+                from.x(), from.y(), to.x(), to.y(),
+            ),
+            None => "null".to_string(),
+        };
+        let labels = match &leg.labels {
+            Some((from, to)) => format!(r#", "from_label": {}, "to_label": {}"#, json_string(from), json_string(to)),
+            None => String::new(),
+        };
+        writeln!(
+            writer,
+            r#"  {{"type": "Feature", "geometry": {}, "properties": {{"from": {}, "to": {}, "distance": {}, "cumulative": {}{}}}}}{}"#,
+            geometry, leg.from, leg.to, leg.distance, leg.cumulative, labels,
+            if i + 1 < legs.len() { "," } else { "" },
+        )?;
+    }
+    writeln!(writer, "]}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::anytime::Budget;
+    use crate::types::export::{write_legs_geojson, write_legs_json, write_penalties_csv, write_penalties_svg, write_report_csv};
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::path::Path;
+    use crate::types::penalties::Penalties;
+    use crate::types::point::Point;
+    use crate::types::route::{Leg, Route};
+
+    fn create_penalties() -> Penalties {
+        let mut penalties = Penalties::from_size(4);
+        penalties.set((0, 1), 2);
+        penalties.set((1, 3), 4);
+        penalties
+    }
+
+    #[test]
+    fn csv() {
+        let mut actual = Vec::new();
+        write_penalties_csv(&create_penalties(), &mut actual).unwrap();
+
+        let expected = "i,j,penalty\n0,1,2\n1,3,4\n";
+        assert_eq!(String::from_utf8(actual).unwrap(), expected);
+    }
+
+    #[test]
+    fn report_csv() {
+        let gls = GuidedLocalSearch::from_fn(10, |i, j| ((i * 7 + j * 3) % 11 + 1) as i32);
+        let report = gls.solve_anytime(666, Budget::Steps(5));
+
+        let mut actual = Vec::new();
+        write_report_csv(&report, &mut actual).unwrap();
+        let actual = String::from_utf8(actual).unwrap();
+
+        let lines: Vec<_> = actual.lines().collect();
+        assert_eq!(lines[0], "step,cost,penalized,max_penalty,penalty_mass,moves");
+        assert_eq!(lines.len(), 7);
+        assert!(lines[1].starts_with(&format!("0,{},0,0,0,", report.history[0])));
+        assert!(lines[6].starts_with(&format!("5,{},", report.history[5])));
+    }
+
+    #[test]
+    fn svg() {
+        let points = vec![Point::new(0, 0), Point::new(10, 0), Point::new(10, 10), Point::new(0, 10)];
+
+        let mut actual = Vec::new();
+        write_penalties_svg(&create_penalties(), &points, &mut actual).unwrap();
+        let actual = String::from_utf8(actual).unwrap();
+
+        assert!(actual.starts_with("<svg"));
+        assert!(actual.trim_end().ends_with("</svg>"));
+        assert_eq!(actual.matches("<circle").count(), 4);
+        assert_eq!(actual.matches("<line").count(), 2);
+
+        // The most penalized edge (1, 3) is fully red and goes from (10, 0) to (0, 10)
+        assert!(actual.contains(
+            r#"<line x1="1010.0" y1="1010.0" x2="10.0" y2="10.0" stroke="rgb(255,0,0)" stroke-width="3.00"/>"#
+        ));
+    }
+
+    fn create_legs() -> Vec<Leg> {
+        let (a, b) = (Point::new(0, 0), Point::new(3, 4));
+        vec![
+            Leg { from: 0, to: 1, distance: 5, cumulative: 5, coords: Some((a, b)), labels: None },
+            Leg {
+                from: 1,
+                to: 0,
+                distance: 5,
+                cumulative: 10,
+                coords: None,
+                labels: Some(("Customer \"B\"".to_string(), "Depot".to_string())),
+            },
+        ]
+    }
+
+    #[test]
+    fn legs_json() {
+        let mut actual = Vec::new();
+        write_legs_json(&create_legs(), &mut actual).unwrap();
+
+        let expected = r#"[
+  {"from": 0, "to": 1, "distance": 5, "cumulative": 5, "from_coords": [0, 0], "to_coords": [3, 4]},
+  {"from": 1, "to": 0, "distance": 5, "cumulative": 10, "from_label": "Customer \"B\"", "to_label": "Depot"}
+]
+"#;
+        assert_eq!(String::from_utf8(actual).unwrap(), expected);
+    }
+
+    #[test]
+    fn legs_geojson() {
+        let mut actual = Vec::new();
+        write_legs_geojson(&create_legs(), &mut actual).unwrap();
+
+        let expected = r#"{"type": "FeatureCollection", "features": [
+  {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0], [3, 4]]}, "properties": {"from": 0, "to": 1, "distance": 5, "cumulative": 5}},
+  {"type": "Feature", "geometry": null, "properties": {"from": 1, "to": 0, "distance": 5, "cumulative": 10, "from_label": "Customer \"B\"", "to_label": "Depot"}}
+]}
+"#;
+        assert_eq!(String::from_utf8(actual).unwrap(), expected);
+    }
+
+    #[test]
+    fn route_csv() {
+        let instance = crate::instance! { 0-1: 2, 0-2: 7, 1-2: 4 };
+        let route = Route::new(13, Path::new(vec![0, 2, 1]));
+
+        let mut actual = Vec::new();
+        route.write_csv(&instance, &mut actual).unwrap();
+        let expected = "order,vertex,label,distance,cumulative\n0,0,,0,0\n1,2,,7,7\n2,1,,4,11\n3,0,,2,13\n";
+        assert_eq!(String::from_utf8(actual).unwrap(), expected);
+
+        let labels = vec!["Depot".to_string(), "Smith, J.".to_string(), "Customer #2".to_string()];
+        let mut actual = Vec::new();
+        route.write_csv(&instance.with_labels(labels), &mut actual).unwrap();
+        let expected = "order,vertex,label,distance,cumulative\n\
+            0,0,Depot,0,0\n1,2,Customer #2,7,7\n2,1,\"Smith, J.\",4,11\n3,0,Depot,2,13\n";
+        assert_eq!(String::from_utf8(actual).unwrap(), expected);
+
+        let mut actual = Vec::new();
+        Route::new(0, Path(vec![])).write_csv(&crate::instance! { 0-1: 2 }, &mut actual).unwrap();
+        assert_eq!(String::from_utf8(actual).unwrap(), "order,vertex,label,distance,cumulative\n");
+    }
+}
@@ -0,0 +1,128 @@
+use crate::types::matrix::SymmetricMatrix;
+
+/// Mean earth radius in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Earth radius in kilometers used by TSPLIB.
+const TSPLIB_RADIUS: f64 = 6378.388;
+
+/// Approximation of pi used by TSPLIB, needed to reproduce the published distances.
+#[allow(clippy::approx_constant)]
+const TSPLIB_PI: f64 = 3.141592;
+
+/// Geographical position.
+///
+/// With `GeoDistance::Haversine` the coordinates are decimal degrees, with
+/// `GeoDistance::Tsplib` they follow the TSPLIB `DDD.MM` format (degrees and minutes).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GeoDistance {
+    /// Great-circle distance in meters, rounded.
+    Haversine,
+    /// TSPLIB `GEO` distance in kilometers.
+    Tsplib,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Great-circle distance in meters.
+    pub fn haversine(self, other: Self) -> f64 {
+        let lat_a = self.lat.to_radians();
+        let lat_b = other.lat.to_radians();
+        let d_lat = lat_b - lat_a;
+        let d_lon = (other.lon - self.lon).to_radians();
+
+        let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * h.sqrt().asin()
+    }
+
+    /// Distance as defined by TSPLIB for `EDGE_WEIGHT_TYPE: GEO`.
+    pub fn tsplib_geo(self, other: Self) -> i32 {
+        let (lat_a, lon_a) = self.tsplib_radians();
+        let (lat_b, lon_b) = other.tsplib_radians();
+
+        let q1 = (lon_a - lon_b).cos();
+        let q2 = (lat_a - lat_b).cos();
+        let q3 = (lat_a + lat_b).cos();
+        let angle = (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos();
+        (TSPLIB_RADIUS * angle + 1.0) as i32
+    }
+
+    fn tsplib_radians(self) -> (f64, f64) {
+        let radians = |x: f64| {
+            let degrees = x.trunc();
+            let minutes = x - degrees;
+            TSPLIB_PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+        };
+        (radians(self.lat), radians(self.lon))
+    }
+
+    pub fn dist(self, other: Self, distance: GeoDistance) -> i32 {
+        match distance {
+            GeoDistance::Haversine => self.haversine(other).round() as i32,
+            GeoDistance::Tsplib => self.tsplib_geo(other),
+        }
+    }
+}
+
+impl SymmetricMatrix {
+    pub fn from_geo_coords(points: &[GeoPoint], distance: GeoDistance) -> Self {
+        let size = points.len();
+        assert!(size > 0);
+
+        let mut res = Self::from_size(size);
+
+        for (i, point) in points.iter().copied().enumerate() {
+            for (j, neighbor) in points.iter().copied().enumerate().skip(i + 1) {
+                res.set(i, j, point.dist(neighbor, distance));
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::geo::{GeoDistance, GeoPoint};
+    use crate::types::matrix::SymmetricMatrix;
+
+    #[test]
+    fn haversine() {
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        let london = GeoPoint::new(51.5074, -0.1278);
+
+        assert_eq!(paris.dist(london, GeoDistance::Haversine), 343_557);
+        assert_eq!(london.dist(paris, GeoDistance::Haversine), 343_557);
+        assert_eq!(paris.dist(paris, GeoDistance::Haversine), 0);
+    }
+
+    #[test]
+    fn tsplib_geo() {
+        // First vertices of burma14
+        let a = GeoPoint::new(16.47, 96.10);
+        let b = GeoPoint::new(16.47, 94.44);
+        let c = GeoPoint::new(20.09, 92.54);
+
+        assert_eq!(a.tsplib_geo(b), 153);
+        assert_eq!(a.tsplib_geo(c), 510);
+    }
+
+    #[test]
+    fn from_geo_coords() {
+        let points = [GeoPoint::new(16.47, 96.10), GeoPoint::new(16.47, 94.44), GeoPoint::new(20.09, 92.54)];
+        let actual = SymmetricMatrix::from_geo_coords(&points, GeoDistance::Tsplib);
+
+        assert_eq!(actual.row(0), &[0, 153, 510]);
+        assert_eq!(actual.row(1)[0], 153);
+        assert_eq!(actual.row(2)[0], 510);
+    }
+}
@@ -1,24 +1,335 @@
+use crate::types::config::{Acceptance, Arithmetic, GlsConfig, Overflow, StepUnit, TieBreak, UtilityComparison};
+use crate::types::edge::Edge;
+use crate::types::ids::{TourPos, VertexId};
+use crate::types::instance::Instance;
 use crate::types::matrix::SymmetricMatrix;
-use crate::types::route::Route;
+use crate::types::replay::{Event, EventLog};
+use crate::types::route::{Route, RunInfo};
 use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::observer::{Observer, Progress};
 use rand_mt::Mt64;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use rand::seq::SliceRandom;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
 
-#[derive(Eq, PartialEq)]
+/// Tour edges scanned by each parallel task of `penalize`, smaller tours being scanned by one.
+const PENALIZE_MIN_LEN: usize = 4096;
+
+/// Vertices scanned by each parallel task of the nearest neighbor construction, smaller
+/// instances being scanned by one.
+const NEAREST_NEIGHBOR_MIN_LEN: usize = 4096;
+
+/// Cost of a tour and its augmented cost, the objective of the penalized local search.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AugmentedCost {
+    pub cost: i32,
+    /// `cost + lambda * penalties` of the tour edges.
+    pub augmented: i32,
+}
+
+/// The search stopped at `step` because its arithmetic would overflow, with `Overflow::Error`.
+///
+/// Step 0 is the first local search: the distances or the tour costs are too large for `i32`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct OverflowError {
+    pub step: usize,
+}
+
+/// Cloning is cheap, the distances being shared.
+#[derive(Clone, Eq, PartialEq)]
 pub struct GuidedLocalSearch {
-    distances: SymmetricMatrix,
+    distances: Arc<SymmetricMatrix>,
+    /// Largest absolute distance, bounding the penalties under the checked `Overflow` modes.
+    longest: u32,
+    acceptance: Acceptance,
+    utility: UtilityComparison,
+    tie_break: TieBreak,
+    arithmetic: Arithmetic,
+    alpha_percent: u32,
+    min_gain: i32,
+    depot: Option<usize>,
+    overflow: Overflow,
+    step_unit: StepUnit,
+}
+
+/// Buffers of a search (penalty matrix, neighborhood, construction buffers), kept between
+/// solves to avoid paying their allocation each time.
+///
+/// A workspace can be used with solvers of any size, the buffers grow as needed.
+pub struct SolverWorkspace {
+    penalties: Penalties,
+    neighborhood: Path,
+    visited: Vec<bool>,
+    log: Option<EventLog>,
+    overflow: Option<OverflowError>,
+}
+
+impl SolverWorkspace {
+    pub fn new() -> Self {
+        Self {
+            penalties: Penalties::from_size(0),
+            neighborhood: Path(Vec::new()),
+            visited: Vec::new(),
+            log: None,
+            overflow: None,
+        }
+    }
+
+    /// Same workspace, recording the events of each solve (see `EventLog`).
+    pub fn with_event_log(mut self) -> Self {
+        self.log = Some(EventLog::default());
+        self
+    }
+
+    /// Penalties accumulated during the last solve.
+    pub fn penalties(&self) -> &Penalties {
+        &self.penalties
+    }
+
+    /// Events of the last solve, when recorded.
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.log.as_ref()
+    }
+
+    /// Overflow that stopped the last solve, with `Overflow::Error`.
+    pub fn overflow(&self) -> Option<OverflowError> {
+        self.overflow
+    }
+}
+
+/// Utility `distance / weight` of a feature, compared exactly by cross-multiplication.
+#[derive(Copy, Clone, Debug)]
+struct Utility {
+    distance: i64,
+    /// `1 + penalty`, always positive.
+    weight: i64,
+}
+
+impl Ord for Utility {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.distance * other.weight).cmp(&(other.distance * self.weight))
+    }
+}
+
+impl PartialOrd for Utility {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Utility {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Utility {}
+
+/// Edges of `path` with the maximum utility, in tour order, scanned in parallel by tasks of at
+/// least `min_len` edges.
+///
+/// Each task folds its contiguous range into its maximum and the edges reaching it, and `reduce`
+/// merges adjacent ranges in order, keeping the larger maximum or concatenating on equality. The
+/// result is thus the same for any split of the tour, so the penalization doesn't depend on the
+/// number of threads.
+fn maximal_edges(path: &Path, min_len: usize, utility: impl Fn(Edge) -> Utility + Sync) -> Vec<Edge> {
+    let (_, maximal) = (0..path.len())
+        .into_par_iter()
+        .with_min_len(min_len)
+        .fold(|| (None, Vec::new()), |(max, mut edges), i| {
+            let e = Edge::from(path.edge_at(i));
+            let current = Some(utility(e));
+            match current.cmp(&max) {
+                Ordering::Less => (max, edges),
+                Ordering::Equal => {
+                    edges.push(e);
+                    (max, edges)
+                },
+                Ordering::Greater => {
+                    edges.clear();
+                    edges.push(e);
+                    (current, edges)
+                },
+            }
+        })
+        .reduce(|| (None, Vec::new()), |(max_a, mut a), (max_b, b)| match max_a.cmp(&max_b) {
+            Ordering::Less => (max_b, b),
+            Ordering::Greater => (max_a, a),
+            Ordering::Equal => {
+                a.extend(b);
+                (max_a, a)
+            },
+        });
+    maximal
+}
+
+/// Position in `edges` of the first edge `{b, b_next}` whose exchange with an edge of augmented
+/// cost `removed` lowers the augmented cost and is accepted, `added(b, b_next)` being the cost
+/// of the new edges and `accepts(gain, k)` telling whether lowering it by `gain` is enough.
+#[inline]
+fn first_improvement(
+    edges: &[(usize, usize, i32)],
+    removed: i32,
+    added: impl Fn(usize, usize) -> i32,
+    mut accepts: impl FnMut(i32, usize) -> bool) -> Option<usize>
+{
+    edges.iter()
+        .enumerate()
+        .position(|(k, &(b, b_next, cost))| {
+            let gain = removed + cost - added(b, b_next);
+            gain > 0 && accepts(gain, k)
+        })
+}
+
+impl Default for SolverWorkspace {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GuidedLocalSearch {
     pub fn new(distances: SymmetricMatrix) -> Self {
-        Self { distances }
+        Self::from_shared(Arc::new(distances))
     }
 
-    fn cost(&self, path: &Path) -> i32 {
+    /// Solver of the costs given by `cost(i, j)` for `i < j`, see `SymmetricMatrix::from_fn`.
+    pub fn from_fn(size: usize, cost: impl FnMut(usize, usize) -> i32) -> Self {
+        Self::new(SymmetricMatrix::from_fn(size, cost))
+    }
+
+    /// Solver sharing the distances of `instance`, without copying them.
+    pub fn from_instance(instance: &Instance) -> Self {
+        Self::from_shared(instance.shared_distances())
+    }
+
+    fn from_shared(distances: Arc<SymmetricMatrix>) -> Self {
+        let longest = distances.data.iter().map(|d| d.unsigned_abs()).max().unwrap_or(0);
+        Self {
+            distances,
+            longest,
+            acceptance: Acceptance::Always,
+            utility: UtilityComparison::Truncated,
+            tie_break: TieBreak::All,
+            arithmetic: Arithmetic::Float,
+            alpha_percent: 30,
+            min_gain: 1,
+            depot: None,
+            overflow: Overflow::default(),
+            step_unit: StepUnit::Penalization,
+        }
+    }
+
+    pub fn with_acceptance(mut self, acceptance: Acceptance) -> Self {
+        self.acceptance = acceptance;
+        self
+    }
+
+    pub fn with_utility(mut self, utility: UtilityComparison) -> Self {
+        self.utility = utility;
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn with_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    /// Weigh the penalties by `alpha_percent` percent of the mean edge length of the first local
+    /// optimum, 30 by default. Lower values stay close to the local optimum, higher ones move
+    /// away from it faster.
+    pub fn with_alpha_percent(mut self, alpha_percent: u32) -> Self {
+        self.alpha_percent = alpha_percent;
+        self
+    }
+
+    /// Skip the 2-opt moves lowering the augmented cost by less than `min_gain`, cutting the
+    /// long tail of tiny improvements on large instances. 1, the default, applies them all,
+    /// see `GlsConfig::validate` for lower values. `RunInfo::skipped` counts the others.
+    pub fn with_min_gain(mut self, min_gain: i32) -> Self {
+        self.min_gain = min_gain;
+        self
+    }
+
+    /// Start the returned tours at `depot`, which does not change the search.
+    pub fn with_depot(mut self, depot: Option<usize>) -> Self {
+        self.depot = depot;
+        self
+    }
+
+    /// Guard the search against `i32` overflows, see `Overflow`. The checks cost a scan of the
+    /// distances per solve and a comparison per penalized edge.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Count the steps in `step_unit`, see `StepUnit`. `Evaluations(0)` runs no step.
+    pub fn with_step_unit(mut self, step_unit: StepUnit) -> Self {
+        self.step_unit = step_unit;
+        self
+    }
+
+    /// Apply the search options of `config`, its seed and steps being given to `solve`.
+    pub fn with_config(self, config: &GlsConfig) -> Self {
+        self.with_acceptance(config.acceptance)
+            .with_utility(config.utility)
+            .with_tie_break(config.tie_break)
+            .with_arithmetic(config.arithmetic)
+            .with_alpha_percent(config.alpha_percent)
+            .with_min_gain(config.min_gain)
+            .with_depot(config.depot)
+            .with_overflow(config.overflow)
+            .with_step_unit(config.step_unit)
+    }
+
+    pub(crate) fn distances(&self) -> &SymmetricMatrix {
+        &self.distances
+    }
+
+    pub(crate) fn cost(&self, path: &Path) -> i32 {
         self.distances.sum(path.edges())
     }
 
+    /// Cost of `path` and whether it overflows `i32`, clamped to its range unless the arithmetic
+    /// is unchecked.
+    fn overflowing_cost(&self, path: &Path) -> (i32, bool) {
+        if self.overflow == Overflow::Unchecked {
+            return (self.cost(path), false);
+        }
+        let cost: i64 = path.edges().map(|e| self.distances[e] as i64).sum();
+        let clamped = cost.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        (clamped, clamped as i64 != cost)
+    }
+
+    /// Largest penalty keeping the local search from overflowing, `None` when unchecked.
+    ///
+    /// A move sums the augmented costs of four edges, so each one must stay within a quarter of
+    /// the `i32` range. The limit is negative when even the distances exceed it.
+    fn penalty_limit(&self, penalty_factor: i32) -> Option<i64> {
+        if self.overflow == Overflow::Unchecked {
+            return None;
+        }
+        let headroom = (i32::MAX / 4) as i64 - self.longest as i64;
+        Some(if penalty_factor > 0 { headroom.div_euclid(penalty_factor as i64) } else { headroom.signum() * i64::MAX })
+    }
+
+    /// Cost and augmented cost of `path`, `lambda` being the penalty factor (see
+    /// `penalty_factor` for the one used by `solve`).
+    pub fn augmented_cost(&self, path: &Path, penalties: &Penalties, lambda: i32) -> AugmentedCost {
+        let cost = self.cost(path);
+        let augmented = cost + lambda * penalties.sum(path.edges());
+        AugmentedCost { cost, augmented }
+    }
+
     pub fn sequential(&self) -> Route {
         let path = Path::sequential(self.distances.size());
         let cost = self.cost(&path);
@@ -26,24 +337,40 @@ impl GuidedLocalSearch {
     }
 
     pub fn nearest_neighbor(&self) -> Route {
+        self.nearest_neighbor_in(&mut Vec::new())
+    }
+
+    fn nearest_neighbor_in(&self, visited: &mut Vec<bool>) -> Route {
+        self.nearest_neighbor_by(visited, |a, b| self.distances[(a, b)])
+    }
+
+    /// Nearest neighbor construction from vertex 0, `weight` telling how close two vertices are.
+    ///
+    /// Ties go to the smallest vertex. Each step scans the unvisited vertices in parallel on
+    /// large instances, the scans being the whole cost of the construction.
+    pub(crate) fn nearest_neighbor_by(&self, visited: &mut Vec<bool>, weight: impl Fn(VertexId, VertexId) -> i32 + Sync) -> Route {
         let size = self.distances.size();
 
         let mut res = Path::from_size(size);
-        let mut remainders: Vec<_> = (1..size).collect();
+        visited.clear();
+        visited.resize(size, false);
+        visited[0] = true;
 
-        for i in 0..size - 1 {
-            let (remainder, neighbor) = remainders.iter().copied()
-                .enumerate()
-                .min_by(|&(_, n_a), &(_, n_b)|
-                    self.distances[(i, n_a)].cmp(&self.distances[(i, n_b)])
-                )
-                .unwrap();
+        for pos in (0..size - 1).map(TourPos) {
+            let current = res.vertex(pos);
+            let unvisited = |&v: &usize| !visited[v];
+            let candidate = |v: usize| (weight(current, VertexId(v)), v);
+            let (_, neighbor) = if size < NEAREST_NEIGHBOR_MIN_LEN {
+                (0..size).filter(unvisited).map(candidate).min()
+            } else {
+                (0..size).into_par_iter().with_min_len(NEAREST_NEIGHBOR_MIN_LEN).filter(unvisited).map(candidate).min()
+            }.unwrap();
 
-            remainders.remove(remainder);
-            res[i + 1] = neighbor;
+            visited[neighbor] = true;
+            res.set_vertex(res.next_pos(pos), VertexId(neighbor));
         }
 
-        let cost = self.cost(&res);
+        let (cost, _) = self.overflowing_cost(&res);
         let res = Route::new(cost, res);
 
         debug_assert!(res.path.is_hamiltonian());
@@ -56,111 +383,469 @@ impl GuidedLocalSearch {
         candidate: &mut Path,
         neighborhood: &Path,
         penalty_factor: i32,
-        penalties: &mut SymmetricMatrix)
+        penalties: &mut Penalties)
     {
-        let cost_change = |va: (usize, usize), vb: (usize, usize)| {
-            self.distances[va] + self.distances[vb]
-                + penalty_factor * (penalties[va] + penalties[vb])
-        };
+        self.local_search_filtered(candidate, neighborhood, penalty_factor, penalties, |_, _, _| true);
+    }
+
+    /// Same as `local_search`, only applying the improving moves for which
+    /// `allowed(candidate, from, to)`, `from` and `to` being the positions `Path::twist` would
+    /// reverse between. Side constraints on the tour are kept this way.
+    pub(crate) fn local_search_filtered(
+        &self,
+        candidate: &mut Path,
+        neighborhood: &Path,
+        penalty_factor: i32,
+        penalties: &mut Penalties,
+        allowed: impl Fn(&Path, usize, usize) -> bool)
+    {
+        self.local_search_logged(candidate, neighborhood, penalty_factor, penalties, allowed, None);
+    }
+
+    /// Same as `local_search_filtered`, recording the applied twists in `log`. Returns the
+    /// number of moves evaluated, applied, and improving ones skipped for their gain.
+    fn local_search_logged(
+        &self,
+        candidate: &mut Path,
+        neighborhood: &Path,
+        penalty_factor: i32,
+        penalties: &mut Penalties,
+        allowed: impl Fn(&Path, usize, usize) -> bool,
+        mut log: Option<&mut EventLog>) -> (u64, u64, u64)
+    {
+        // Validate the inputs once, indexing may be unchecked (see the `checked-index` feature)
+        let size = self.distances.size();
+        assert!(candidate.len() == size && candidate.is_hamiltonian());
+        assert!(neighborhood.len() == size && neighborhood.is_hamiltonian());
+        assert_eq!(penalties.size(), size);
+
+        // Edge leaving each position of the neighborhood, in its order, with its augmented cost.
+        // Rebuilt after each twist, it keeps the inner loop on a flat slice.
+        let mut edges: Vec<(usize, usize, i32)> = Vec::with_capacity(size);
+        let mut skipped = 0;
+        let mut evaluated = 0;
+        let mut moves = 0;
 
         'outer: loop {
-            for (skip, i) in neighborhood.0.iter().copied().enumerate() {
-                // Find vertexes to twist
-                let i_next = (i + 1) % candidate.len();
-                let i_vertex = candidate[i];
-                let i_vertex_next = candidate[i_next];
-
-                for j in neighborhood.0.iter().copied().skip(skip + 2) {
-                    let j_next = (j + 1) % candidate.len();
-                    let j_vertex = candidate[j];
-                    let j_vertex_next = candidate[j_next];
-
-                    // Calculate the new cost: {i, i+1}, {j, j+1} -> {i, j}, {i+1, j+1}
-                    let cost_decreased = cost_change((i_vertex, i_vertex_next), (j_vertex, j_vertex_next));
-                    let cost_increased = cost_change((i_vertex, j_vertex), (i_vertex_next, j_vertex_next));
-                    let cost_change = cost_increased - cost_decreased;
-
-                    // If the cost is decreased, apply the twist and finish the step
-                    if cost_change < 0 {
-                        candidate.twist(i_next, j);
-                        continue 'outer; // Improvement found, start again.
+            edges.clear();
+            edges.extend(neighborhood.0.iter().map(|&i| {
+                let (a, b) = candidate.edge_at(i);
+                (a, b, self.augmented_distance(VertexId(a), VertexId(b), penalties, penalty_factor))
+            }));
+
+            for (skip, &(a, a_next, removed)) in edges.iter().enumerate() {
+                // Candidate moves: {a, a_next}, {b, b_next} -> {a, b}, {a_next, b_next}
+                let rest = edges.get(skip + 2..).unwrap_or(&[]);
+                let (row, row_next) = (self.distances.row(a), self.distances.row(a_next));
+                let from = (neighborhood[skip] + 1) % size;
+                let accepts = |gain: i32, k: usize| {
+                    if gain >= self.min_gain {
+                        allowed(candidate, from, neighborhood[skip + 2 + k])
+                    } else {
+                        skipped += 1;
+                        false
+                    }
+                };
+
+                let improving = if penalty_factor == 0 {
+                    first_improvement(rest, removed, |b, b_next| row[b] + row_next[b_next], accepts)
+                } else {
+                    let (penalty_row, penalty_row_next) = (penalties.row(a), penalties.row(a_next));
+                    let added = |b: usize, b_next: usize| {
+                        row[b] + row_next[b_next] + penalty_factor * (penalty_row[b] + penalty_row_next[b_next])
+                    };
+                    first_improvement(rest, removed, added, accepts)
+                };
+                evaluated += improving.map_or(rest.len(), |k| k + 1) as u64;
+
+                // If the cost is decreased, apply the twist and start again
+                if let Some(k) = improving {
+                    let to = neighborhood[skip + 2 + k];
+                    candidate.twist(from, to);
+                    moves += 1;
+                    if let Some(log) = log.as_deref_mut() {
+                        log.push(Event::Twist { from: from as u32, to: to as u32 });
                     }
+                    continue 'outer;
                 }
             }
 
             // If reached, there was no improvement.
             break;
         }
+
+        (evaluated, moves, skipped)
+    }
+
+    /// Change of the augmented cost when replacing the edges `{a, a_next}, {b, b_next}` by
+    /// `{a, b}, {a_next, b_next}`.
+    #[inline]
+    pub(crate) fn exchange_delta(
+        &self,
+        (a, a_next): (VertexId, VertexId),
+        (b, b_next): (VertexId, VertexId),
+        penalties: &Penalties,
+        penalty_factor: i32) -> i32
+    {
+        let cost = |va: (VertexId, VertexId), vb: (VertexId, VertexId)| {
+            self.distances[va] + self.distances[vb]
+                + penalty_factor * (penalties[va] + penalties[vb])
+        };
+        cost((a, b), (a_next, b_next)) - cost((a, a_next), (b, b_next))
+    }
+
+    /// Distance plus weighted penalty of the edge `{a, b}`.
+    #[inline]
+    pub(crate) fn augmented_distance(&self, a: VertexId, b: VertexId, penalties: &Penalties, penalty_factor: i32) -> i32 {
+        self.distances[(a, b)] + penalty_factor * penalties[(a, b)]
     }
 
+    /// Tour found by GLS from the nearest neighbor tour with `steps` steps.
+    ///
+    /// Panics if the search overflows with `Overflow::Error`, see `try_solve`.
     pub fn solve(&self, seed: u64, steps: usize) -> Route {
+        self.solve_observed(seed, steps, &mut |_: &Progress| {})
+    }
+
+    /// Same as `solve`, reporting the progress to `observer` after the first local search and
+    /// after every step.
+    pub fn solve_observed(&self, seed: u64, steps: usize, observer: &mut impl Observer) -> Route {
+        self.search(seed, steps, observer).0
+    }
+
+    /// Same as `solve`, also returning the penalties accumulated during the search so they can
+    /// guide further work on the route, e.g. `perturb::penalized_double_bridge`.
+    pub fn solve_with_penalties(&self, seed: u64, steps: usize) -> (Route, Penalties) {
+        self.search(seed, steps, &mut |_: &Progress| {})
+    }
+
+    /// Same as `solve`, failing when the search overflowed with `Overflow::Error` instead of
+    /// returning the tour it stopped at.
+    pub fn try_solve(&self, seed: u64, steps: usize) -> Result<Route, OverflowError> {
+        let mut workspace = SolverWorkspace::new();
+        let route = self.search_in(seed, steps, None, &mut |_: &Progress| {}, &mut workspace);
+        match workspace.overflow {
+            Some(overflow) => Err(overflow),
+            None => Ok(route),
+        }
+    }
+
+    /// Same as `solve`, using the buffers of `workspace` instead of allocating new ones.
+    pub fn solve_with_workspace(&self, seed: u64, steps: usize, workspace: &mut SolverWorkspace) -> Route {
+        self.search_in(seed, steps, None, &mut |_: &Progress| {}, workspace)
+    }
+
+    fn search(&self, seed: u64, steps: usize, observer: &mut impl Observer) -> (Route, Penalties) {
+        let mut workspace = SolverWorkspace::new();
+        let route = self.search_in(seed, steps, None, observer, &mut workspace);
+        if let Some(overflow) = workspace.overflow {
+            panic!("arithmetic overflow at step {}, see try_solve", overflow.step);
+        }
+        (route, workspace.penalties)
+    }
+
+    /// GLS with at most `steps` steps, stopping early once `deadline` is reached.
+    pub(crate) fn search_in(
+        &self,
+        seed: u64,
+        steps: usize,
+        deadline: Option<Instant>,
+        observer: &mut impl Observer,
+        workspace: &mut SolverWorkspace) -> Route
+    {
+        let route = self.nearest_neighbor_in(&mut workspace.visited);
+        self.search_from(route, seed, steps, deadline, observer, workspace)
+    }
+
+    /// Same as `search_in`, starting from `route` instead of the nearest neighbor tour.
+    pub(crate) fn search_from(
+        &self,
+        mut route: Route,
+        seed: u64,
+        steps: usize,
+        deadline: Option<Instant>,
+        observer: &mut impl Observer,
+        workspace: &mut SolverWorkspace) -> Route
+    {
+        let start = Instant::now();
         let size = self.distances.size();
+        let SolverWorkspace { penalties, neighborhood, log, overflow, .. } = workspace;
+        let mut log = log.as_mut();
+        *overflow = None;
+        if self.overflow == Overflow::Error && self.penalty_limit(0).is_some_and(|limit| limit < 0) {
+            *overflow = Some(OverflowError { step: 0 });
+            return self.pinned(route);
+        }
+        if let Some(log) = log.as_deref_mut() {
+            log.start(&route.path);
+        }
 
         // RNG
         let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
 
         // Neighborhood search
-        let mut neighborhood: Vec<_> = (0..size).collect();
-        neighborhood.shuffle(&mut rng);
-        let neighborhood = &Path::new(neighborhood);
-
-        // Candidate
-        let mut route = self.nearest_neighbor();
+        self.shuffle_neighborhood(&mut rng, neighborhood);
+        let neighborhood = &*neighborhood;
 
         // First iteration
-        let mut penalties = SymmetricMatrix::from_size(size);
-        self.local_search(&mut route.path, neighborhood, 0, &mut penalties);
-        route.cost = self.cost(&route.path);
+        penalties.reset_to_size(size);
+        let (mut evaluations, mut moves, mut skipped) = self.local_search_logged(&mut route.path, neighborhood, 0, penalties, |_, _, _| true, log.as_deref_mut());
+        let (cost, overflowed) = self.overflowing_cost(&route.path);
+        route.cost = cost;
+        // Step at which an overflow stopped the search
+        let mut stopped = (overflowed && self.overflow == Overflow::Error).then_some(0);
 
-        let penalty_factor = (0.3 * (route.cost as f64 / size as f64)) as i32;
+        let mut best_cost = route.cost;
+        observer.on_step(&Progress {
+            step: 0,
+            steps,
+            cost: route.cost,
+            best_cost,
+            elapsed: start.elapsed(),
+            path: &route.path,
+            penalties,
+            penalized: 0,
+            moves,
+        });
 
-        for _ in 0..steps {
-            let calc_utility = |penalties: &SymmetricMatrix, e: (usize, usize)| -> i32 {
-                (self.distances[e] as f64 / (1.0 + penalties[e] as f64)) as i32
+        let penalty_factor = self.penalty_factor(route.cost);
+        let penalty_limit = self.penalty_limit(penalty_factor);
+
+        // Best tour to roll back to, only kept when some local optima can be rejected
+        let mut incumbent = match self.acceptance {
+            Acceptance::Always => None,
+            Acceptance::Threshold { .. } => Some(route.clone()),
+        };
+        if let (Some(log), Some(_)) = (log.as_deref_mut(), &incumbent) {
+            log.push(Event::Incumbent);
+        }
+
+        // Evaluations the steps may spend, counted from here
+        let budget = match self.step_unit {
+            StepUnit::Penalization => None,
+            StepUnit::Evaluations(per_step) => Some(evaluations.saturating_add(per_step.saturating_mul(steps as u64))),
+        };
+        let mut iterations = 0;
+        let mut stopped_empty = false;
+        for step in 1.. {
+            let done = match budget {
+                None => step > steps,
+                Some(budget) => evaluations >= budget,
             };
+            if done || stopped_empty || stopped.is_some() || observer.should_stop() || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            iterations = step;
 
-            // Find the maximum utility
-            let max_utility = route.path.edges()
-                .map(|e| calc_utility(&penalties, e))
-                .max()
-                .unwrap();
+            let mut edges = self.penalized_edges(&route.path, penalties, &mut rng);
+            if let Some(limit) = penalty_limit {
+                let saturated = |e: &Edge| penalties[*e] as i64 >= limit;
+                if edges.iter().any(saturated) {
+                    if self.overflow == Overflow::Error {
+                        stopped = Some(step);
+                        break;
+                    }
+                    edges.retain(|e| !saturated(e));
+                }
+            }
+            for &e in &edges {
+                penalties.inc(e);
+                let (a, b) = e.endpoints();
+                if let Some(log) = log.as_deref_mut() {
+                    log.push(Event::Penalize { a: a as u32, b: b as u32 });
+                }
+            }
+            let penalized = edges.len();
+            let (evaluated, moved, skipped_now) = self.local_search_logged(&mut route.path, neighborhood, penalty_factor, penalties, |_, _, _| true, log.as_deref_mut());
+            evaluations += evaluated;
+            moves += moved;
+            skipped += skipped_now;
+            // No move to evaluate (2 vertices or fewer): the budget would never run out
+            if evaluated == 0 && budget.is_some() {
+                stopped_empty = true;
+            }
 
-            // Penalize features with maximum utility
-            for e in route.path.edges() {
-                if calc_utility(&penalties, e) == max_utility {
-                    penalties.inc(e.0, e.1, 1);
+            let (mut cost, overflowed) = self.overflowing_cost(&route.path);
+            if overflowed && self.overflow == Overflow::Error {
+                stopped = Some(step);
+            }
+            if let Some(incumbent) = &mut incumbent {
+                if cost < incumbent.cost {
+                    incumbent.cost = cost;
+                    incumbent.path.clone_from(&route.path);
+                    if let Some(log) = log.as_deref_mut() {
+                        log.push(Event::Incumbent);
+                    }
+                } else if !self.acceptance.accepts(cost, incumbent.cost) {
+                    cost = incumbent.cost;
+                    route.path.clone_from(&incumbent.path);
+                    if let Some(log) = log.as_deref_mut() {
+                        log.push(Event::Restore);
+                    }
                 }
             }
 
-            self.local_search(&mut route.path, neighborhood, penalty_factor, &mut penalties);
+            best_cost = best_cost.min(cost);
+            observer.on_step(&Progress {
+                step,
+                steps,
+                cost,
+                best_cost,
+                elapsed: start.elapsed(),
+                path: &route.path,
+                penalties,
+                penalized,
+                moves: moved,
+            });
         }
 
         // Run a last local search pass without penalties to reach the local minimum
-        self.local_search(&mut route.path, neighborhood, 0, &mut penalties);
-        route.cost = self.cost(&route.path);
-        route
+        let (evaluated, moved, skipped_now) = self.local_search_logged(&mut route.path, neighborhood, 0, penalties, |_, _, _| true, log.as_deref_mut());
+        evaluations += evaluated;
+        moves += moved;
+        skipped += skipped_now;
+        let (cost, overflowed) = self.overflowing_cost(&route.path);
+        route.cost = cost;
+        if overflowed && self.overflow == Overflow::Error {
+            stopped.get_or_insert(iterations);
+        }
+        *overflow = stopped.map(|step| OverflowError { step });
+        observer.on_finish(&Progress {
+            step: iterations,
+            steps,
+            cost,
+            best_cost: best_cost.min(cost),
+            elapsed: start.elapsed(),
+            path: &route.path,
+            penalties,
+            penalized: 0,
+            moves: moved,
+        });
+        if let (Some(log), Some(depot)) = (log, self.depot) {
+            log.push(Event::Rotate { start: depot as u32 });
+        }
+
+        let config = GlsConfig::default()
+            .with_seed(seed)
+            .with_steps(steps)
+            .with_acceptance(self.acceptance)
+            .with_utility(self.utility)
+            .with_tie_break(self.tie_break)
+            .with_arithmetic(self.arithmetic)
+            .with_alpha_percent(self.alpha_percent)
+            .with_min_gain(self.min_gain)
+            .with_step_unit(self.step_unit);
+        route.info = Some(RunInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            config: config.fingerprint(),
+            seed,
+            instance: self.distances.checksum(),
+            elapsed: start.elapsed(),
+            iterations,
+            evaluations,
+            moves,
+            skipped,
+        });
+        self.pinned(route)
+    }
+
+    /// `route` starting at the depot, if any.
+    pub(crate) fn pinned(&self, route: Route) -> Route {
+        match self.depot {
+            Some(depot) => route.rotated_to_start(depot),
+            None => route,
+        }
+    }
+
+    /// Random order in which the local search visits the tour positions.
+    pub(crate) fn neighborhood(&self, rng: &mut Mt64) -> Path {
+        let mut neighborhood = Path(Vec::new());
+        self.shuffle_neighborhood(rng, &mut neighborhood);
+        neighborhood
+    }
+
+    fn shuffle_neighborhood(&self, rng: &mut Mt64, neighborhood: &mut Path) {
+        neighborhood.0.clear();
+        neighborhood.0.extend(0..self.distances.size());
+        neighborhood.0.shuffle(rng);
+    }
+
+    /// Weight of the penalties in the augmented cost, given the cost of the first local minimum
+    /// (see `Arithmetic` for the formulas).
+    pub fn penalty_factor(&self, cost: i32) -> i32 {
+        let size = self.distances.size();
+        match self.arithmetic {
+            Arithmetic::Float => (self.alpha_percent as f64 / 100.0 * (cost as f64 / size as f64)) as i32,
+            Arithmetic::Integer => (self.alpha_percent as i64 * cost as i64 / (100 * size as i64)) as i32,
+        }
+    }
+
+    /// Penalize the edges of `path` with maximum utility, or one of them depending on the tie
+    /// break, and return the number of penalized edges.
+    pub(crate) fn penalize(&self, path: &Path, penalties: &mut Penalties, rng: &mut impl Rng) -> usize {
+        let chosen = self.penalized_edges(path, penalties, rng);
+        for &e in &chosen {
+            penalties.inc(e);
+        }
+        chosen.len()
+    }
+
+    /// The edges `penalize` would penalize.
+    fn penalized_edges(&self, path: &Path, penalties: &Penalties, rng: &mut impl Rng) -> Vec<Edge> {
+        let shared = penalties;
+        let utility = |e: Edge| match self.utility {
+            UtilityComparison::Truncated => {
+                let truncated = match self.arithmetic {
+                    Arithmetic::Float => (self.distances[e] as f64 / (1.0 + shared[e] as f64)) as i64,
+                    Arithmetic::Integer => self.distances[e] as i64 / (1 + shared[e] as i64),
+                };
+                Utility { distance: truncated, weight: 1 }
+            },
+            UtilityComparison::Exact => Utility { distance: self.distances[e] as i64, weight: 1 + shared[e] as i64 },
+        };
+
+        let maximal = maximal_edges(path, PENALIZE_MIN_LEN, utility);
+
+        match self.tie_break {
+            TieBreak::All => maximal,
+            TieBreak::Random => vec![maximal[rng.gen_range(0..maximal.len())]],
+            TieBreak::Longest => {
+                // `max_by_key` keeps the last maximum
+                let longest = maximal.iter().rev().max_by_key(|&&e| self.distances[e]).unwrap();
+                vec![*longest]
+            },
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(test)]
+    mod from_fn {
+        use crate::types::edge::Edge;
+        use crate::types::gls::GuidedLocalSearch;
+
+        #[test]
+        fn test() {
+            // Stops on a line with a surcharge on the edge {0, 1}
+            let cost = |i: usize, j: usize| if (i, j) == (0, 1) { 1000 } else { (j - i) as i32 };
+            let gls = GuidedLocalSearch::from_fn(12, cost);
+            let route = gls.solve(666, 10);
+
+            assert!(!route.path.edge_set().contains(&Edge::new(0, 1)));
+            assert_eq!(route.cost, gls.cost(&route.path));
+        }
+    }
+
     #[cfg(test)]
     mod sequential {
-        use crate::types::matrix::SymmetricMatrix;
         use crate::types::gls::GuidedLocalSearch;
         use crate::types::route::Route;
         use crate::types::path::Path;
 
         #[test]
         fn test() {
-            let mut matrix = SymmetricMatrix::from_size(4);
-            matrix.set(0, 1, 2);
-            matrix.set(0, 2, 7);
-            matrix.set(0, 3, 3);
-            matrix.set(1, 2, 4);
-            matrix.set(1, 3, 1);
-            matrix.set(2, 3, 9);
-
+            let matrix = crate::matrix! { 0-1: 2, 0-2: 7, 0-3: 3, 1-2: 4, 1-3: 1, 2-3: 9 };
             let gls = GuidedLocalSearch::new(matrix);
             let actual = gls.sequential();
 
@@ -169,4 +854,352 @@ mod tests {
             assert_eq!(actual, expected);
         }
     }
+
+    #[cfg(test)]
+    mod augmented_cost {
+        use crate::types::gls::{AugmentedCost, GuidedLocalSearch};
+        use crate::types::path::Path;
+        use crate::types::penalties::Penalties;
+
+        #[test]
+        fn test() {
+            let gls = GuidedLocalSearch::new(crate::matrix! { 0-1: 2, 0-2: 7, 1-2: 4 });
+
+            let mut penalties = Penalties::from_size(3);
+            penalties.set((0, 1), 1);
+            penalties.set((1, 2), 3);
+
+            let path = Path::new(vec![0, 1, 2]);
+            assert_eq!(gls.augmented_cost(&path, &penalties, 0), AugmentedCost { cost: 13, augmented: 13 });
+            assert_eq!(gls.augmented_cost(&path, &penalties, 5), AugmentedCost { cost: 13, augmented: 33 });
+        }
+    }
+
+    #[cfg(test)]
+    mod penalize {
+        use crate::types::config::{TieBreak, UtilityComparison};
+        use crate::types::edge::Edge;
+        use crate::types::gls::{maximal_edges, GuidedLocalSearch, Utility};
+        use crate::types::path::Path;
+        use crate::types::penalties::Penalties;
+        use rand_mt::Mt64;
+        use rand::SeedableRng;
+        use rand::seq::SliceRandom;
+
+        fn rng() -> Mt64 {
+            SeedableRng::seed_from_u64(666)
+        }
+
+        #[test]
+        fn maximal_utilities() {
+            // Tour edges cost 4, 4, 6, 2, 3; {2, 3} already has a penalty
+            let costs = [((0, 1), 4), ((1, 2), 4), ((2, 3), 6), ((3, 4), 2), ((0, 4), 3)];
+            let gls = GuidedLocalSearch::from_fn(5, |i, j| {
+                costs.iter().find(|&&(e, _)| e == (i, j)).map_or(100, |&(_, c)| c)
+            });
+            let mut penalties = Penalties::from_size(5);
+            penalties.set((2, 3), 1);
+
+            gls.penalize(&Path::sequential(5), &mut penalties, &mut rng());
+            // Utilities 4, 4, 3, 2, 3: both maxima are penalized
+            assert_eq!(penalties[(0, 1)], 1);
+            assert_eq!(penalties[(1, 2)], 1);
+            assert_eq!(penalties[(2, 3)], 1);
+            assert_eq!(penalties[(3, 4)], 0);
+            assert_eq!(penalties[(0, 4)], 0);
+        }
+
+        #[test]
+        fn exact() {
+            // Utilities 7 / 2 and 10 / 3 both truncate to 3
+            let costs = [((0, 1), 7), ((1, 2), 10), ((0, 2), 1)];
+            let gls = GuidedLocalSearch::from_fn(3, |i, j| costs.iter().find(|&&(e, _)| e == (i, j)).unwrap().1);
+            let initial = || {
+                let mut penalties = Penalties::from_size(3);
+                penalties.set((0, 1), 1);
+                penalties.set((1, 2), 2);
+                penalties
+            };
+
+            let mut truncated = initial();
+            gls.penalize(&Path::sequential(3), &mut truncated, &mut rng());
+            assert_eq!((truncated[(0, 1)], truncated[(1, 2)]), (2, 3));
+
+            let mut exact = initial();
+            gls.with_utility(UtilityComparison::Exact).penalize(&Path::sequential(3), &mut exact, &mut rng());
+            assert_eq!((exact[(0, 1)], exact[(1, 2)]), (2, 2));
+        }
+
+        #[test]
+        fn tie_break() {
+            // Utilities 5 / 1 and 11 / 2 both truncate to 5
+            let costs = [((0, 1), 5), ((1, 2), 11), ((0, 2), 1)];
+            let gls = |tie_break| {
+                GuidedLocalSearch::from_fn(3, |i, j| costs.iter().find(|&&(e, _)| e == (i, j)).unwrap().1)
+                    .with_tie_break(tie_break)
+            };
+            let penalize = |tie_break| {
+                let mut penalties = Penalties::from_size(3);
+                penalties.set((1, 2), 1);
+                let count = gls(tie_break).penalize(&Path::sequential(3), &mut penalties, &mut rng());
+                (count, penalties[(0, 1)], penalties[(1, 2)])
+            };
+
+            assert_eq!(penalize(TieBreak::All), (2, 1, 2));
+            assert_eq!(penalize(TieBreak::Longest), (1, 0, 2));
+            let (count, a, b) = penalize(TieBreak::Random);
+            assert_eq!((count, a + b), (1, 2));
+        }
+
+        #[test]
+        fn deterministic_reduction() {
+            // Many ties spread over the tour, in an order unrelated to the vertices
+            let mut rng = rng();
+            let mut path = Path::sequential(1000);
+            path.0.shuffle(&mut rng);
+            let utility = |e: Edge| {
+                let (a, b) = e.endpoints();
+                Utility { distance: ((a * 31 + b * 17) % 50) as i64, weight: 1 + (a % 3) as i64 }
+            };
+
+            let expected = maximal_edges(&path, usize::MAX, utility);
+            assert!(expected.len() > 1);
+
+            // As if run with several values of RAYON_NUM_THREADS, the global pool being set once
+            for threads in [1, 2, 3, 8] {
+                let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+                for min_len in [1, 7, 64, 999] {
+                    assert_eq!(pool.install(|| maximal_edges(&path, min_len, utility)), expected);
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod arithmetic {
+        use crate::testing::random_gls;
+        use crate::types::config::Arithmetic;
+
+        #[test]
+        fn integer() {
+            let gls = random_gls(200);
+            let integer = gls.clone().with_arithmetic(Arithmetic::Integer);
+
+            assert_eq!(integer.penalty_factor(10_000), 15);
+            assert_eq!(integer.penalty_factor(i32::MAX), gls.penalty_factor(i32::MAX));
+            for cost in (0..100_000).step_by(7) {
+                assert!((gls.penalty_factor(cost) - integer.penalty_factor(cost)).abs() <= 1);
+            }
+
+            // Same formulas up to rounding, which this instance doesn't hit
+            assert_eq!(integer.solve(666, 50), gls.solve(666, 50));
+        }
+    }
+
+    #[cfg(test)]
+    mod min_gain {
+        use crate::types::gls::GuidedLocalSearch;
+        use crate::types::matrix::SymmetricMatrix;
+        use crate::types::point::Point;
+
+        #[test]
+        fn skipped() {
+            let points: Vec<_> = (0..300).map(|i| Point::new(i * 37 % 1009, i * 53 % 997)).collect();
+            let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+
+            let all = gls.solve(666, 10);
+            assert_eq!(all.info.as_ref().unwrap().skipped, 0);
+
+            let coarse = gls.clone().with_min_gain(50).solve(666, 10);
+            assert!(coarse.info.as_ref().unwrap().skipped > 0);
+            assert!(coarse.path.is_hamiltonian());
+            assert_eq!(coarse.cost, gls.cost(&coarse.path));
+            assert!(coarse.cost > all.cost);
+        }
+    }
+
+    #[cfg(test)]
+    mod nearest_neighbor {
+        use crate::types::gls::GuidedLocalSearch;
+        use crate::types::matrix::SymmetricMatrix;
+        use crate::types::path::Path;
+        use crate::types::point::Point;
+
+        #[test]
+        fn follows_last_vertex() {
+            // Each step must start from the last visited vertex, not from the vertex numbered
+            // like the position
+            let points = [Point::new(0, 0), Point::new(5, 0), Point::new(1, 0), Point::new(2, 0)];
+            let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+            let actual = gls.nearest_neighbor();
+
+            assert_eq!(actual.path, Path::new(vec![0, 2, 3, 1]));
+            assert_eq!(actual.cost, 10);
+        }
+
+        #[test]
+        fn parallel_ties() {
+            // A grid, with ties at every step, large enough to be scanned in parallel
+            let points: Vec<_> = (0..4200).map(|i| Point::new(i % 70, i / 70)).collect();
+            let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+            let actual = gls.nearest_neighbor();
+
+            let mut expected = vec![0];
+            let mut remainders: Vec<_> = (1..points.len()).collect();
+            while !remainders.is_empty() {
+                let last = expected[expected.len() - 1];
+                let (i, _) = remainders.iter().enumerate().min_by_key(|&(_, &v)| gls.distances()[(last, v)]).unwrap();
+                expected.push(remainders.remove(i));
+            }
+            assert_eq!(actual.path, Path::new(expected));
+        }
+    }
+
+    #[cfg(test)]
+    mod workspace {
+        use crate::testing::scattered_gls;
+        use crate::types::config::GlsConfig;
+        use crate::types::gls::SolverWorkspace;
+
+        #[test]
+        fn same_as_solve() {
+            let mut workspace = SolverWorkspace::new();
+
+            // Reused across solvers of different sizes, in both directions
+            for size in [40, 10, 25] {
+                let gls = scattered_gls(size);
+                let actual = gls.solve_with_workspace(666, 10, &mut workspace);
+                let (expected, penalties) = gls.solve_with_penalties(666, 10);
+
+                assert_eq!(actual, expected);
+                assert!(*workspace.penalties() == penalties);
+            }
+        }
+
+        #[test]
+        fn run_info() {
+            let gls = scattered_gls(40);
+            let info = gls.solve(666, 10).info.unwrap();
+
+            assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+            assert_eq!(info.config, GlsConfig::default().with_steps(10).fingerprint());
+            assert_eq!((info.seed, info.iterations), (666, 10));
+            assert_eq!(info.instance, gls.distances().checksum());
+        }
+    }
+
+    #[cfg(test)]
+    mod acceptance {
+        use crate::testing::scattered_gls;
+        use crate::types::config::Acceptance;
+        use crate::types::observer::Progress;
+
+        #[test]
+        fn accepts() {
+            assert!(Acceptance::Always.accepts(200, 100));
+            assert!(Acceptance::Threshold { percent: 5 }.accepts(105, 100));
+            assert!(!Acceptance::Threshold { percent: 5 }.accepts(106, 100));
+        }
+
+        #[test]
+        fn rolls_back() {
+            let gls = scattered_gls(60)
+                .with_acceptance(Acceptance::Threshold { percent: 0 });
+
+            // Never continues from a tour worse than the best one
+            let route = gls.solve_observed(666, 20, &mut |progress: &Progress| {
+                assert_eq!(progress.cost, progress.best_cost);
+                assert_eq!(progress.cost, gls.cost(progress.path));
+            });
+            assert!(route.path.is_hamiltonian());
+            assert_eq!(route.cost, gls.cost(&route.path));
+        }
+    }
+
+    #[cfg(test)]
+    mod overflow {
+        use crate::testing::scattered_gls;
+        use crate::types::config::Overflow;
+        use crate::types::gls::{GuidedLocalSearch, OverflowError};
+
+        #[test]
+        fn in_range() {
+            let gls = scattered_gls(60);
+            let expected = gls.solve(666, 20);
+
+            for overflow in [Overflow::Saturate, Overflow::Error] {
+                let actual = gls.clone().with_overflow(overflow).try_solve(666, 20).unwrap();
+                assert_eq!((actual.cost, actual.path), (expected.cost, expected.path.clone()));
+            }
+        }
+
+        #[test]
+        fn penalties() {
+            // The penalty factor alone is beyond a quarter of the range
+            let gls = GuidedLocalSearch::from_fn(12, |i, j| (40 + (i * 7 + j * 13) % 11) as i32 * 1_000_000)
+                .with_alpha_percent(2000);
+
+            let error = gls.clone().with_overflow(Overflow::Error);
+            assert_eq!(error.try_solve(666, 10), Err(OverflowError { step: 1 }));
+
+            let (route, penalties) = gls.clone().with_overflow(Overflow::Saturate).solve_with_penalties(666, 10);
+            assert_eq!(penalties.total(), 0);
+            assert_eq!(route.cost, gls.cost(&route.path));
+        }
+
+        #[test]
+        fn costs() {
+            // Tours cost about 3e9
+            let gls = GuidedLocalSearch::from_fn(10, |i, j| (300 + (i + j) % 7) as i32 * 1_000_000);
+
+            let error = gls.clone().with_overflow(Overflow::Error);
+            assert_eq!(error.try_solve(666, 10), Err(OverflowError { step: 0 }));
+            let solved = std::panic::catch_unwind(|| error.solve(666, 10));
+            assert!(solved.is_err());
+
+            let route = gls.with_overflow(Overflow::Saturate).solve(666, 10);
+            assert!(route.path.is_hamiltonian());
+            assert_eq!(route.cost, i32::MAX);
+        }
+    }
+
+    #[cfg(test)]
+    mod step_unit {
+        use crate::testing::scattered_gls;
+        use crate::types::config::StepUnit;
+        use crate::types::gls::GuidedLocalSearch;
+
+        #[test]
+        fn evaluations() {
+            let gls = scattered_gls(60);
+            let penalization = gls.solve(666, 20);
+            let info = penalization.info.unwrap();
+            assert_eq!(info.iterations, 20);
+            assert!(info.evaluations > 0);
+
+            let per_step = info.evaluations / 20;
+            let route = gls.clone().with_step_unit(StepUnit::Evaluations(per_step)).solve(666, 20);
+            assert_eq!(route.cost, gls.cost(&route.path));
+            let counted = route.info.unwrap();
+            assert!(counted.iterations > 0 && counted.evaluations >= 20 * per_step);
+        }
+
+        #[test]
+        fn comparable_work() {
+            // The same work runs fewer steps of plain GLS on a larger instance
+            let iterations = |size: usize| {
+                let gls = scattered_gls(size).with_step_unit(StepUnit::Evaluations(20_000));
+                gls.solve(666, 10).info.unwrap().iterations
+            };
+            assert!(iterations(30) > iterations(90));
+        }
+
+        #[test]
+        fn nothing_to_evaluate() {
+            let gls = GuidedLocalSearch::from_fn(2, |_, _| 1).with_step_unit(StepUnit::Evaluations(10));
+            assert_eq!(gls.solve(1, 5).info.unwrap().iterations, 1);
+            let gls = gls.with_step_unit(StepUnit::Evaluations(0));
+            assert_eq!(gls.solve(1, 5).info.unwrap().iterations, 0);
+        }
+    }
 }
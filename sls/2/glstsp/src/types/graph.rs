@@ -0,0 +1,167 @@
+use crate::types::matrix::SymmetricMatrix;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+
+/// Sparse weighted undirected graph, e.g. a road network extracted from OSM.
+///
+/// Costs between stops are the shortest paths over this graph. Since the solver works on
+/// symmetric costs, every edge can be traversed in both directions.
+pub struct Graph {
+    adjacency: Vec<Vec<(usize, i32)>>,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum GraphError {
+    /// The line (1-based) is not a `from to weight` triple with a non-negative weight.
+    Parse { line: usize },
+    /// The stop is not a vertex of the graph.
+    UnknownStop(usize),
+    Unreachable { from: usize, to: usize },
+    /// The shortest path between the stops costs more than an `i32` holds.
+    Overflow { from: usize, to: usize },
+}
+
+impl Graph {
+    pub fn from_size(size: usize) -> Self {
+        Self { adjacency: vec![Vec::new(); size] }
+    }
+
+    /// Parse an edge list with one `from to weight` edge per line, vertices being 0-based ids.
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn from_edge_list(text: &str) -> Result<Self, GraphError> {
+        let mut edges = Vec::new();
+
+        for (line, content) in text.lines().enumerate() {
+            let content = content.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            let error = || GraphError::Parse { line: line + 1 };
+            let fields: Vec<_> = content.split_whitespace().collect();
+            if fields.len() != 3 {
+                return Err(error());
+            }
+
+            let from = fields[0].parse::<usize>().map_err(|_| error())?;
+            let to = fields[1].parse::<usize>().map_err(|_| error())?;
+            let weight = fields[2].parse::<i32>().map_err(|_| error())?;
+            if weight < 0 {
+                return Err(error());
+            }
+
+            edges.push((from, to, weight));
+        }
+
+        let size = edges.iter().map(|&(a, b, _)| a.max(b) + 1).max().unwrap_or(0);
+        let mut res = Self::from_size(size);
+        for (from, to, weight) in edges {
+            res.add_edge(from, to, weight);
+        }
+        Ok(res)
+    }
+
+    pub fn size(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: i32) {
+        debug_assert!(weight >= 0);
+        self.adjacency[from].push((to, weight));
+        self.adjacency[to].push((from, weight));
+    }
+
+    /// Dijkstra from `from` to every vertex, `None` for unreachable ones.
+    pub fn shortest_distances(&self, from: usize) -> Vec<Option<i64>> {
+        let mut res = vec![None; self.size()];
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0i64, from)));
+
+        while let Some(Reverse((dist, vertex))) = heap.pop() {
+            if res[vertex].is_some() {
+                continue;
+            }
+            res[vertex] = Some(dist);
+
+            for &(neighbor, weight) in &self.adjacency[vertex] {
+                if res[neighbor].is_none() {
+                    heap.push(Reverse((dist + weight as i64, neighbor)));
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Cost matrix between `stops` (vertices of the graph), the vertex `i` of the matrix being
+    /// `stops[i]`. Shortest paths are computed in parallel, one Dijkstra per stop.
+    pub fn distance_matrix(&self, stops: &[usize]) -> Result<SymmetricMatrix, GraphError> {
+        if let Some(&stop) = stops.iter().find(|&&stop| stop >= self.size()) {
+            return Err(GraphError::UnknownStop(stop));
+        }
+
+        let rows: Vec<_> = stops.par_iter()
+            .map(|&stop| self.shortest_distances(stop))
+            .collect();
+
+        let mut res = SymmetricMatrix::from_size(stops.len());
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &stop) in stops.iter().enumerate().skip(i + 1) {
+                let dist = row[stop].ok_or(GraphError::Unreachable { from: stops[i], to: stop })?;
+                let dist = i32::try_from(dist).map_err(|_| GraphError::Overflow { from: stops[i], to: stop })?;
+                res.set(i, j, dist);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::graph::{Graph, GraphError};
+
+    // 0 --4-- 1 --1-- 2
+    //  \             /
+    //   ----10------      3 --2-- 4
+    fn create_graph() -> Graph {
+        Graph::from_edge_list("# road network\n0 1 4\n1 2 1\n\n0 2 10\n3 4 2\n").unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(create_graph().size(), 5);
+        assert_eq!(Graph::from_edge_list("0 1 4\n1 2\n").err(), Some(GraphError::Parse { line: 2 }));
+        assert_eq!(Graph::from_edge_list("0 1 -4\n").err(), Some(GraphError::Parse { line: 1 }));
+        assert_eq!(Graph::from_edge_list("0 x 4\n").err(), Some(GraphError::Parse { line: 1 }));
+    }
+
+    #[test]
+    fn shortest_distances() {
+        let actual = create_graph().shortest_distances(0);
+        assert_eq!(actual, vec![Some(0), Some(4), Some(5), None, None]);
+    }
+
+    #[test]
+    fn distance_matrix() {
+        let actual = create_graph().distance_matrix(&[2, 0, 1]).unwrap();
+
+        assert_eq!(actual.row(0), &[0, 5, 1]);
+        assert_eq!(actual.row(1), &[5, 0, 4]);
+        assert_eq!(actual.row(2), &[1, 4, 0]);
+    }
+
+    #[test]
+    fn distance_matrix_errors() {
+        let graph = create_graph();
+        assert_eq!(graph.distance_matrix(&[0, 7]).err(), Some(GraphError::UnknownStop(7)));
+        assert_eq!(graph.distance_matrix(&[0, 1, 3]).err(), Some(GraphError::Unreachable { from: 0, to: 3 }));
+
+        let long = Graph::from_edge_list(&format!("0 1 {0}\n1 2 {0}\n", i32::MAX)).unwrap();
+        assert_eq!(long.distance_matrix(&[0, 1]).unwrap().row(0), &[0, i32::MAX]);
+        assert_eq!(long.distance_matrix(&[1, 2, 0]).err(), Some(GraphError::Overflow { from: 2, to: 0 }));
+    }
+}
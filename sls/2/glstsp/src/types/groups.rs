@@ -0,0 +1,125 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+impl GuidedLocalSearch {
+    /// Tour visiting the vertices of each of `groups` consecutively, in any order within the
+    /// group, e.g. the stops of a same building, improved by `steps` GLS steps.
+    ///
+    /// A 2-opt move removing two edges keeps the groups together when neither edge is inside
+    /// a group, moving whole groups as super-nodes, or when both are inside the same group,
+    /// reordering it. The other moves are skipped.
+    pub fn solve_grouped(&self, groups: &[Vec<usize>], seed: u64, steps: usize) -> Route {
+        let size = self.distances().size();
+        let mut group_of = vec![None; size];
+        for (g, group) in groups.iter().enumerate() {
+            for &vertex in group {
+                assert!(vertex < size && group_of[vertex].is_none(), "invalid group vertex {}", vertex);
+                group_of[vertex] = Some(g);
+            }
+        }
+
+        let mut path = self.grouped_nearest_neighbor(groups, &group_of);
+
+        let inside = |(a, b): (usize, usize)| group_of[a].filter(|&g| group_of[b] == Some(g));
+        let allowed = |path: &Path, from: usize, to: usize| {
+            let len = path.len();
+            inside(path.edge_at((from + len - 1) % len)) == inside(path.edge_at(to))
+        };
+
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = self.neighborhood(&mut rng);
+        let mut penalties = Penalties::from_size(size);
+
+        self.local_search_filtered(&mut path, &neighborhood, 0, &mut penalties, allowed);
+        let mut best = Route::new(self.cost(&path), path.clone());
+        let penalty_factor = self.penalty_factor(best.cost);
+
+        for _ in 0..steps {
+            self.penalize(&path, &mut penalties, &mut rng);
+            self.local_search_filtered(&mut path, &neighborhood, penalty_factor, &mut penalties, allowed);
+
+            let cost = self.cost(&path);
+            if cost < best.cost {
+                best = Route::new(cost, path.clone());
+            }
+        }
+
+        self.local_search_filtered(&mut best.path, &neighborhood, 0, &mut penalties, allowed);
+        best.cost = self.cost(&best.path);
+        best
+    }
+
+    /// Nearest neighbor construction over the groups and the ungrouped vertices, each group
+    /// being visited in the given order.
+    fn grouped_nearest_neighbor(&self, groups: &[Vec<usize>], group_of: &[Option<usize>]) -> Path {
+        let distances = self.distances();
+        let mut units: Vec<Vec<usize>> = groups.iter()
+            .filter(|group| !group.is_empty())
+            .cloned()
+            .collect();
+        units.extend((0..group_of.len()).filter(|&v| group_of[v].is_none()).map(|v| vec![v]));
+
+        let mut tour = units.swap_remove(0);
+        while !units.is_empty() {
+            let last = tour[tour.len() - 1];
+            let (next, _) = units.iter()
+                .enumerate()
+                .min_by_key(|(_, unit)| distances[(last, unit[0])])
+                .unwrap();
+            tour.extend(units.swap_remove(next));
+        }
+        Path::new(tour)
+    }
+}
+
+/// Whether the vertices of each of `groups` are consecutive in `path`, wrapping around its end.
+pub fn keeps_groups(path: &Path, groups: &[Vec<usize>]) -> bool {
+    let len = path.len();
+    let mut positions = vec![0; len];
+    for (pos, vertex) in path.0.iter().copied().enumerate() {
+        positions[vertex] = pos;
+    }
+
+    groups.iter().all(|group| {
+        // The group is a block when all but one of its members follow another member
+        let mut member = vec![false; len];
+        for &v in group {
+            member[v] = true;
+        }
+        let linked = group.iter().filter(|&&v| member[path[(positions[v] + len - 1) % len]]).count();
+        linked + 1 >= group.len()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::groups::keeps_groups;
+    use crate::types::path::Path;
+
+    #[test]
+    fn groups() {
+        let path = Path::new(vec![4, 0, 3, 1, 2, 5]);
+        assert!(keeps_groups(&path, &[vec![3, 0], vec![1, 2, 5]]));
+        assert!(keeps_groups(&path, &[vec![5, 4, 0]]));
+        assert!(!keeps_groups(&path, &[vec![0, 1]]));
+        assert!(keeps_groups(&path, &[vec![], vec![2]]));
+    }
+
+    #[test]
+    fn solve_grouped() {
+        let gls = random_gls(60);
+        // Far apart vertices, which an unconstrained tour would not visit together
+        let groups = vec![vec![5, 17, 3], vec![42, 11], vec![29, 0, 50, 8]];
+
+        let actual = gls.solve_grouped(&groups, 666, 20);
+        assert!(actual.path.is_hamiltonian());
+        assert!(keeps_groups(&actual.path, &groups));
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+        assert!(!keeps_groups(&gls.solve(666, 20).path, &groups));
+    }
+}
@@ -0,0 +1,248 @@
+use crate::types::anytime::Budget;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::observer::{Observer, Progress};
+use crate::types::route::Route;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Control of a search running in a background thread, started by `GuidedLocalSearch::spawn`,
+/// e.g. for a UI to pause it, show its best tour or give it more time.
+///
+/// The requests take effect between two GLS steps. Dropping the handle cancels the search.
+pub struct SolveHandle {
+    shared: Arc<Shared>,
+    /// Taken by `join`.
+    worker: Option<JoinHandle<Route>>,
+}
+
+/// State shared by the handle and the search.
+struct Shared {
+    control: Mutex<Control>,
+    /// Notified when the search is resumed, cancelled, held or ended.
+    changed: Condvar,
+    best: Mutex<Option<Route>>,
+    steps: AtomicUsize,
+}
+
+struct Control {
+    paused: bool,
+    cancelled: bool,
+    budget: Budget,
+    /// The search waits for `resume` or `cancel`.
+    held: bool,
+    ended: bool,
+}
+
+impl GuidedLocalSearch {
+    /// Run GLS in a background thread within `budget`, the paused time not counting towards a
+    /// time budget. `SolveHandle::join` returns the best tour seen, as `solve_anytime`.
+    pub fn spawn(&self, seed: u64, budget: Budget) -> SolveHandle {
+        let shared = Arc::new(Shared {
+            control: Mutex::new(Control { paused: false, cancelled: false, budget, held: false, ended: false }),
+            changed: Condvar::new(),
+            best: Mutex::new(None),
+            steps: AtomicUsize::new(0),
+        });
+
+        let gls = self.clone();
+        let worker = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                let mut controller = Controller { shared: Arc::clone(&shared), start: Instant::now(), paused_for: Duration::ZERO };
+                let last = gls.search_in(seed, usize::MAX, None, &mut controller, &mut SolverWorkspace::new());
+                shared.control.lock().unwrap().ended = true;
+                shared.changed.notify_all();
+
+                // The last pass without penalties may still improve the tour
+                let best = shared.best.lock().unwrap().take();
+                let mut best = match best {
+                    Some(best) if best.cost <= last.cost => best,
+                    _ => last.clone(),
+                };
+                best.info = last.info;
+                gls.pinned(best)
+            })
+        };
+
+        SolveHandle { shared, worker: Some(worker) }
+    }
+}
+
+impl SolveHandle {
+    /// Hold the search after its current step until `resume` or `cancel`.
+    pub fn pause(&self) {
+        self.shared.control.lock().unwrap().paused = true;
+    }
+
+    pub fn resume(&self) {
+        self.shared.control.lock().unwrap().paused = false;
+        self.shared.changed.notify_all();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.shared.control.lock().unwrap().paused
+    }
+
+    /// Wait until the search is held by `pause`, its steps and best tour no longer changing,
+    /// or has ended. Returns whether it is held.
+    pub fn wait_paused(&self) -> bool {
+        let mut control = self.shared.control.lock().unwrap();
+        while control.paused && !control.held && !control.ended {
+            control = self.shared.changed.wait(control).unwrap();
+        }
+        control.held
+    }
+
+    /// Stop the search before its next step, paused or not.
+    pub fn cancel(&self) {
+        self.shared.control.lock().unwrap().cancelled = true;
+        self.shared.changed.notify_all();
+    }
+
+    /// Replace the budget of the search, counted from its start. A budget already spent stops
+    /// it before its next step.
+    pub fn set_budget(&self, budget: Budget) {
+        self.shared.control.lock().unwrap().budget = budget;
+    }
+
+    /// Best tour found so far, `None` until the first local search ends.
+    pub fn best(&self) -> Option<Route> {
+        self.shared.best.lock().unwrap().clone()
+    }
+
+    /// Steps run so far.
+    pub fn steps(&self) -> usize {
+        self.shared.steps.load(Ordering::Relaxed)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.worker.as_ref().is_none_or(|worker| worker.is_finished())
+    }
+
+    /// Wait for the end of the search and return its best tour. A paused search is never
+    /// resumed by this: `resume` or `cancel` it first.
+    pub fn join(mut self) -> Route {
+        self.worker.take().unwrap().join().unwrap()
+    }
+}
+
+impl Drop for SolveHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Observer of the search thread, applying the requests of the handle.
+struct Controller {
+    shared: Arc<Shared>,
+    start: Instant,
+    paused_for: Duration,
+}
+
+impl Observer for Controller {
+    fn on_step(&mut self, progress: &Progress) {
+        self.shared.steps.store(progress.step, Ordering::Relaxed);
+        {
+            let mut best = self.shared.best.lock().unwrap();
+            if best.as_ref().is_none_or(|best| progress.cost < best.cost) {
+                *best = Some(Route::new(progress.cost, progress.path.clone()));
+            }
+        }
+
+        let mut control = self.shared.control.lock().unwrap();
+        if control.paused && !control.cancelled {
+            let since = Instant::now();
+            control.held = true;
+            self.shared.changed.notify_all();
+            while control.paused && !control.cancelled {
+                control = self.shared.changed.wait(control).unwrap();
+            }
+            control.held = false;
+            self.paused_for += since.elapsed();
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        let control = self.shared.control.lock().unwrap();
+        control.cancelled || match control.budget {
+            Budget::Steps(steps) => self.shared.steps.load(Ordering::Relaxed) >= steps,
+            Budget::Time(time) => self.start.elapsed().saturating_sub(self.paused_for) >= time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::anytime::Budget;
+    use crate::types::handle::SolveHandle;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    fn wait_for_best(handle: &SolveHandle) {
+        while handle.best().is_none() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn steps() {
+        let gls = random_gls(200);
+        let route = gls.spawn(666, Budget::Steps(20)).join();
+        let expected = gls.solve_anytime(666, Budget::Steps(20)).route;
+
+        assert_eq!((route.cost, &route.path), (expected.cost, &expected.path));
+        assert_eq!(route.info.unwrap().iterations, 20);
+    }
+
+    #[test]
+    fn pause_and_extend() {
+        let gls = random_gls(200);
+        let handle = gls.spawn(666, Budget::Time(Duration::from_secs(60)));
+        wait_for_best(&handle);
+
+        handle.pause();
+        assert!(handle.is_paused());
+        assert!(handle.wait_paused());
+        let steps = handle.steps();
+        let best = handle.best().unwrap();
+        assert_eq!(best.cost, gls.cost(&best.path));
+
+        handle.set_budget(Budget::Steps(steps + 5));
+        handle.resume();
+        let route = handle.join();
+        assert_eq!(route.info.unwrap().iterations, steps + 5);
+        assert!(route.cost <= best.cost);
+    }
+
+    #[test]
+    fn cancel() {
+        let gls = random_gls(200);
+        let handle = gls.spawn(666, Budget::Time(Duration::from_secs(60)));
+        wait_for_best(&handle);
+
+        handle.pause();
+        handle.cancel();
+        let route = handle.join();
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, gls.cost(&route.path));
+    }
+
+    #[test]
+    fn drop() {
+        let gls = random_gls(200);
+        let handle = gls.spawn(666, Budget::Time(Duration::from_secs(60)));
+        handle.pause();
+        let shared = Arc::clone(&handle.shared);
+        std::mem::drop(handle);
+
+        // The search thread ends, releasing its references
+        while Arc::strong_count(&shared) > 1 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(shared.control.lock().unwrap().ended);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::types::config::{GlsConfig, Orientation};
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::instance::Instance;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use crate::types::sampling::{representatives, skeleton, Selection};
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+/// Rounds of k-means placing the representatives.
+const K_MEANS_ITERATIONS: usize = 10;
+
+/// Tour of a large Euclidean instance solving only `count` representative vertices with GLS.
+///
+/// The representatives are the points closest to k-means centers (see `representatives`),
+/// solved with `config` as a smaller instance. The other vertices are inserted into their
+/// tour by cheapest insertion, and a 2-opt local search on the whole instance polishes the
+/// result. Much faster than `GuidedLocalSearch::solve` when `count` is a small fraction of the
+/// vertices, for a somewhat longer tour.
+///
+/// The depot and orientation of `config` apply to the returned tour only.
+///
+/// Panics when the instance has no coordinates, `count` is not between 2 and its size or the
+/// depot is not a vertex.
+pub fn solve_hierarchical(instance: &Instance, count: usize, config: &GlsConfig) -> Route {
+    let points = instance.points().expect("hierarchical solving needs coordinates");
+    assert!((2..=instance.size()).contains(&count));
+
+    let subset = representatives(points, count, Selection::KMeans { iterations: K_MEANS_ITERATIONS }, config.seed);
+    let sub = instance.subset(&subset);
+    let outline = if count > 3 {
+        // The depot and the direction are those of the whole tour, applied at the end
+        let sub_config = GlsConfig { depot: None, orientation: Orientation::Any, ..*config };
+        let route = GuidedLocalSearch::from_instance(&sub).with_config(&sub_config).solve(config.seed, config.steps);
+        skeleton(&route, &subset)
+    } else {
+        // Every order is optimal
+        subset
+    };
+
+    let gls = GuidedLocalSearch::from_instance(instance).with_config(config);
+    let mut route = gls.cheapest_insertion_from(&outline);
+
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(config.seed);
+    let neighborhood = gls.neighborhood(&mut rng);
+    let mut penalties = Penalties::from_size(instance.size());
+    gls.local_search(&mut route.path, &neighborhood, 0, &mut penalties);
+    route.cost = gls.cost(&route.path);
+    gls.pinned(route).oriented(instance, config.orientation)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::config::{GlsConfig, Orientation};
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::hierarchical::solve_hierarchical;
+    use crate::types::instance::Instance;
+
+    #[test]
+    fn solve() {
+        let points = random_points(1000);
+        let instance = Instance::from_points(points);
+        let config = GlsConfig::default().with_steps(20);
+
+        let actual = solve_hierarchical(&instance, 100, &config);
+        assert!(actual.path.is_hamiltonian());
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+
+        // Not far from solving the whole instance
+        let full = gls.solve(config.seed, config.steps);
+        assert!((actual.cost as f64) < 1.1 * full.cost as f64, "{} vs {}", actual.cost, full.cost);
+
+        let tiny = solve_hierarchical(&instance, 2, &config);
+        assert!(tiny.path.is_hamiltonian());
+
+        // The depot and the orientation are those of the whole tour, not of the outline
+        let pinned = solve_hierarchical(&instance, 100, &config.with_depot(500).with_orientation(Orientation::Clockwise));
+        assert_eq!(pinned.path.as_slice()[0], 500);
+        assert_eq!(pinned.cost, gls.cost(&pinned.path));
+        assert_eq!(pinned.oriented(&instance, Orientation::Clockwise), pinned);
+    }
+}
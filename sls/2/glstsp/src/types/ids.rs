@@ -0,0 +1,105 @@
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use std::ops::Index;
+
+/// A vertex of the instance, i.e. a row of the distance matrix.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct VertexId(pub usize);
+
+/// A position in a tour, the vertex visited there being `path.vertex(pos)`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct TourPos(pub usize);
+
+impl VertexId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl TourPos {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for VertexId {
+    fn from(index: usize) -> Self {
+        Self(index)
+    }
+}
+
+impl From<VertexId> for usize {
+    fn from(vertex: VertexId) -> Self {
+        vertex.0
+    }
+}
+
+impl From<usize> for TourPos {
+    fn from(index: usize) -> Self {
+        Self(index)
+    }
+}
+
+impl From<TourPos> for usize {
+    fn from(pos: TourPos) -> Self {
+        pos.0
+    }
+}
+
+/// Typed accessors. Indexing a path with a raw `usize` and a matrix with `(usize, usize)` is
+/// still supported for compatibility, these make the intent explicit.
+impl Path {
+    pub fn vertex(&self, pos: TourPos) -> VertexId {
+        VertexId(self[pos.0])
+    }
+
+    pub fn set_vertex(&mut self, pos: TourPos, vertex: VertexId) {
+        self[pos.0] = vertex.0;
+    }
+
+    /// Position following `pos`, wrapping around the end of the tour.
+    pub fn next_pos(&self, pos: TourPos) -> TourPos {
+        TourPos((pos.0 + 1) % self.len())
+    }
+
+    pub fn vertices(&self) -> impl Iterator<Item=VertexId> + '_ {
+        self.0.iter().copied().map(VertexId)
+    }
+}
+
+impl Index<(VertexId, VertexId)> for SymmetricMatrix {
+    type Output = i32;
+
+    fn index(&self, index: (VertexId, VertexId)) -> &Self::Output {
+        &self[(index.0.0, index.1.0)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::ids::{TourPos, VertexId};
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+
+    #[test]
+    fn path_accessors() {
+        let mut path = Path::new(vec![2, 0, 3, 1]);
+
+        assert_eq!(path.vertex(TourPos(0)), VertexId(2));
+        assert_eq!(path.next_pos(TourPos(3)), TourPos(0));
+        itertools::assert_equal(path.vertices(), [2, 0, 3, 1].iter().copied().map(VertexId));
+
+        path.set_vertex(TourPos(1), VertexId(7));
+        assert_eq!(path, Path::new(vec![2, 7, 3, 1]));
+    }
+
+    #[test]
+    fn matrix_index() {
+        let mut matrix = SymmetricMatrix::from_size(3);
+        matrix.set(0, 2, 5);
+
+        assert_eq!(matrix[(VertexId(2), VertexId(0))], 5);
+        assert_eq!(matrix[(VertexId::from(0), VertexId::from(2))], matrix[(0, 2)]);
+        assert_eq!(usize::from(TourPos::from(4)), 4);
+    }
+}
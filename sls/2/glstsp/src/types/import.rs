@@ -0,0 +1,530 @@
+use crate::types::coord::Coord2;
+use crate::types::geo::{GeoDistance, GeoPoint};
+use crate::types::instance::Instance;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::point::Point;
+use std::fs;
+use std::io;
+
+/// What the two coordinates of a point are, and so how the distances are computed.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Coordinates {
+    /// `x` and `y` in the plane, the Euclidean distances being multiplied by `scale` and
+    /// rounded (see `SymmetricMatrix::from_coords`).
+    Planar { scale: f64 },
+    /// Latitude and longitude in decimal degrees, the distances being great-circle ones in
+    /// meters.
+    Geographic,
+}
+
+impl Default for Coordinates {
+    fn default() -> Self {
+        Coordinates::Planar { scale: 1.0 }
+    }
+}
+
+/// Positions of the fields of a CSV line, starting at 0.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Columns {
+    /// Column of the vertex ids, which become the labels of the instance.
+    pub id: Option<usize>,
+    /// Columns of `x` and `y`, or of the latitude and longitude.
+    pub coords: (usize, usize),
+}
+
+/// Layout of a CSV points file, by default comma-separated `id,x,y` lines under a header.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CsvFormat {
+    pub delimiter: char,
+    /// Whether the first line names the columns.
+    pub header: bool,
+    /// Where the fields are, found by name in the header when `None`: `id` (or `name`,
+    /// `label`), then `x` and `y`, or `lat` (`latitude`) and `lon` (`lng`, `longitude`) with
+    /// geographic coordinates. Without a header, `id,x,y`.
+    pub columns: Option<Columns>,
+    pub coordinates: Coordinates,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self { delimiter: ',', header: true, columns: None, coordinates: Coordinates::default() }
+    }
+}
+
+impl CsvFormat {
+    /// Fields separated by `delimiter`, e.g. `;` for spreadsheets with decimal commas.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: Columns) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    pub fn with_coordinates(mut self, coordinates: Coordinates) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum ImportError {
+    Io(io::ErrorKind),
+    /// The header has no column of this name.
+    MissingColumn(&'static str),
+    /// The line lacks a field, or a coordinate is not a finite number, or not a latitude or
+    /// longitude with geographic coordinates.
+    Parse { line: usize },
+    /// The JSON is malformed at this byte, nests too deeply, or is not an array of points.
+    Json { offset: usize },
+    /// Not a single point.
+    Empty,
+}
+
+/// Names of the id column, then of the two coordinates, in the order they are looked for.
+const ID_NAMES: &[&str] = &["id", "name", "label"];
+const X_NAMES: &[&str] = &["x"];
+const Y_NAMES: &[&str] = &["y"];
+const LAT_NAMES: &[&str] = &["lat", "latitude"];
+const LON_NAMES: &[&str] = &["lon", "lng", "longitude"];
+/// Most arrays and objects a JSON value may be nested in, a point list needing 3.
+const MAX_DEPTH: usize = 64;
+
+/// Instance of the points listed in a CSV file, see `parse_csv`.
+pub fn read_csv(path: impl AsRef<std::path::Path>, format: &CsvFormat) -> Result<Instance, ImportError> {
+    let text = fs::read_to_string(path).map_err(|e| ImportError::Io(e.kind()))?;
+    parse_csv(&text, format)
+}
+
+/// Instance of the points listed one per line in `text`, labeled with their ids when the
+/// format has an id column. Fields may be quoted, as written by spreadsheets, and empty lines
+/// are skipped.
+///
+/// The instance keeps the points, multiplied by the scale and rounded for planar coordinates,
+/// or as longitude and latitude in millionths of a degree for geographic ones. Only the
+/// former suit `Instance::insert_point` and `move_point`, which measure plane distances.
+pub fn parse_csv(text: &str, format: &CsvFormat) -> Result<Instance, ImportError> {
+    let mut lines = text.lines()
+        .enumerate()
+        .map(|(line, content)| (line + 1, content))
+        .filter(|(_, content)| !content.trim().is_empty());
+
+    let header = if format.header { lines.next().map(|(_, content)| split_csv(content, format.delimiter)) } else { None };
+    let columns = match (format.columns, &header) {
+        (Some(columns), _) => columns,
+        (None, Some(header)) => find_columns(header, format.coordinates)?,
+        (None, None) => Columns { id: Some(0), coords: (1, 2) },
+    };
+
+    let mut ids = Vec::new();
+    let mut coords = Vec::new();
+    for (line, content) in lines {
+        let fields = split_csv(content, format.delimiter);
+        let field = |column: usize| fields.get(column).ok_or(ImportError::Parse { line });
+        let number = |column: usize| field(column)?.parse::<f64>().map_err(|_| ImportError::Parse { line });
+
+        let coord = (number(columns.coords.0)?, number(columns.coords.1)?);
+        if !is_valid(coord, format.coordinates) {
+            return Err(ImportError::Parse { line });
+        }
+        coords.push(coord);
+        if let Some(column) = columns.id {
+            ids.push(field(column)?.clone());
+        }
+    }
+
+    instance(&coords, columns.id.is_some().then_some(ids), format.coordinates)
+}
+
+/// Fields of a CSV line, unquoted and trimmed.
+fn split_csv(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(field.split_off(0).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn find_columns(header: &[String], coordinates: Coordinates) -> Result<Columns, ImportError> {
+    let find = |names: &[&str]| {
+        header.iter().position(|column| names.iter().any(|name| column.eq_ignore_ascii_case(name)))
+    };
+    let (first, second) = match coordinates {
+        Coordinates::Planar { .. } => ((X_NAMES, "x"), (Y_NAMES, "y")),
+        Coordinates::Geographic => ((LAT_NAMES, "lat"), (LON_NAMES, "lon")),
+    };
+    let first = find(first.0).ok_or(ImportError::MissingColumn(first.1))?;
+    let second = find(second.0).ok_or(ImportError::MissingColumn(second.1))?;
+    Ok(Columns { id: find(ID_NAMES), coords: (first, second) })
+}
+
+/// Instance of the points listed in a JSON file, see `parse_json`.
+pub fn read_json(path: impl AsRef<std::path::Path>, coordinates: Coordinates) -> Result<Instance, ImportError> {
+    let text = fs::read_to_string(path).map_err(|e| ImportError::Io(e.kind()))?;
+    parse_json(&text, coordinates)
+}
+
+/// Instance of the points of a JSON array, either `[x, y]` pairs or objects with the fields
+/// named as the CSV columns, e.g. `{"id": "A1", "lat": 48.85, "lon": 2.35}`. The instance is
+/// labeled with the ids, strings or numbers, when every object has one. The points are kept
+/// as by `parse_csv`.
+pub fn parse_json(text: &str, coordinates: Coordinates) -> Result<Instance, ImportError> {
+    let mut parser = JsonParser { text: text.as_bytes(), pos: 0, depth: 0 };
+    let value = parser.document()?;
+    let (first, second) = match coordinates {
+        Coordinates::Planar { .. } => (X_NAMES, Y_NAMES),
+        Coordinates::Geographic => (LAT_NAMES, LON_NAMES),
+    };
+
+    let Json::Array(items) = value else { return Err(ImportError::Json { offset: 0 }) };
+    let mut ids = Vec::new();
+    let mut coords = Vec::new();
+    for (offset, item) in items {
+        let error = ImportError::Json { offset };
+        let coord = match item {
+            Json::Array(pair) => match pair.as_slice() {
+                [(_, Json::Number(a)), (_, Json::Number(b))] => (*a, *b),
+                _ => return Err(error),
+            },
+            Json::Object(fields) => {
+                let find = |names: &[&str]| {
+                    fields.iter().find(|(key, _)| names.iter().any(|name| key.eq_ignore_ascii_case(name))).map(|(_, value)| value)
+                };
+                match find(ID_NAMES) {
+                    Some(Json::String(id)) => ids.push(id.clone()),
+                    Some(Json::Number(id)) => ids.push(id.to_string()),
+                    _ => {},
+                }
+                match (find(first), find(second)) {
+                    (Some(Json::Number(a)), Some(Json::Number(b))) => (*a, *b),
+                    _ => return Err(error),
+                }
+            },
+            _ => return Err(error),
+        };
+        if !is_valid(coord, coordinates) {
+            return Err(error);
+        }
+        coords.push(coord);
+    }
+
+    let labeled = !ids.is_empty() && ids.len() == coords.len();
+    instance(&coords, labeled.then_some(ids), coordinates)
+}
+
+/// Whether `coord` is a point: finite, and a latitude and longitude in range with geographic
+/// coordinates.
+fn is_valid(coord: (f64, f64), coordinates: Coordinates) -> bool {
+    match coordinates {
+        Coordinates::Planar { .. } => coord.0.is_finite() && coord.1.is_finite(),
+        Coordinates::Geographic => (-90.0..=90.0).contains(&coord.0) && (-180.0..=180.0).contains(&coord.1),
+    }
+}
+
+fn instance(coords: &[(f64, f64)], labels: Option<Vec<String>>, coordinates: Coordinates) -> Result<Instance, ImportError> {
+    if coords.is_empty() {
+        return Err(ImportError::Empty);
+    }
+    let (distances, points) = match coordinates {
+        Coordinates::Planar { scale } => {
+            let points: Vec<_> = coords.iter().map(|&(x, y)| Coord2::new([x, y])).collect();
+            let scaled = coords.iter().map(|&(x, y)| Point::new((x * scale).round() as i32, (y * scale).round() as i32));
+            (SymmetricMatrix::from_coords(&points, scale), scaled.collect())
+        },
+        Coordinates::Geographic => {
+            let points: Vec<_> = coords.iter().map(|&(lat, lon)| GeoPoint::new(lat, lon)).collect();
+            // East then north, so that the tours keep their orientation on a map
+            let degrees = coords.iter().map(|&(lat, lon)| Point::new((lon * 1e6).round() as i32, (lat * 1e6).round() as i32));
+            (SymmetricMatrix::from_geo_coords(&points, GeoDistance::Haversine), degrees.collect())
+        },
+    };
+    let instance = Instance::from_matrix(distances).with_points(points);
+    Ok(match labels {
+        Some(labels) => instance.with_labels(labels),
+        None => instance,
+    })
+}
+
+/// JSON value, the items of arrays keeping their offset for the errors.
+enum Json {
+    Null,
+    Bool,
+    Number(f64),
+    String(String),
+    Array(Vec<(usize, Json)>),
+    Object(Vec<(String, Json)>),
+}
+
+/// Recursive descent parser of the JSON values, enough for point lists.
+struct JsonParser<'a> {
+    text: &'a [u8],
+    pos: usize,
+    /// Arrays and objects the parser is in.
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn document(&mut self) -> Result<Json, ImportError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        if self.pos < self.text.len() {
+            return Err(self.error());
+        }
+        Ok(value)
+    }
+
+    fn error(&self) -> ImportError {
+        ImportError::Json { offset: self.pos }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.text.get(self.pos).is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip the whitespace, then `expected` if it comes next.
+    fn eat(&mut self, expected: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.text.get(self.pos) == Some(&expected);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json, ImportError> {
+        if !self.text[self.pos..].starts_with(keyword.as_bytes()) {
+            return Err(self.error());
+        }
+        self.pos += keyword.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, ImportError> {
+        self.skip_whitespace();
+        match self.text.get(self.pos) {
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.keyword("true", Json::Bool),
+            Some(b'f') => self.keyword("false", Json::Bool),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(_) => self.number(),
+            None => Err(self.error()),
+        }
+    }
+
+    /// `parse` within one more level of nesting, refused beyond `MAX_DEPTH` rather than
+    /// overflowing the stack.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, ImportError>) -> Result<Json, ImportError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error());
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<Json, ImportError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if self.eat(b']') {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push((self.pos, self.value()?));
+            if self.eat(b']') {
+                return Ok(Json::Array(items));
+            }
+            if !self.eat(b',') {
+                return Err(self.error());
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, ImportError> {
+        self.pos += 1;
+        let mut fields = Vec::new();
+        if self.eat(b'}') {
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.text.get(self.pos) != Some(&b'"') {
+                return Err(self.error());
+            }
+            let key = self.string()?;
+            if !self.eat(b':') {
+                return Err(self.error());
+            }
+            fields.push((key, self.value()?));
+            if self.eat(b'}') {
+                return Ok(Json::Object(fields));
+            }
+            if !self.eat(b',') {
+                return Err(self.error());
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ImportError> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            let c = *self.text.get(self.pos).ok_or_else(|| self.error())?;
+            self.pos += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = *self.text.get(self.pos).ok_or_else(|| self.error())?;
+                    self.pos += 1;
+                    let c = match escaped {
+                        b'"' | b'\\' | b'/' => escaped as char,
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => {
+                            let code = self.text.get(self.pos..self.pos + 4)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| self.error())?;
+                            self.pos += 4;
+                            // Surrogate pairs are not needed for ids
+                            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        },
+                        _ => return Err(self.error()),
+                    };
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                },
+                c => bytes.push(c),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+
+    fn number(&mut self) -> Result<Json, ImportError> {
+        let start = self.pos;
+        while self.text.get(self.pos).is_some_and(|c| matches!(c, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.pos])
+            .ok()
+            .and_then(|number| number.parse::<f64>().ok())
+            .filter(|number| number.is_finite())
+            .map(Json::Number)
+            .ok_or(ImportError::Json { offset: start })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::import::{parse_csv, parse_json, Columns, Coordinates, CsvFormat, ImportError};
+    use crate::types::point::Point;
+
+    #[test]
+    fn csv() {
+        let text = "id,x,y\nA,0,0\n\"B, north\",0,10\nC,10,10\n\n";
+        let instance = parse_csv(text, &CsvFormat::default()).unwrap();
+        assert_eq!(instance.size(), 3);
+        assert_eq!(instance.distances()[(0, 2)], 14);
+        assert_eq!(instance.labels().unwrap(), ["A", "B, north", "C"]);
+        assert_eq!(instance.points().unwrap()[1], Point::new(0, 10));
+
+        // Spreadsheet export: other delimiter and column order, no id
+        let text = "Y;X;Weight\n0;0;1\n10;0;1\n";
+        let format = CsvFormat::default().with_delimiter(';').with_coordinates(Coordinates::Planar { scale: 10.0 });
+        let instance = parse_csv(text, &format).unwrap();
+        assert_eq!(instance.distances()[(0, 1)], 100);
+        assert_eq!(instance.labels(), None);
+        assert_eq!(instance.points().unwrap(), [Point::new(0, 0), Point::new(0, 100)]);
+
+        let format = CsvFormat::default().with_header(false).with_columns(Columns { id: None, coords: (1, 0) });
+        assert_eq!(parse_csv("3,0\n3,4\n", &format).unwrap().distances()[(0, 1)], 4);
+    }
+
+    #[test]
+    fn csv_geographic() {
+        let text = "name,latitude,longitude\nParis,48.8566,2.3522\nLondon,51.5074,-0.1278\n";
+        let format = CsvFormat::default().with_coordinates(Coordinates::Geographic);
+        let instance = parse_csv(text, &format).unwrap();
+        assert!((instance.distances()[(0, 1)] - 343_500).abs() < 1000);
+        assert_eq!(instance.label(1), Some("London"));
+        assert_eq!(instance.points().unwrap()[0], Point::new(2_352_200, 48_856_600));
+    }
+
+    #[test]
+    fn csv_errors() {
+        let format = CsvFormat::default();
+        assert_eq!(parse_csv("id,x,z\n", &format).err(), Some(ImportError::MissingColumn("y")));
+        assert_eq!(parse_csv("id,x,y\nA,0,0\nB,zero,0\n", &format).err(), Some(ImportError::Parse { line: 3 }));
+        assert_eq!(parse_csv("id,x,y\nA,0\n", &format).err(), Some(ImportError::Parse { line: 2 }));
+        assert_eq!(parse_csv("id,x,y\n", &format).err(), Some(ImportError::Empty));
+        assert_eq!(parse_csv("id,x,y\nA,0,0\nB,NaN,0\n", &format).err(), Some(ImportError::Parse { line: 3 }));
+        assert_eq!(parse_csv("id,x,y\nA,inf,0\n", &format).err(), Some(ImportError::Parse { line: 2 }));
+        let geographic = CsvFormat::default().with_coordinates(Coordinates::Geographic);
+        assert_eq!(parse_csv("id,lat,lon\nA,91,0\n", &geographic).err(), Some(ImportError::Parse { line: 2 }));
+        assert_eq!(parse_csv("id,lat,lon\nA,0,-180.5\n", &geographic).err(), Some(ImportError::Parse { line: 2 }));
+    }
+
+    #[test]
+    fn json() {
+        let instance = parse_json("[[0, 0], [3, 4.0], [0, 4]]", Coordinates::default()).unwrap();
+        assert_eq!(instance.size(), 3);
+        assert_eq!(instance.distances()[(0, 1)], 5);
+        assert_eq!(instance.labels(), None);
+
+        let text = r#"[
+            {"id": "Customer \"1\"", "x": 0, "y": 0, "demand": [1, 2]},
+            {"id": 2, "x": 0, "y": -1e1, "open": true, "note": null}
+        ]"#;
+        let instance = parse_json(text, Coordinates::default()).unwrap();
+        assert_eq!(instance.distances()[(0, 1)], 10);
+        assert_eq!(instance.labels().unwrap(), ["Customer \"1\"", "2"]);
+
+        let text = r#"[{"lat": 48.8566, "lng": 2.3522}, {"lat": 51.5074, "lng": -0.1278}]"#;
+        let instance = parse_json(text, Coordinates::Geographic).unwrap();
+        assert!((instance.distances()[(0, 1)] - 343_500).abs() < 1000);
+    }
+
+    #[test]
+    fn json_errors() {
+        let parse = |text: &str| parse_json(text, Coordinates::default()).err();
+        assert_eq!(parse("[[0, 0], [1, 1]"), Some(ImportError::Json { offset: 15 }));
+        assert_eq!(parse(r#"[[0, 0], {"x": 1}]"#), Some(ImportError::Json { offset: 9 }));
+        assert_eq!(parse(r#"{"x": 1, "y": 1}"#), Some(ImportError::Json { offset: 0 }));
+        assert_eq!(parse("[[0, 0]] 1"), Some(ImportError::Json { offset: 9 }));
+        assert_eq!(parse("[]"), Some(ImportError::Empty));
+        assert_eq!(parse("[[0, 0], [1e999, 1]]"), Some(ImportError::Json { offset: 10 }));
+        let text = r#"[{"lat": 48.8566, "lon": 2.3522}, {"lat": 148.8566, "lon": 2.3522}]"#;
+        assert_eq!(parse_json(text, Coordinates::Geographic).err(), Some(ImportError::Json { offset: 34 }));
+
+        // Nested beyond the limit, without overflowing the stack
+        assert_eq!(parse(&"[".repeat(100_000)), Some(ImportError::Json { offset: 64 }));
+        let nested = format!("[[0, 0], {}1{}]", "[".repeat(60), "]".repeat(60));
+        assert_eq!(parse(&nested), Some(ImportError::Json { offset: 9 }));
+    }
+}
@@ -0,0 +1,487 @@
+use crate::types::checksum::Fnv64;
+use crate::types::config::GlsConfig;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::observer::Progress;
+use crate::types::point::Point;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+use rand::seq::index;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+/// Positions re-optimized on each side of an insertion or removal.
+const REPAIR_RADIUS: usize = 10;
+const REPAIR_STEPS: usize = 10;
+
+/// Tour refused by `Instance::improve`, computed for another instance.
+#[derive(Eq, PartialEq, Debug)]
+pub enum MismatchError {
+    Size { expected: usize, found: usize },
+    /// The route's `RunInfo` records other distances.
+    Distances,
+    /// The depot of the configuration is not a vertex of the instance.
+    Depot(usize),
+    /// The route visits a vertex twice or not at all, see `Route::repair`.
+    NotATour,
+}
+
+/// A problem instance: the distance matrix and, when known, the coordinates it was built from.
+#[derive(Eq, PartialEq)]
+pub struct Instance {
+    points: Option<Vec<Point>>,
+    distances: Arc<SymmetricMatrix>,
+    /// Time spent at each vertex, in the unit of the distances.
+    service_times: Option<Vec<i32>>,
+    /// Name of each vertex in the exports, e.g. `Customer #123`.
+    labels: Option<Vec<String>>,
+}
+
+impl Instance {
+    pub fn from_points(points: Vec<Point>) -> Self {
+        let distances = SymmetricMatrix::from_euclidean_coords(&points);
+        Self { points: Some(points), distances: Arc::new(distances), service_times: None, labels: None }
+    }
+
+    pub fn from_matrix(distances: SymmetricMatrix) -> Self {
+        Self { points: None, distances: Arc::new(distances), service_times: None, labels: None }
+    }
+
+    /// Same instance where each vertex takes `service_times[v]` to serve, e.g. unloading at a
+    /// stop. The search still minimizes the travel cost, see `Route::total_duration`.
+    pub fn with_service_times(mut self, service_times: Vec<i32>) -> Self {
+        assert_eq!(service_times.len(), self.size());
+        self.service_times = Some(service_times);
+        self
+    }
+
+    /// Same instance naming each vertex, so the legs and the exports read in terms of
+    /// `labels[v]` rather than vertex numbers. The labels are not part of the problem: the
+    /// search and the fingerprint ignore them.
+    pub fn with_labels(mut self, labels: Vec<String>) -> Self {
+        assert_eq!(labels.len(), self.size());
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Same instance placing each vertex at `points[v]`, for the plots, the orientation of the
+    /// tours and the clustering, e.g. imported coordinates the distances were computed from
+    /// in another way. The distances are kept as they are.
+    pub fn with_points(mut self, points: Vec<Point>) -> Self {
+        assert_eq!(points.len(), self.size());
+        self.points = Some(points);
+        self
+    }
+
+    pub fn size(&self) -> usize {
+        self.distances.size()
+    }
+
+    pub fn distances(&self) -> &SymmetricMatrix {
+        &self.distances
+    }
+
+    pub(crate) fn shared_distances(&self) -> Arc<SymmetricMatrix> {
+        Arc::clone(&self.distances)
+    }
+
+    pub fn points(&self) -> Option<&[Point]> {
+        self.points.as_deref()
+    }
+
+    pub fn service_times(&self) -> Option<&[i32]> {
+        self.service_times.as_deref()
+    }
+
+    pub fn labels(&self) -> Option<&[String]> {
+        self.labels.as_deref()
+    }
+
+    /// Label of `vertex`, if the vertices are labeled.
+    pub fn label(&self, vertex: usize) -> Option<&str> {
+        self.labels.as_ref().map(|labels| labels[vertex].as_str())
+    }
+
+    /// Time spent at `vertex`, 0 without service times.
+    pub fn service_time(&self, vertex: usize) -> i32 {
+        self.service_times.as_ref().map_or(0, |service_times| service_times[vertex])
+    }
+
+    /// Stable hash of the size, the kind of distances (from coordinates or given as a matrix)
+    /// and the costs, identifying the problem a stored tour belongs to. Service times, when
+    /// given, are hashed too.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv64::new();
+        hash.write_u8(self.points.is_some() as u8);
+        hash.write_u64(self.distances.checksum());
+        for &service_time in self.service_times().into_iter().flatten() {
+            hash.write_i32(service_time);
+        }
+        hash.finish()
+    }
+
+    /// Continue the search from `route`, e.g. a tour stored by an earlier run, with the options
+    /// and steps of `config`.
+    ///
+    /// Refuses tours of another size, routes that are not tours, tours whose `RunInfo` was
+    /// computed on other distances and a depot beyond the vertices.
+    pub fn improve(&self, route: Route, config: &GlsConfig) -> Result<Route, MismatchError> {
+        if route.path.len() != self.size() {
+            return Err(MismatchError::Size { expected: self.size(), found: route.path.len() });
+        }
+        if route.info.as_ref().is_some_and(|info| info.instance != self.distances.checksum()) {
+            return Err(MismatchError::Distances);
+        }
+        if let Some(depot) = config.depot.filter(|&depot| depot >= self.size()) {
+            return Err(MismatchError::Depot(depot));
+        }
+        if !route.path.is_hamiltonian() {
+            return Err(MismatchError::NotATour);
+        }
+
+        let gls = GuidedLocalSearch::from_instance(self).with_config(config);
+        let observer = &mut |_: &Progress| {};
+        let route = gls.search_from(route, config.seed, config.steps, None, observer, &mut SolverWorkspace::new());
+        Ok(route.oriented(self, config.orientation))
+    }
+
+    /// Add a vertex at `point`, numbered `size()`, and insert it in `route`, a tour of the
+    /// instance, where it costs the least. The tour is then re-optimized around it.
+    ///
+    /// The new vertex takes no service time and, if the vertices are labeled, its number as
+    /// label.
+    ///
+    /// Panics if the instance was not built from points.
+    pub fn insert_point(&mut self, point: Point, route: &mut Route) -> usize {
+        assert_eq!(route.path.len(), self.size());
+        let points = self.points.as_mut().expect("instance without coordinates");
+        let vertex = points.len();
+        points.push(point);
+        if let Some(service_times) = &mut self.service_times {
+            service_times.push(0);
+        }
+        if let Some(labels) = &mut self.labels {
+            labels.push(vertex.to_string());
+        }
+
+        let mut distances = SymmetricMatrix::from_size(vertex + 1);
+        for (i, other) in points[..vertex].iter().copied().enumerate() {
+            for j in i + 1..vertex {
+                distances.set(i, j, self.distances[(i, j)]);
+            }
+            distances.set(i, vertex, other.dist(point));
+        }
+        self.distances = Arc::new(distances);
+
+        let distances = &self.distances;
+        let (pos, _) = route.path.edges()
+            .enumerate()
+            .min_by_key(|&(_, (a, b))| distances[(a, vertex)] + distances[(vertex, b)] - distances[(a, b)])
+            .unwrap();
+        route.path.0.insert(pos + 1, vertex);
+
+        self.repair(route, pos + 1);
+        vertex
+    }
+
+    /// Move `vertex` to `point` and reinsert it in `route`, a tour of the instance, where it
+    /// costs the least. The vertices keep their numbers and the tour is re-optimized around it.
+    ///
+    /// Panics if the instance was not built from points.
+    pub fn move_point(&mut self, vertex: usize, point: Point, route: &mut Route) {
+        let size = self.size();
+        assert!(vertex < size && size > 2);
+        assert_eq!(route.path.len(), size);
+        let points = self.points.as_mut().expect("instance without coordinates");
+        points[vertex] = point;
+
+        let distances = Arc::make_mut(&mut self.distances);
+        for (i, other) in points.iter().enumerate().filter(|&(i, _)| i != vertex) {
+            distances.set(i, vertex, other.dist(point));
+        }
+
+        let pos = route.path.0.iter().position(|&v| v == vertex).unwrap();
+        route.path.0.remove(pos);
+        let distances = &self.distances;
+        let (pos, _) = route.path.edges()
+            .enumerate()
+            .min_by_key(|&(_, (a, b))| distances[(a, vertex)] + distances[(vertex, b)] - distances[(a, b)])
+            .unwrap();
+        route.path.0.insert(pos + 1, vertex);
+
+        self.repair(route, pos + 1);
+    }
+
+    /// Remove `vertex` from the instance and from `route`, a tour of the instance, linking its
+    /// neighbors. The following vertices are renumbered one lower and the tour is
+    /// re-optimized around the removed vertex.
+    pub fn remove_vertex(&mut self, vertex: usize, route: &mut Route) {
+        let size = self.size();
+        assert!(vertex < size && size > 2);
+        assert_eq!(route.path.len(), size);
+
+        let old = |i: usize| if i < vertex { i } else { i + 1 };
+        let mut distances = SymmetricMatrix::from_size(size - 1);
+        for i in 0..size - 1 {
+            for j in i + 1..size - 1 {
+                distances.set(i, j, self.distances[(old(i), old(j))]);
+            }
+        }
+        self.distances = Arc::new(distances);
+        if let Some(points) = &mut self.points {
+            points.remove(vertex);
+        }
+        if let Some(service_times) = &mut self.service_times {
+            service_times.remove(vertex);
+        }
+        if let Some(labels) = &mut self.labels {
+            labels.remove(vertex);
+        }
+
+        let pos = route.path.0.iter().position(|&v| v == vertex).unwrap();
+        route.path.0.remove(pos);
+        for v in &mut route.path.0 {
+            if *v > vertex {
+                *v -= 1;
+            }
+        }
+
+        self.repair(route, pos);
+    }
+
+    /// Change the cost of the edge `{a, b}`, e.g. after a road closure. The coordinates, if any,
+    /// no longer match the distances.
+    pub fn set_distance(&mut self, a: usize, b: usize, cost: i32) {
+        assert_ne!(a, b);
+        Arc::make_mut(&mut self.distances).set(a, b, cost);
+    }
+
+    /// Instance of the given vertices, the `i`-th one being numbered `i`, with their distances,
+    /// coordinates, service times and labels.
+    pub fn subset(&self, vertices: &[usize]) -> Instance {
+        let distances = SymmetricMatrix::from_fn(vertices.len(), |i, j| self.distances[(vertices[i], vertices[j])]);
+        Self {
+            points: self.points.as_ref().map(|points| vertices.iter().map(|&v| points[v]).collect()),
+            distances: Arc::new(distances),
+            service_times: self.service_times.as_ref().map(|times| vertices.iter().map(|&v| times[v]).collect()),
+            labels: self.labels.as_ref().map(|labels| vertices.iter().map(|&v| labels[v].clone()).collect()),
+        }
+    }
+
+    /// The first `count` vertices, e.g. a smaller instance for a quick test.
+    pub fn take(&self, count: usize) -> Instance {
+        assert!(count <= self.size());
+        self.subset(&(0..count).collect::<Vec<_>>())
+    }
+
+    /// `count` vertices drawn with `seed`, in increasing order. Unlike `take`, the sample
+    /// spreads over the whole instance when the vertices are numbered by area.
+    pub fn sample(&self, count: usize, seed: u64) -> Instance {
+        assert!(count <= self.size());
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let mut vertices = index::sample(&mut rng, self.size(), count).into_vec();
+        vertices.sort_unstable();
+        self.subset(&vertices)
+    }
+
+    /// Re-optimize `route` in a window around the position `pos`, also updating its cost.
+    fn repair(&self, route: &mut Route, pos: usize) {
+        let gls = GuidedLocalSearch::from_instance(self);
+        let range = pos.saturating_sub(REPAIR_RADIUS)..(pos + REPAIR_RADIUS + 1).min(route.path.len());
+
+        route.cost = gls.cost(&route.path);
+        gls.optimize_segment(route, range, 0, REPAIR_STEPS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scattered_points;
+    use crate::types::config::GlsConfig;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::instance::{Instance, MismatchError};
+    use crate::types::path::Path;
+    use crate::types::point::Point;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::route::Route;
+
+    #[test]
+    fn from_points() {
+        let points = vec![Point::new(0, 0), Point::new(3, 4), Point::new(6, 8)];
+        let instance = Instance::from_points(points.clone());
+
+        assert_eq!(instance.size(), 3);
+        assert_eq!(instance.points(), Some(points.as_slice()));
+        assert_eq!(instance.distances()[(0, 1)], 5);
+        assert_eq!(instance.distances()[(0, 2)], 10);
+    }
+
+    fn create_instance() -> Instance {
+        Instance::from_points(scattered_points(30))
+    }
+
+    #[test]
+    fn insert_point() {
+        let mut instance = create_instance();
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        let mut route = gls.solve(666, 10);
+
+        let vertex = instance.insert_point(Point::new(50, 50), &mut route);
+
+        assert_eq!(vertex, 30);
+        assert_eq!(instance.size(), 31);
+        assert_eq!(instance.distances()[(0, 30)], 70);
+        assert_eq!(instance.distances().data, SymmetricMatrix::from_euclidean_coords(instance.points().unwrap()).data);
+
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, gls.cost(&route.path));
+    }
+
+    #[test]
+    fn move_point() {
+        let mut instance = create_instance();
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        let mut route = gls.solve(666, 10);
+
+        instance.move_point(7, Point::new(50, 50), &mut route);
+
+        assert_eq!(instance.size(), 30);
+        assert_eq!(instance.points().unwrap()[7], Point::new(50, 50));
+        assert_eq!(instance.distances().data, SymmetricMatrix::from_euclidean_coords(instance.points().unwrap()).data);
+
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, gls.cost(&route.path));
+    }
+
+    #[test]
+    fn remove_vertex() {
+        let mut instance = create_instance();
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        let mut route = gls.solve(666, 10);
+        let before = route.cost;
+
+        instance.remove_vertex(7, &mut route);
+
+        assert_eq!(instance.size(), 29);
+        assert_eq!(instance.points().unwrap()[7], Point::new(8 * 37 % 101, 8 * 53 % 97));
+        assert_eq!(instance.distances().data, SymmetricMatrix::from_euclidean_coords(instance.points().unwrap()).data);
+
+        let gls = GuidedLocalSearch::from_instance(&instance);
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, gls.cost(&route.path));
+        // Shortcutting the removed vertex
+        assert!(route.cost <= before);
+    }
+
+    #[test]
+    fn subsets() {
+        let instance = create_instance().with_service_times((0..30).collect());
+
+        let first = instance.take(5);
+        assert_eq!(first.size(), 5);
+        assert_eq!(first.points(), Some(&instance.points().unwrap()[..5]));
+        assert_eq!(first.distances()[(1, 4)], instance.distances()[(1, 4)]);
+        assert_eq!(first.service_time(3), 3);
+
+        let subset = instance.subset(&[9, 2]);
+        assert_eq!(subset.distances()[(0, 1)], instance.distances()[(9, 2)]);
+        assert_eq!(subset.service_times(), Some(&[9, 2][..]));
+
+        let sample = instance.sample(10, 666);
+        assert_eq!(sample.size(), 10);
+        assert!(sample == instance.sample(10, 666));
+        assert_eq!(sample.distances().data, SymmetricMatrix::from_euclidean_coords(sample.points().unwrap()).data);
+    }
+
+    #[test]
+    fn service_times() {
+        let mut instance = create_instance().with_service_times((0..30).collect());
+        assert_eq!(instance.service_time(4), 4);
+        assert_ne!(instance.fingerprint(), create_instance().fingerprint());
+
+        let mut route = GuidedLocalSearch::from_instance(&instance).solve(666, 10);
+        instance.remove_vertex(7, &mut route);
+        let vertex = instance.insert_point(Point::new(50, 50), &mut route);
+        assert_eq!(instance.service_time(7), 8);
+        assert_eq!(instance.service_time(vertex), 0);
+        assert_eq!(create_instance().service_time(4), 0);
+    }
+
+    #[test]
+    fn labels() {
+        let labels: Vec<_> = (0..30).map(|i| format!("Customer #{}", 100 + i)).collect();
+        let mut instance = create_instance().with_labels(labels);
+        assert_eq!(instance.label(4), Some("Customer #104"));
+        assert_eq!(instance.fingerprint(), create_instance().fingerprint());
+        assert_eq!(instance.subset(&[9, 2]).labels().unwrap(), ["Customer #109", "Customer #102"]);
+
+        let mut route = GuidedLocalSearch::from_instance(&instance).solve(666, 10);
+        instance.remove_vertex(7, &mut route);
+        let vertex = instance.insert_point(Point::new(50, 50), &mut route);
+        assert_eq!(instance.label(7), Some("Customer #108"));
+        assert_eq!(instance.label(vertex), Some("29"));
+        assert_eq!(create_instance().label(4), None);
+    }
+
+    #[test]
+    fn set_distance() {
+        let mut instance = create_instance();
+        let shared = instance.shared_distances();
+
+        instance.set_distance(3, 1, 1000);
+        assert_eq!(instance.distances()[(1, 3)], 1000);
+        // Solvers built before keep the previous distances
+        assert_ne!(shared[(1, 3)], 1000);
+    }
+
+    #[test]
+    fn fingerprint() {
+        let instance = create_instance();
+        assert_eq!(instance.fingerprint(), create_instance().fingerprint());
+
+        // Same costs given as a matrix
+        let matrix = Instance::from_matrix(instance.distances().clone());
+        assert_ne!(matrix.fingerprint(), instance.fingerprint());
+
+        let mut changed = create_instance();
+        changed.set_distance(3, 1, 1000);
+        assert_ne!(changed.fingerprint(), instance.fingerprint());
+    }
+
+    #[test]
+    fn improve() {
+        let instance = create_instance();
+        let config = GlsConfig::default().with_steps(10);
+        let start = Route::new(0, Path::sequential(30));
+
+        let route = instance.improve(start, &config).unwrap();
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, GuidedLocalSearch::from_instance(&instance).cost(&route.path));
+
+        // Warm start from its own result
+        let again = instance.improve(route.clone(), &config).unwrap();
+        assert!(again.cost <= route.cost);
+
+        let mut other = create_instance();
+        other.set_distance(3, 1, 1000);
+        assert_eq!(other.improve(route, &config), Err(MismatchError::Distances));
+
+        let short = Route::new(0, Path::sequential(20));
+        assert_eq!(instance.improve(short, &config), Err(MismatchError::Size { expected: 30, found: 20 }));
+        let route = GuidedLocalSearch::from_instance(&instance).solve(666, 5);
+        assert_eq!(instance.improve(route, &config.with_depot(30)), Err(MismatchError::Depot(30)));
+        let repeated = Route::new(0, Path::new((0..30).map(|v| v % 29).collect()));
+        assert_eq!(instance.improve(repeated, &config), Err(MismatchError::NotATour));
+    }
+
+    #[test]
+    fn from_matrix() {
+        let instance = Instance::from_matrix(SymmetricMatrix::from_size(3));
+
+        assert_eq!(instance.size(), 3);
+        assert_eq!(instance.points(), None);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::instance::Instance;
+use crate::types::route::Route;
+use crate::types::tsplib::{Tsplib, TsplibTour};
+
+/// A TSPLIB instance bundled for tests, with its proven optimal tour length and an optimal
+/// tour, as a `.opt.tour` file.
+pub struct KnownInstance {
+    pub name: &'static str,
+    pub text: &'static str,
+    pub optimum: i32,
+    pub opt_tour: &'static str,
+}
+
+pub const INSTANCES: &[KnownInstance] = &[
+    KnownInstance {
+        name: "burma14",
+        text: include_str!("../../data/tsplib/burma14.tsp"),
+        optimum: 3323,
+        opt_tour: include_str!("../../data/tsplib/burma14.opt.tour"),
+    },
+    KnownInstance {
+        name: "ulysses16",
+        text: include_str!("../../data/tsplib/ulysses16.tsp"),
+        optimum: 6859,
+        opt_tour: include_str!("../../data/tsplib/ulysses16.opt.tour"),
+    },
+    KnownInstance {
+        name: "ulysses22",
+        text: include_str!("../../data/tsplib/ulysses22.tsp"),
+        optimum: 7013,
+        opt_tour: include_str!("../../data/tsplib/ulysses22.opt.tour"),
+    },
+    KnownInstance {
+        name: "berlin52",
+        text: include_str!("../../data/tsplib/berlin52.tsp"),
+        optimum: 7542,
+        opt_tour: include_str!("../../data/tsplib/berlin52.opt.tour"),
+    },
+];
+
+impl KnownInstance {
+    pub fn instance(&self) -> Instance {
+        Tsplib::parse(self.text).unwrap().into_instance()
+    }
+
+    pub fn optimal_route(&self) -> Route {
+        let path = TsplibTour::parse(self.opt_tour).unwrap().path;
+        Route::new(self.gls().cost(&path), path)
+    }
+
+    pub fn gls(&self) -> GuidedLocalSearch {
+        GuidedLocalSearch::from_instance(&self.instance())
+    }
+
+    /// Relative excess of `cost` over the optimum, `0.01` being 1% above it.
+    pub fn gap(&self, cost: i32) -> f64 {
+        (cost - self.optimum) as f64 / self.optimum as f64
+    }
+
+    /// Panics if `cost` is below the optimum or more than `max_gap` above it.
+    pub fn assert_within(&self, cost: i32, max_gap: f64) {
+        let gap = self.gap(cost);
+        assert!(gap >= 0.0, "{}: cost {} below the optimum {}", self.name, cost, self.optimum);
+        assert!(
+            gap <= max_gap,
+            "{}: cost {} is {:.2}% above the optimum {}, more than {:.2}%",
+            self.name, cost, 100.0 * gap, self.optimum, 100.0 * max_gap
+        );
+    }
+}
+
+/// Declare a test running `$solve` on every known instance and checking the cost of the
+/// route against the optimum, e.g. `within_gap!(solve, 0.02, |gls| gls.solve(666, 50));`.
+macro_rules! within_gap {
+    ($name:ident, $max_gap:expr, |$gls:ident| $solve:expr) => {
+        #[test]
+        fn $name() {
+            for known in crate::types::instances::INSTANCES {
+                let $gls = known.gls();
+                let route = $solve;
+
+                assert!(route.path.is_hamiltonian(), "{}", known.name);
+                assert_eq!(route.cost, $gls.cost(&route.path), "{}", known.name);
+                known.assert_within(route.cost, $max_gap);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::check_golden;
+    use crate::types::anytime::Budget;
+    use crate::types::batch::solve_batch;
+    use crate::types::config::GlsConfig;
+    use crate::types::instances::INSTANCES;
+    use crate::types::tsplib::Tsplib;
+
+    #[test]
+    fn parse() {
+        for known in INSTANCES {
+            let tsplib = Tsplib::parse(known.text).unwrap();
+            assert_eq!(tsplib.name, known.name);
+        }
+    }
+
+    #[test]
+    fn optimal_tours() {
+        for known in INSTANCES {
+            let optimal = known.optimal_route();
+            assert_eq!(optimal.cost, known.optimum, "{}", known.name);
+
+            let report = known.gls().solve_anytime(666, Budget::Steps(20)).with_optimum(known.optimum);
+            assert_eq!(report.optimality_gap, Some(report.route.gap_to(&optimal)));
+            assert!(report.optimality_gap.unwrap() >= 0.0, "{}", known.name);
+        }
+    }
+
+    within_gap!(solve, 0.03, |gls| gls.solve(666, 100));
+    // Not reproducible, hence the wider gap
+    within_gap!(solve_cooperative, 0.05, |gls| gls.solve_cooperative(&[1, 2, 3, 4], 100, 10));
+    within_gap!(solve_with_relinking, 0.03, |gls| gls.solve_with_relinking(666, 100, 4));
+
+    macro_rules! preset {
+        ($name:ident, $max_gap:expr) => {
+            within_gap!($name, $max_gap, |gls| {
+                let config = GlsConfig::$name();
+                gls.clone().with_config(&config).solve(config.seed, config.steps)
+            });
+        };
+    }
+
+    // Gaps of the bundled instances with the seed of the presets
+    preset!(fast, 0.06);
+    preset!(balanced, 0.01);
+    preset!(quality, 0.0);
+
+    #[test]
+    fn golden() {
+        let config = GlsConfig::balanced();
+        for known in INSTANCES {
+            let route = known.gls().with_config(&config).solve(config.seed, config.steps);
+            let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/data/golden");
+            assert_eq!(check_golden(dir, known.name, "balanced", config.seed, &config, &route), Ok(()), "{}", known.name);
+        }
+    }
+
+    #[test]
+    fn batch() {
+        let instances: Vec<_> = INSTANCES.iter().map(|known| known.instance()).collect();
+        let config = GlsConfig::default();
+
+        for (known, route) in INSTANCES.iter().zip(solve_batch(&instances, &config)) {
+            known.assert_within(route.cost, 0.03);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::types::edge::Edge;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::memory::{self, MemoryFileError, SearchMemory};
+use crate::types::observer::Progress;
+use crate::types::path::Path;
+use crate::types::route::Route;
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+
+/// How many of the recorded tours contain each edge.
+pub struct EdgeFrequencies {
+    counts: SymmetricMatrix,
+    samples: usize,
+}
+
+impl EdgeFrequencies {
+    pub fn new(size: usize) -> Self {
+        Self { counts: SymmetricMatrix::from_size(size), samples: 0 }
+    }
+
+    pub fn record(&mut self, path: &Path) {
+        assert_eq!(path.len(), self.counts.size());
+        for (a, b) in path.edges() {
+            self.counts.inc(a, b, 1);
+        }
+        self.samples += 1;
+    }
+
+    /// Number of recorded tours.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    pub fn count(&self, a: usize, b: usize) -> i32 {
+        self.counts[(a, b)]
+    }
+
+    /// Share of the recorded tours containing the edge, 0 if none was recorded.
+    pub fn frequency(&self, a: usize, b: usize) -> f64 {
+        if self.samples == 0 { 0.0 } else { self.count(a, b) as f64 / self.samples as f64 }
+    }
+
+    /// Edges found in at least one tour, the most frequent first.
+    pub fn edges(&self) -> Vec<(Edge, i32)> {
+        let mut res: Vec<_> = self.counts.pairs()
+            .map(|(a, b)| (Edge::new(a, b), self.counts[(a, b)]))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        res.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        res
+    }
+}
+
+impl SearchMemory for EdgeFrequencies {
+    fn size(&self) -> usize {
+        self.counts.size()
+    }
+
+    /// Number of recorded tours containing the edge.
+    fn value(&self, edge: Edge) -> i32 {
+        let (a, b) = edge.endpoints();
+        self.count(a, b)
+    }
+
+    /// Record the tours of `other` too.
+    fn merge(&mut self, other: &Self) {
+        assert_eq!(other.counts.size(), self.counts.size());
+        for (a, b) in other.counts.pairs() {
+            self.counts.inc(a, b, other.counts[(a, b)]);
+        }
+        self.samples += other.samples;
+    }
+
+    fn write_memory(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut entries = self.edges();
+        entries.sort();
+        memory::write_data(b'F', self.counts.size(), self.samples as u64, entries.into_iter(), writer)
+    }
+
+    fn read_memory(reader: &mut impl Read, size: usize) -> Result<Self, MemoryFileError> {
+        let data = memory::read_data(b'F', size, reader)?;
+        let mut res = Self::new(data.size);
+        for (edge, count) in data.entries {
+            let (a, b) = edge.endpoints();
+            res.counts.set(a, b, count);
+        }
+        res.samples = usize::try_from(data.samples).map_err(|_| MemoryFileError::Overflow)?;
+        Ok(res)
+    }
+}
+
+impl GuidedLocalSearch {
+    /// Nearest neighbor construction where the length of each edge is reduced by `bonus` times
+    /// its frequency in `frequencies`, favoring edges often found in good tours.
+    pub fn nearest_neighbor_biased(&self, frequencies: &EdgeFrequencies, bonus: i32) -> Route {
+        let distances = self.distances();
+        let samples = frequencies.samples().max(1) as i64;
+        self.nearest_neighbor_by(&mut Vec::new(), |a, b| {
+            let bonus = bonus as i64 * frequencies.count(a.index(), b.index()) as i64 / samples;
+            (distances[(a, b)] as i64 - bonus) as i32
+        })
+    }
+
+    /// Multi-start GLS: `restarts` runs of `steps` steps, with the seeds `seed`, `seed + 1`...
+    /// Returns the best route and the frequencies of the edges of the routes found by each run.
+    ///
+    /// The first run starts from the nearest neighbor tour, the following ones from
+    /// `nearest_neighbor_biased` with the frequencies learned so far. A zero `bonus` makes all
+    /// the runs start from the same tour, only their neighborhood order differing.
+    pub fn solve_learning(&self, seed: u64, restarts: usize, steps: usize, bonus: i32) -> (Route, EdgeFrequencies) {
+        assert!(restarts > 0);
+
+        let mut frequencies = EdgeFrequencies::new(self.distances().size());
+        let mut workspace = SolverWorkspace::new();
+        let mut best: Option<Route> = None;
+
+        for restart in 0..restarts as u64 {
+            let initial = self.nearest_neighbor_biased(&frequencies, bonus);
+            let seed = seed.wrapping_add(restart);
+            let route = self.search_from(initial, seed, steps, None, &mut |_: &Progress| {}, &mut workspace);
+
+            frequencies.record(&route.path);
+            if best.as_ref().is_none_or(|best| route.cost < best.cost) {
+                best = Some(route);
+            }
+        }
+
+        (best.unwrap(), frequencies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scattered_points;
+    use crate::types::edge::Edge;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::learning::EdgeFrequencies;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+
+    fn create_gls() -> GuidedLocalSearch {
+        let points = scattered_points(60);
+        GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points))
+    }
+
+    #[test]
+    fn frequencies() {
+        let mut frequencies = EdgeFrequencies::new(4);
+        assert_eq!(frequencies.frequency(0, 1), 0.0);
+
+        frequencies.record(&Path::new(vec![0, 1, 2, 3]));
+        frequencies.record(&Path::new(vec![0, 2, 1, 3]));
+
+        assert_eq!(frequencies.samples(), 2);
+        assert_eq!(frequencies.count(2, 1), 2);
+        assert_eq!(frequencies.frequency(0, 1), 0.5);
+        // Ties broken by edge
+        assert_eq!(&frequencies.edges()[..3], &[(Edge::new(0, 3), 2), (Edge::new(1, 2), 2), (Edge::new(0, 1), 1)]);
+        assert_eq!(frequencies.edges().len(), 6);
+    }
+
+    #[test]
+    fn biased_construction() {
+        let gls = create_gls();
+        let frequencies = EdgeFrequencies::new(60);
+
+        // Nothing learned yet
+        assert_eq!(gls.nearest_neighbor_biased(&frequencies, 1000), gls.nearest_neighbor());
+
+        // With a huge bonus, the construction follows the recorded tour
+        let mut frequencies = EdgeFrequencies::new(60);
+        let tour = gls.solve(666, 5).path;
+        frequencies.record(&tour);
+        let actual = gls.nearest_neighbor_biased(&frequencies, 1_000_000);
+        assert_eq!(actual.path.common_edges(&tour), 60);
+    }
+
+    #[test]
+    fn solve_learning() {
+        let gls = create_gls();
+        let (route, frequencies) = gls.solve_learning(666, 4, 10, 50);
+
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, gls.cost(&route.path));
+        assert_eq!(frequencies.samples(), 4);
+        assert!(route.cost <= gls.solve(666, 10).cost);
+    }
+}
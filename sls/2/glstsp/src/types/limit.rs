@@ -0,0 +1,166 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+/// Upper bound on the tour length, e.g. the range of a vehicle.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct LengthLimit {
+    pub max: i32,
+    pub mode: LimitMode,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum LimitMode {
+    /// Reject the moves taking the tour above `max`. A tour already above it may still shorten.
+    Hard,
+    /// Add `weight` per unit above `max` to the augmented cost, so the search may cross the
+    /// limit when nothing shorter is found.
+    Soft { weight: i32 },
+}
+
+/// Best tour of `GuidedLocalSearch::solve_limited` and how much it exceeds the limit.
+#[derive(Clone, Debug)]
+pub struct LimitedRoute {
+    pub route: Route,
+    /// Length above `max`, 0 when the limit is met.
+    pub violation: i32,
+}
+
+impl LengthLimit {
+    pub fn violation(&self, cost: i32) -> i32 {
+        (cost - self.max).max(0)
+    }
+
+    /// Key to minimize among tours: feasibility first in hard mode, the penalized length in
+    /// soft mode.
+    fn rank(&self, cost: i32) -> (i64, i64) {
+        let violation = self.violation(cost) as i64;
+        match self.mode {
+            LimitMode::Hard => (violation, cost as i64),
+            LimitMode::Soft { weight } => (cost as i64 + weight as i64 * violation, 0),
+        }
+    }
+
+    /// Whether a move changing the length from `cost` by `delta` and the augmented cost by
+    /// `augmented` improves the constrained objective.
+    fn accepts(&self, cost: i32, delta: i32, augmented: i32) -> bool {
+        match self.mode {
+            LimitMode::Hard => augmented < 0 && (cost + delta <= self.max || delta <= 0),
+            LimitMode::Soft { weight } => {
+                let overload = self.violation(cost + delta) as i64 - self.violation(cost) as i64;
+                augmented as i64 + weight as i64 * overload < 0
+            },
+        }
+    }
+}
+
+impl GuidedLocalSearch {
+    /// GLS keeping the tour length under `limit`, the best tour being the shortest feasible
+    /// one in hard mode and the one of least penalized length in soft mode.
+    ///
+    /// The overload is not a sum over the edges, so the local search evaluates the 2-opt moves
+    /// one by one instead of using the fast scan of `local_search`.
+    pub fn solve_limited(&self, limit: LengthLimit, seed: u64, steps: usize) -> LimitedRoute {
+        let size = self.distances().size();
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = self.neighborhood(&mut rng);
+        let mut penalties = Penalties::from_size(size);
+
+        let mut path = self.nearest_neighbor().path;
+        let mut cost = self.cost(&path);
+        self.limited_local_search(&mut path, &mut cost, &neighborhood, 0, &penalties, limit);
+        let mut best = Route::new(cost, path.clone());
+        let penalty_factor = self.penalty_factor(cost);
+
+        for _ in 0..steps {
+            self.penalize(&path, &mut penalties, &mut rng);
+            self.limited_local_search(&mut path, &mut cost, &neighborhood, penalty_factor, &penalties, limit);
+
+            if limit.rank(cost) < limit.rank(best.cost) {
+                best = Route::new(cost, path.clone());
+            }
+        }
+
+        self.limited_local_search(&mut best.path, &mut best.cost, &neighborhood, 0, &penalties, limit);
+        LimitedRoute { violation: limit.violation(best.cost), route: best }
+    }
+
+    /// First improvement 2-opt under `limit`, `cost` being the length of `path`, kept up to date.
+    fn limited_local_search(
+        &self,
+        path: &mut Path,
+        cost: &mut i32,
+        neighborhood: &Path,
+        penalty_factor: i32,
+        penalties: &Penalties,
+        limit: LengthLimit)
+    {
+        let size = path.len();
+        let distances = self.distances();
+        let augmented = |a: usize, b: usize| distances[(a, b)] + penalty_factor * penalties[(a, b)];
+
+        'outer: loop {
+            for &i in &neighborhood.0 {
+                let (a, a_next) = path.edge_at(i);
+                for j in 0..size {
+                    if j == i || (j + 1) % size == i || (i + 1) % size == j {
+                        continue;
+                    }
+                    let (b, b_next) = path.edge_at(j);
+
+                    let delta = distances[(a, b)] + distances[(a_next, b_next)]
+                        - distances[(a, a_next)] - distances[(b, b_next)];
+                    let augmented_delta = augmented(a, b) + augmented(a_next, b_next)
+                        - augmented(a, a_next) - augmented(b, b_next);
+
+                    if limit.accepts(*cost, delta, augmented_delta) {
+                        path.twist((i + 1) % size, j);
+                        *cost += delta;
+                        continue 'outer;
+                    }
+                }
+            }
+
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::limit::{LengthLimit, LimitMode};
+
+    #[test]
+    fn accepts() {
+        let hard = LengthLimit { max: 100, mode: LimitMode::Hard };
+        assert!(hard.accepts(90, 10, -1));
+        assert!(!hard.accepts(90, 11, -1));
+        assert!(hard.accepts(120, -5, -1));
+        assert!(!hard.accepts(90, -5, 0));
+
+        let soft = LengthLimit { max: 100, mode: LimitMode::Soft { weight: 3 } };
+        assert!(soft.accepts(90, 10, -1));
+        assert!(!soft.accepts(90, 12, -5));
+        assert!(soft.accepts(110, -5, 10));
+    }
+
+    #[test]
+    fn solve_limited() {
+        let gls = random_gls(60);
+        let free = gls.solve(666, 20).cost;
+
+        for mode in [LimitMode::Hard, LimitMode::Soft { weight: 10 }] {
+            let feasible = gls.solve_limited(LengthLimit { max: free * 11 / 10, mode }, 666, 20);
+            assert!(feasible.route.path.is_hamiltonian());
+            assert_eq!(feasible.route.cost, gls.cost(&feasible.route.path));
+            assert_eq!(feasible.violation, 0);
+
+            let tight = gls.solve_limited(LengthLimit { max: free / 2, mode }, 666, 20);
+            assert_eq!(tight.violation, tight.route.cost - free / 2);
+        }
+    }
+}
@@ -1,12 +1,31 @@
 use crate::types::point::Point;
 use std::ops::Index;
-use std::fmt::{Display, Formatter};
-use std::fmt;
+use std::fmt::{Alignment, Debug, Display, Formatter};
+use std::{fmt, iter, mem};
+use rand_mt::Mt64;
+use rand::{SeedableRng, Rng};
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct SymmetricMatrix {
-    size: usize,
-    data: Vec<i32>,
+    pub(crate) size: usize,
+    pub(crate) data: Vec<i32>,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum Violation {
+    Negative { i: usize, j: usize, value: i32 },
+    NonZeroDiagonal { i: usize, value: i32 },
+    /// `d(i, j) > d(i, k) + d(k, j)`
+    Triangle { i: usize, j: usize, k: usize },
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TriangleCheck {
+    Skip,
+    /// Check every triple, O(n³).
+    Exhaustive,
+    /// Check `samples` random triples.
+    Sampled { samples: usize, seed: u64 },
 }
 
 impl SymmetricMatrix {
@@ -31,13 +50,38 @@ impl SymmetricMatrix {
         res
     }
 
+    /// Matrix of the costs given by `cost(i, j)`, called once for each pair `i < j`.
+    ///
+    /// Costs that don't come from coordinates (turn penalties, business rules...) can be
+    /// computed on the fly this way, the solver only reading them from the matrix.
+    pub fn from_fn(size: usize, mut cost: impl FnMut(usize, usize) -> i32) -> Self {
+        let mut res = Self::from_size(size);
+        for i in 0..size {
+            for j in i + 1..size {
+                res.set(i, j, cost(i, j));
+            }
+        }
+        res
+    }
+
     #[inline]
     fn get_index(&self, x: usize, y: usize) -> usize {
-        debug_assert!(x < self.size);
-        debug_assert!(y < self.size);
+        if cfg!(feature = "checked-index") {
+            assert!(x < self.size && y < self.size, "index ({}, {}) out of range for size {}", x, y, self.size);
+        } else {
+            debug_assert!(x < self.size);
+            debug_assert!(y < self.size);
+        }
         x * self.size + y
     }
 
+    /// Resize to `size` and fill with zeros, reusing the allocation.
+    pub(crate) fn reset(&mut self, size: usize) {
+        self.size = size;
+        self.data.clear();
+        self.data.resize(size * size, 0);
+    }
+
     pub fn set(&mut self, x: usize, y: usize, value: i32) {
         let ia = self.get_index(x, y);
         self.data[ia] = value;
@@ -60,45 +104,226 @@ impl SymmetricMatrix {
             .map(|edge| self[edge])
             .sum()
     }
+
+    /// Iterator over the indexes above the diagonal, each unordered pair once.
+    pub fn pairs(&self) -> impl Iterator<Item=(usize, usize)> {
+        let size = self.size;
+        (0..size).flat_map(move |i| (i + 1..size).map(move |j| (i, j)))
+    }
+
+    /// Values from `i` to every index, as a contiguous slice. Scanning a row this way skips the
+    /// bounds check of each `Index` access, so loops over it vectorize.
+    #[inline]
+    pub fn row(&self, i: usize) -> &[i32] {
+        let start = self.get_index(i, 0);
+        &self.data[start..start + self.size]
+    }
+
+    /// Apply `f` to every value outside the diagonal, which is kept as is.
+    ///
+    /// `f` is called once per unordered pair, so the result stays symmetric even if `f` is not
+    /// a pure function (e.g. noise injection).
+    pub fn map(&self, mut f: impl FnMut(i32) -> i32) -> Self {
+        let mut res = Self::from_size(self.size);
+        for i in 0..self.size {
+            res.set(i, i, self[(i, i)]);
+        }
+        for (i, j) in self.pairs() {
+            res.set(i, j, f(self[(i, j)]));
+        }
+        res
+    }
+
+    /// Multiply every value by `factor`, truncating the result.
+    pub fn scale(&self, factor: f64) -> Self {
+        self.map(|value| (value as f64 * factor) as i32)
+    }
+
+    /// Element-wise sum of two matrices of the same size, saturating at the bounds of `i32`.
+    pub fn add(&self, other: &Self) -> Self {
+        assert_eq!(self.size, other.size);
+
+        let data = self.data.iter()
+            .zip(other.data.iter())
+            .map(|(a, b)| a.saturating_add(*b))
+            .collect();
+        Self { size: self.size, data }
+    }
+
+    /// Check that the matrix is a sane distance matrix: no negative values, a zero diagonal
+    /// and, depending on `triangle`, the triangle inequality.
+    pub fn validate(&self, triangle: TriangleCheck) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for i in 0..self.size {
+            let value = self[(i, i)];
+            if value != 0 {
+                violations.push(Violation::NonZeroDiagonal { i, value });
+            }
+        }
+
+        for (i, j) in self.pairs() {
+            let value = self[(i, j)];
+            if value < 0 {
+                violations.push(Violation::Negative { i, j, value });
+            }
+        }
+
+        let mut check_triangle = |i: usize, j: usize, k: usize| {
+            if self[(i, j)] as i64 > self[(i, k)] as i64 + self[(k, j)] as i64 {
+                violations.push(Violation::Triangle { i, j, k });
+            }
+        };
+
+        match triangle {
+            TriangleCheck::Skip => {}
+            TriangleCheck::Exhaustive => {
+                for (i, j) in self.pairs() {
+                    for k in (0..self.size).filter(|&k| k != i && k != j) {
+                        check_triangle(i, j, k);
+                    }
+                }
+            }
+            TriangleCheck::Sampled { samples, seed } => {
+                if self.size >= 3 {
+                    let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+                    for _ in 0..samples {
+                        let i = rng.gen_range(0..self.size);
+                        let j = rng.gen_range(0..self.size);
+                        let k = rng.gen_range(0..self.size);
+                        if i != j && j != k && k != i {
+                            check_triangle(i.min(j), i.max(j), k);
+                        }
+                    }
+                }
+            }
+        }
+
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+
+    /// Minimum value outside the diagonal, `None` if there is less than two vertices.
+    pub fn min(&self) -> Option<i32> {
+        self.pairs().map(|e| self[e]).min()
+    }
+
+    /// Maximum value outside the diagonal, `None` if there is less than two vertices.
+    pub fn max(&self) -> Option<i32> {
+        self.pairs().map(|e| self[e]).max()
+    }
+
+    /// Mean value outside the diagonal, `None` if there is less than two vertices.
+    pub fn mean(&self) -> Option<f64> {
+        let count = self.size * self.size.saturating_sub(1) / 2;
+        if count == 0 {
+            return None;
+        }
+
+        let sum: i64 = self.pairs().map(|e| self[e] as i64).sum();
+        Some(sum as f64 / count as f64)
+    }
+
+    /// The first `rows` rows and columns as a table with the indexes in the margins, and the
+    /// number of rows left out, e.g. to look at a matrix too large to print.
+    pub fn preview(&self, rows: usize) -> String {
+        let rows = rows.min(self.size);
+        let width = (0..rows)
+            .flat_map(|i| self.row(i)[..rows].iter().map(|v| v.to_string().len()))
+            .chain(iter::once(rows.saturating_sub(1).to_string().len()))
+            .max()
+            .unwrap();
+
+        let mut res = format!("{:>w$} |", "", w = width);
+        for j in 0..rows {
+            res += &format!(" {:>w$}", j, w = width);
+        }
+        res += &format!("\n{}", "-".repeat((width + 1) * (rows + 1) + 1));
+        for i in 0..rows {
+            res += &format!("\n{:>w$} |", i, w = width);
+            for value in &self.row(i)[..rows] {
+                res += &format!(" {:>w$}", value, w = width);
+            }
+        }
+        if rows < self.size {
+            res += &format!("\n({} of {} rows)", rows, self.size);
+        }
+        res
+    }
 }
 
 impl Index<(usize, usize)> for SymmetricMatrix {
     type Output = i32;
 
+    #[cfg(feature = "checked-index")]
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (x, y) = index;
+        &self.data[self.get_index(x, y)]
+    }
+
+    #[cfg(not(feature = "checked-index"))]
     fn index(&self, index: (usize, usize)) -> &Self::Output {
         let (x, y) = index;
         let index = self.get_index(x, y);
+        // SAFETY: the solver only indexes with vertices of Hamiltonian paths of the matrix size,
+        // checked in `GuidedLocalSearch::local_search` (and `Path::try_new` for external tours),
+        // so `x, y < size`. Opting out of `checked-index` extends this contract to every caller.
         unsafe { self.data.get_unchecked(index) }
     }
 }
 
+/// Every row on its own line, the values separated by spaces. The values are padded to the
+/// width of the format (`{:5}`), by default the widest value, with its fill and alignment
+/// (right by default, `{:<}`, `{:^}`).
+///
+/// Prints `size²` values, see `preview` for large matrices.
 impl Display for SymmetricMatrix {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let precision = f.precision().unwrap_or(self.size);
-
-        let precision_fmt = if precision == self.size {
-            ":     ".to_owned()
-        } else {
-            format!(" (precision {}):\n              ", precision)
-        };
-
-        write!(f, "SymmetricMatrix: {{\n    size: {},\n    data{}", self.size, precision_fmt)?;
-
-        for i in 0..precision { write!(f, "{:>3} ", i)?; }
-        write!(f, "\n             ")?;
-        for _ in 0..precision { write!(f, "----")?; }
-        writeln!(f)?;
-
-        for i in 0..precision {
-            write!(f, "        {:>3} | ", i)?;
-            for j in 0..precision {
-                write!(f, "{:>3} ", self[(i, j)])?;
+        let width = f.width().unwrap_or_else(|| self.data.iter().map(|v| v.to_string().len()).max().unwrap_or(0));
+        for i in 0..self.size {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, value) in self.row(i).iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                pad(f, &value.to_string(), width)?;
             }
-            writeln!(f)?;
         }
+        Ok(())
+    }
+}
+
+/// Size, memory and range of the values, without the values themselves. See `Display` or
+/// `preview` for those.
+impl Debug for SymmetricMatrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SymmetricMatrix")
+            .field("size", &self.size)
+            .field("bytes", &(self.data.len() * mem::size_of::<i32>()))
+            .field("min", &self.min())
+            .field("max", &self.max())
+            .finish()
+    }
+}
 
-        write!(f, "}}")
+/// Write `text` padded to `width` with the fill and alignment of `f`, right by default.
+fn pad(f: &mut Formatter<'_>, text: &str, width: usize) -> fmt::Result {
+    let padding = width.saturating_sub(text.chars().count());
+    let (before, after) = match f.align() {
+        Some(Alignment::Left) => (0, padding),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(Alignment::Right) | None => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        write!(f, "{}", fill)?;
+    }
+    write!(f, "{}", text)?;
+    for _ in 0..after {
+        write!(f, "{}", fill)?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -106,6 +331,15 @@ mod tests {
     use crate::types::matrix::SymmetricMatrix;
     use crate::types::point::Point;
 
+    #[test]
+    #[cfg(feature = "checked-index")]
+    #[should_panic(expected = "out of range")]
+    fn checked_index() {
+        // (0, 5) would land on (1, 2) without checking the column
+        let matrix = SymmetricMatrix::from_size(3);
+        let _ = matrix[(0, 5)];
+    }
+
     fn create_matrix() -> SymmetricMatrix {
         let points = vec![
             Point::new(2.83000e+03 as i32, 4.00000e+01 as i32),
@@ -134,6 +368,27 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod from_fn {
+        use crate::types::matrix::SymmetricMatrix;
+        use crate::types::matrix::tests::create_matrix;
+
+        #[test]
+        fn test() {
+            let expected = create_matrix();
+            let actual = SymmetricMatrix::from_fn(10, |i, j| expected[(i, j)]);
+            assert_eq!(actual.data, expected.data);
+
+            let mut calls = 0;
+            SymmetricMatrix::from_fn(10, |i, j| {
+                assert!(i < j);
+                calls += 1;
+                0
+            });
+            assert_eq!(calls, 45);
+        }
+    }
+
     #[cfg(test)]
     mod create {
         use crate::types::matrix::tests::create_matrix;
@@ -292,4 +547,151 @@ mod tests {
             assert_eq!(actual, 18);
         }
     }
+    #[cfg(test)]
+    mod transforms {
+        use crate::types::matrix::tests::simple_matrix;
+        use crate::types::matrix::SymmetricMatrix;
+
+        #[test]
+        fn pairs() {
+            let graph = simple_matrix();
+            let expected = vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+            itertools::assert_equal(graph.pairs(), expected);
+        }
+
+        #[test]
+        fn row() {
+            let graph = simple_matrix();
+            assert_eq!(graph.row(0), &[0, 1, 2, 5]);
+            assert_eq!(graph.row(3), &[5, 4, 1, 0]);
+        }
+
+        #[test]
+        fn map() {
+            let mut calls = 0;
+            let actual = simple_matrix().map(|v| {
+                calls += 1;
+                v + calls
+            });
+
+            assert_eq!(calls, 6);
+            assert_eq!(actual.data, vec![
+                0, 2, 4, 8,
+                2, 0, 11, 9,
+                4, 11, 0, 7,
+                8, 9, 7, 0,
+            ]);
+        }
+
+        #[test]
+        fn scale() {
+            let actual = simple_matrix().scale(1.5);
+            assert_eq!(actual.data, vec![
+                0, 1, 3, 7,
+                1, 0, 10, 6,
+                3, 10, 0, 1,
+                7, 6, 1, 0,
+            ]);
+        }
+
+        #[test]
+        fn add() {
+            let actual = simple_matrix().add(&simple_matrix());
+            assert_eq!(actual.data, simple_matrix().scale(2.0).data);
+
+            let max = simple_matrix().map(|_| i32::MAX);
+            assert_eq!(max.add(&simple_matrix()), max);
+        }
+
+        #[test]
+        fn stats() {
+            let graph = simple_matrix();
+            assert_eq!(graph.min(), Some(1));
+            assert_eq!(graph.max(), Some(7));
+            assert_eq!(graph.mean(), Some(20.0 / 6.0));
+        }
+
+        #[test]
+        fn stats_single() {
+            let graph = SymmetricMatrix::from_size(1);
+            assert_eq!(graph.min(), None);
+            assert_eq!(graph.max(), None);
+            assert_eq!(graph.mean(), None);
+        }
+    }
+    #[cfg(test)]
+    mod validate {
+        use crate::types::matrix::tests::{simple_matrix, create_matrix};
+        use crate::types::matrix::{SymmetricMatrix, TriangleCheck, Violation};
+
+        #[test]
+        fn valid() {
+            let graph = create_matrix();
+            assert_eq!(graph.validate(TriangleCheck::Exhaustive), Ok(()));
+            assert_eq!(graph.validate(TriangleCheck::Sampled { samples: 100, seed: 666 }), Ok(()));
+        }
+
+        #[test]
+        fn negative_and_diagonal() {
+            let mut graph = SymmetricMatrix::from_size(3);
+            graph.set(0, 1, -2);
+            graph.set(2, 2, 3);
+
+            let expected = vec![
+                Violation::NonZeroDiagonal { i: 2, value: 3 },
+                Violation::Negative { i: 0, j: 1, value: -2 },
+            ];
+            assert_eq!(graph.validate(TriangleCheck::Skip), Err(expected));
+        }
+
+        #[test]
+        fn triangle() {
+            // d(1, 2) = 7 > d(1, 0) + d(0, 2) = 3
+            let graph = simple_matrix();
+
+            let expected = vec![
+                Violation::Triangle { i: 0, j: 3, k: 2 },
+                Violation::Triangle { i: 1, j: 2, k: 0 },
+                Violation::Triangle { i: 1, j: 2, k: 3 },
+            ];
+            assert_eq!(graph.validate(TriangleCheck::Skip), Ok(()));
+            assert_eq!(graph.validate(TriangleCheck::Exhaustive), Err(expected));
+        }
+
+        #[test]
+        fn triangle_sampled() {
+            let graph = simple_matrix();
+            let actual = graph.validate(TriangleCheck::Sampled { samples: 1000, seed: 666 });
+
+            let violations = actual.unwrap_err();
+            assert!(violations.contains(&Violation::Triangle { i: 1, j: 2, k: 0 }));
+            assert!(violations.iter().all(|v| matches!(v, Violation::Triangle { .. })));
+        }
+    }
+
+    #[cfg(test)]
+    mod format {
+        use crate::types::matrix::tests::{create_matrix, simple_matrix};
+
+        #[test]
+        fn display() {
+            let matrix = simple_matrix();
+            assert_eq!(format!("{}", matrix), "0 1 2 5\n1 0 7 4\n2 7 0 1\n5 4 1 0");
+            assert_eq!(format!("{:2}", matrix).lines().next(), Some(" 0  1  2  5"));
+            assert_eq!(format!("{:*<3}", matrix).lines().nth(1), Some("1** 0** 7** 4**"));
+        }
+
+        #[test]
+        fn debug() {
+            let actual = format!("{:?}", create_matrix());
+            assert_eq!(actual, "SymmetricMatrix { size: 10, bytes: 400, min: Some(37), max: Some(344) }");
+        }
+
+        #[test]
+        fn preview() {
+            let expected = "   |  0  1  2\n-------------\n 0 |  0 37 74\n 1 | 37  0 37\n 2 | 74 37  0\n(3 of 10 rows)";
+            assert_eq!(create_matrix().preview(3), expected);
+            assert_eq!(simple_matrix().preview(10).lines().count(), 6);
+        }
+    }
 }
@@ -0,0 +1,218 @@
+use crate::types::edge::Edge;
+use std::convert::TryFrom;
+use std::io;
+use std::io::{Read, Write};
+
+const MAGIC: &[u8; 4] = b"GLSE";
+const VERSION: u8 = 1;
+/// Bytes of an entry: both endpoints as `u32` and the value as `i32`.
+const ENTRY_LEN: usize = 12;
+
+/// What a search learned about the edges of an instance, e.g. the penalties of GLS or the
+/// frequencies of the edges in good tours, in a form other strategies can take in: hybrid
+/// ones in the same process, or distributed ones through the binary format.
+pub trait SearchMemory: Sized {
+    /// Number of vertices of the instance.
+    fn size(&self) -> usize;
+
+    /// Value learned for `edge`, 0 when nothing was.
+    fn value(&self, edge: Edge) -> i32;
+
+    /// Add what `other` learned on the same instance, e.g. in another thread or process.
+    ///
+    /// Panics if the sizes differ.
+    fn merge(&mut self, other: &Self);
+
+    /// Write the memory in a binary format.
+    ///
+    /// After a header (magic `GLSE`, format version, kind of memory as a byte, size and number
+    /// of samples as little-endian `u64`, number of entries as a little-endian `u64`) come the
+    /// edges with a nonzero value, each as its two endpoints in little-endian `u32` and its
+    /// value in little-endian `i32`.
+    fn write_memory(&self, writer: &mut impl Write) -> io::Result<()>;
+
+    /// Memory written by `write_memory` for the same type, on an instance of `size` vertices.
+    /// The size is checked against the header before anything is allocated.
+    fn read_memory(reader: &mut impl Read, size: usize) -> Result<Self, MemoryFileError>;
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum MemoryFileError {
+    Io(io::ErrorKind),
+    /// Not a memory file.
+    Magic,
+    UnsupportedVersion(u8),
+    /// Memory of another type, by its kind byte.
+    Kind(u8),
+    /// The data ends before the last entry, or continues after it.
+    Truncated,
+    /// The size doesn't fit in memory.
+    Overflow,
+    /// Memory of an instance of another size, given by the header.
+    Size(u64),
+    /// An entry is a loop or has an endpoint out of range.
+    InvalidEdge(usize, usize),
+}
+
+/// Content of a memory file.
+pub(crate) struct MemoryData {
+    pub size: usize,
+    /// Tours or runs the values come from, for the memories that count them.
+    pub samples: u64,
+    pub entries: Vec<(Edge, i32)>,
+}
+
+pub(crate) fn write_data(
+    kind: u8,
+    size: usize,
+    samples: u64,
+    entries: impl Iterator<Item=(Edge, i32)>,
+    writer: &mut impl Write) -> io::Result<()>
+{
+    let mut body = Vec::new();
+    let mut count = 0u64;
+    for (edge, value) in entries {
+        let (a, b) = edge.endpoints();
+        body.extend_from_slice(&(a as u32).to_le_bytes());
+        body.extend_from_slice(&(b as u32).to_le_bytes());
+        body.extend_from_slice(&value.to_le_bytes());
+        count += 1;
+    }
+
+    let mut buffer = Vec::with_capacity(MAGIC.len() + 2 + 3 * 8 + body.len());
+    buffer.extend_from_slice(MAGIC);
+    buffer.push(VERSION);
+    buffer.push(kind);
+    buffer.extend_from_slice(&(size as u64).to_le_bytes());
+    buffer.extend_from_slice(&samples.to_le_bytes());
+    buffer.extend_from_slice(&count.to_le_bytes());
+    buffer.extend_from_slice(&body);
+    writer.write_all(&buffer)
+}
+
+/// Content of a memory file of `kind` for an instance of `size` vertices.
+pub(crate) fn read_data(kind: u8, size: usize, reader: &mut impl Read) -> Result<MemoryData, MemoryFileError> {
+    // The memories are matrices of `size * size` values
+    size.checked_mul(size).ok_or(MemoryFileError::Overflow)?;
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).map_err(|e| MemoryFileError::Io(e.kind()))?;
+
+    if data.len() < MAGIC.len() + 1 || &data[..MAGIC.len()] != MAGIC {
+        return Err(MemoryFileError::Magic);
+    }
+    let version = data[MAGIC.len()];
+    if version != VERSION {
+        return Err(MemoryFileError::UnsupportedVersion(version));
+    }
+    let input = &data[MAGIC.len() + 1..];
+    if input.len() < 1 + 3 * 8 {
+        return Err(MemoryFileError::Truncated);
+    }
+    if input[0] != kind {
+        return Err(MemoryFileError::Kind(input[0]));
+    }
+    let word = |i: usize| u64::from_le_bytes(<[u8; 8]>::try_from(&input[1 + 8 * i..9 + 8 * i]).unwrap());
+    if word(0) != size as u64 {
+        return Err(MemoryFileError::Size(word(0)));
+    }
+    let samples = word(1);
+    let count = usize::try_from(word(2)).map_err(|_| MemoryFileError::Overflow)?;
+
+    let input = &input[1 + 3 * 8..];
+    if count.checked_mul(ENTRY_LEN) != Some(input.len()) {
+        return Err(MemoryFileError::Truncated);
+    }
+
+    let entries = input
+        .chunks_exact(ENTRY_LEN)
+        .map(|bytes| {
+            let field = |i: usize| <[u8; 4]>::try_from(&bytes[4 * i..4 * i + 4]).unwrap();
+            let (a, b) = (u32::from_le_bytes(field(0)) as usize, u32::from_le_bytes(field(1)) as usize);
+            if a == b || a.max(b) >= size {
+                return Err(MemoryFileError::InvalidEdge(a, b));
+            }
+            Ok((Edge::new(a, b), i32::from_le_bytes(field(2))))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(MemoryData { size, samples, entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::edge::Edge;
+    use crate::types::learning::EdgeFrequencies;
+    use crate::types::memory::{MemoryFileError, SearchMemory};
+    use crate::types::path::Path;
+    use crate::types::penalties::Penalties;
+
+    fn round_trip<M: SearchMemory>(memory: &M) -> M {
+        let mut buffer = Vec::new();
+        memory.write_memory(&mut buffer).unwrap();
+        M::read_memory(&mut buffer.as_slice(), memory.size()).unwrap()
+    }
+
+    #[test]
+    fn penalties() {
+        let mut penalties = Penalties::from_size(5);
+        penalties.inc((0, 1));
+        penalties.set((4, 2), 3);
+        assert_eq!(round_trip(&penalties), penalties);
+
+        let mut other = Penalties::from_size(5);
+        other.set((1, 0), 2);
+        other.inc((3, 4));
+        penalties.merge(&other);
+        assert_eq!(
+            penalties.nonzero().collect::<Vec<_>>(),
+            vec![(Edge::new(0, 1), 3), (Edge::new(2, 4), 3), (Edge::new(3, 4), 1)],
+        );
+        assert_eq!((penalties.max(), penalties.total()), (3, 7));
+        assert_eq!(penalties.value(Edge::new(0, 2)), 0);
+    }
+
+    #[test]
+    fn frequencies() {
+        let mut frequencies = EdgeFrequencies::new(4);
+        frequencies.record(&Path::new(vec![0, 1, 2, 3]));
+        let read = round_trip(&frequencies);
+        assert_eq!((read.samples(), read.edges()), (1, frequencies.edges()));
+
+        let mut other = EdgeFrequencies::new(4);
+        other.record(&Path::new(vec![0, 2, 1, 3]));
+        frequencies.merge(&other);
+        assert_eq!(frequencies.samples(), 2);
+        assert_eq!(frequencies.value(Edge::new(1, 2)), 2);
+        assert_eq!(frequencies.frequency(0, 1), 0.5);
+    }
+
+    #[test]
+    fn errors() {
+        let mut buffer = Vec::new();
+        Penalties::from_size(3).write_memory(&mut buffer).unwrap();
+        let read = |bytes: &[u8]| Penalties::read_memory(&mut &bytes[..], 3).err();
+
+        assert_eq!(read(b"GLSM\x01"), Some(MemoryFileError::Magic));
+        assert_eq!(read(b"GLSE\x07"), Some(MemoryFileError::UnsupportedVersion(7)));
+        assert_eq!(read(&buffer[..buffer.len() - 1]), Some(MemoryFileError::Truncated));
+        assert_eq!(
+            EdgeFrequencies::read_memory(&mut buffer.as_slice(), 3).err(),
+            Some(MemoryFileError::Kind(buffer[5])),
+        );
+
+        let mut looped = buffer.clone();
+        looped[22] = 1;
+        looped.extend_from_slice(&[2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(read(&looped), Some(MemoryFileError::InvalidEdge(2, 2)));
+        assert_eq!(read(&[&buffer[..], &[0]].concat()), Some(MemoryFileError::Truncated));
+
+        // A size from the file alone is never allocated
+        let mut huge = buffer.clone();
+        huge[6..14].copy_from_slice(&(1u64 << 32).to_le_bytes());
+        assert_eq!(read(&huge), Some(MemoryFileError::Size(1 << 32)));
+        assert_eq!(
+            Penalties::read_memory(&mut buffer.as_slice(), usize::MAX).err(),
+            Some(MemoryFileError::Overflow),
+        );
+    }
+}
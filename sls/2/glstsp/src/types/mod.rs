@@ -1,5 +1,53 @@
+pub mod anytime;
+pub mod archive;
+pub mod batch;
+pub mod binary;
+pub mod cache;
+pub mod checksum;
+pub mod closure;
+pub mod config;
+pub mod construction;
+pub mod cooperative;
+pub mod coord;
+pub mod crossover;
+pub mod decomposition;
+pub mod dynamic;
+pub mod edge;
+pub mod export;
+pub mod geo;
 pub mod gls;
+pub mod graph;
+pub mod groups;
+pub mod handle;
+pub mod hierarchical;
+pub mod ids;
+pub mod import;
+pub mod instance;
+#[cfg(test)]
+pub mod instances;
+pub mod learning;
+pub mod limit;
 pub mod matrix;
+pub mod memory;
+pub mod moves;
+pub mod multistart;
+pub mod observer;
+pub mod ordered;
+pub mod pareto;
 pub mod path;
+pub mod penalties;
+pub mod perturb;
 pub mod point;
+pub mod pool;
+pub mod radius;
+pub mod replay;
+pub mod robustness;
 pub mod route;
+pub mod sampling;
+pub mod segment;
+pub mod settings;
+pub mod solve;
+pub mod spatial;
+pub mod stochastic;
+pub mod timed;
+pub mod tsplib;
@@ -0,0 +1,175 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::ids::{TourPos, VertexId};
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+
+impl GuidedLocalSearch {
+    /// Change of the augmented cost (distances plus `penalty_factor` times the penalties) when
+    /// applying `path.twist(i + 1, j)`, i.e. replacing the edges leaving the positions `i` and
+    /// `j` by `{path[i], path[j]}, {path[i + 1], path[j + 1]}`, positions wrapping around.
+    ///
+    /// `i` and `j` must be distinct. With a zero `penalty_factor`, it is the change of the cost.
+    pub fn two_opt_delta(&self, path: &Path, i: usize, j: usize, penalties: &Penalties, penalty_factor: i32) -> i32 {
+        assert!(i < path.len() && j < path.len());
+        debug_assert_ne!(i, j);
+
+        let (i, j) = (TourPos(i), TourPos(j));
+        self.exchange_delta(
+            (path.vertex(i), path.vertex(path.next_pos(i))),
+            (path.vertex(j), path.vertex(path.next_pos(j))),
+            penalties,
+            penalty_factor,
+        )
+    }
+
+    /// Change of the augmented cost when applying `path.move_segment(start, len, to)`: moving
+    /// the `len` vertices from position `start` between the positions `to` and `to + 1`.
+    ///
+    /// `to` must be outside of the segment and the segment must leave at least two vertices.
+    pub fn or_opt_delta(
+        &self,
+        path: &Path,
+        start: usize,
+        len: usize,
+        to: usize,
+        penalties: &Penalties,
+        penalty_factor: i32) -> i32
+    {
+        let size = path.len();
+        assert!(start < size && to < size);
+        assert!(len >= 1 && len + 2 <= size);
+        assert!((to + size - start) % size >= len, "position {} is inside the segment", to);
+
+        let prev = (start + size - 1) % size;
+        if to == prev {
+            return 0;
+        }
+
+        let vertex = |pos: usize| path.vertex(TourPos(pos % size));
+        let (first, last) = (vertex(start), vertex(start + len - 1));
+        let (before, after) = (vertex(prev), vertex(start + len));
+        let (a, b) = (vertex(to), vertex(to + 1));
+
+        let cost = |x: VertexId, y: VertexId| self.augmented_distance(x, y, penalties, penalty_factor);
+        cost(before, after) + cost(a, first) + cost(last, b)
+            - cost(before, first) - cost(last, after) - cost(a, b)
+    }
+}
+
+impl Path {
+    /// Move the `len` vertices from position `start` (wrapping around) right after the vertex
+    /// at position `to`, keeping their order. `to` must be outside of the segment.
+    ///
+    /// The resulting tour may start from a different vertex.
+    pub fn move_segment(&mut self, start: usize, len: usize, to: usize) {
+        let size = self.len();
+        debug_assert!((to + size - start) % size >= len);
+
+        let segment: Vec<_> = (0..len).map(|k| self.0[(start + k) % size]).collect();
+
+        // The rest of the tour, from the vertex following the segment
+        let mut res = Vec::with_capacity(size);
+        for k in 0..size - len {
+            let pos = (start + len + k) % size;
+            res.push(self.0[pos]);
+            if pos == to {
+                res.extend_from_slice(&segment);
+            }
+        }
+        self.0 = res;
+
+        debug_assert!(self.is_hamiltonian());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use crate::types::penalties::Penalties;
+    use crate::types::point::Point;
+    use rand_mt::Mt64;
+    use rand::{SeedableRng, Rng};
+    use rand::seq::SliceRandom;
+
+    const SIZE: usize = 20;
+    const PENALTY_FACTOR: i32 = 7;
+
+    fn create_rng() -> Mt64 {
+        SeedableRng::seed_from_u64(666)
+    }
+
+    fn create_problem(rng: &mut Mt64) -> (GuidedLocalSearch, Penalties, Path) {
+        let points: Vec<_> = (0..SIZE)
+            .map(|_| Point::new(rng.gen_range(0..1000), rng.gen_range(0..1000)))
+            .collect();
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+
+        let mut penalties = Penalties::from_size(SIZE);
+        for i in 0..SIZE {
+            for j in i + 1..SIZE {
+                penalties.set((i, j), rng.gen_range(0..5));
+            }
+        }
+
+        let mut path: Vec<_> = (0..SIZE).collect();
+        path.shuffle(rng);
+
+        (gls, penalties, Path::new(path))
+    }
+
+    fn augmented_cost(gls: &GuidedLocalSearch, path: &Path, penalties: &Penalties) -> i32 {
+        gls.augmented_cost(path, penalties, PENALTY_FACTOR).augmented
+    }
+
+    #[test]
+    fn two_opt_delta() {
+        let mut rng = create_rng();
+        let (gls, penalties, mut path) = create_problem(&mut rng);
+
+        for _ in 0..200 {
+            let i = rng.gen_range(0..SIZE);
+            let j = rng.gen_range(0..SIZE);
+            if i == j {
+                continue;
+            }
+
+            let before = augmented_cost(&gls, &path, &penalties);
+            let delta = gls.two_opt_delta(&path, i, j, &penalties, PENALTY_FACTOR);
+            path.twist((i + 1) % SIZE, j);
+
+            assert_eq!(augmented_cost(&gls, &path, &penalties) - before, delta);
+        }
+    }
+
+    #[test]
+    fn or_opt_delta() {
+        let mut rng = create_rng();
+        let (gls, penalties, mut path) = create_problem(&mut rng);
+
+        for _ in 0..200 {
+            let start = rng.gen_range(0..SIZE);
+            let len = rng.gen_range(1..=3);
+            let to = (start + len + rng.gen_range(0..SIZE - len)) % SIZE;
+
+            let before = augmented_cost(&gls, &path, &penalties);
+            let delta = gls.or_opt_delta(&path, start, len, to, &penalties, PENALTY_FACTOR);
+            path.move_segment(start, len, to);
+
+            assert_eq!(augmented_cost(&gls, &path, &penalties) - before, delta);
+        }
+    }
+
+    #[test]
+    fn move_segment() {
+        let mut actual = Path::sequential(8);
+        actual.move_segment(1, 2, 5);
+        assert_eq!(actual, Path::new(vec![3, 4, 5, 1, 2, 6, 7, 0]));
+
+        // Wrapping segment
+        let mut actual = Path::sequential(8);
+        actual.move_segment(7, 2, 3);
+        assert_eq!(actual, Path::new(vec![1, 2, 3, 7, 0, 4, 5, 6]));
+    }
+}
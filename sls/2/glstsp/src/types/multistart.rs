@@ -0,0 +1,137 @@
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::observer::Progress;
+use crate::types::perturb::double_bridge;
+use crate::types::pool::ElitePool;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::{Rng, SeedableRng};
+
+/// Options of `GuidedLocalSearch::solve_diverse`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MultiStart {
+    /// Number of GLS runs, each one adding at most a tour to the pool.
+    pub starts: usize,
+    /// Steps of each run.
+    pub steps: usize,
+    /// Fewest edges a new tour must not share with any tour of the pool.
+    pub min_distance: usize,
+    /// Times a run ending too close to the pool is started again, with twice as many kicks.
+    pub retries: usize,
+}
+
+impl MultiStart {
+    /// `starts` runs of `steps` steps, only rejecting tours already in the pool.
+    pub fn new(starts: usize, steps: usize) -> Self {
+        Self { starts, steps, min_distance: 1, retries: 3 }
+    }
+
+    pub fn with_min_distance(mut self, min_distance: usize) -> Self {
+        self.min_distance = min_distance;
+        self
+    }
+
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+}
+
+impl GuidedLocalSearch {
+    /// Distinct good tours from several GLS runs, e.g. to choose among alternatives or to seed
+    /// path relinking.
+    ///
+    /// The first run starts from the nearest neighbor tour with `seed`, as `solve`. The next
+    /// ones start from the best tour of the pool kicked by a double bridge. A run ending with a
+    /// tour sharing all but fewer than `min_distance` edges with a tour of the pool has found
+    /// the same region again: it is started over with twice as many kicks, up to `retries`
+    /// times, and dropped if it still doesn't get away.
+    pub fn solve_diverse(&self, seed: u64, options: &MultiStart) -> ElitePool {
+        let size = self.distances().size();
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let mut workspace = SolverWorkspace::new();
+        let mut pool = ElitePool::new(options.starts.max(1));
+
+        let first = self.search_in(seed, options.steps, None, &mut |_: &Progress| {}, &mut workspace);
+        pool.insert(first.cost, &first.path);
+
+        for _ in 1..options.starts {
+            let mut kicks = 1;
+            for _ in 0..=options.retries {
+                let mut start = pool.best().unwrap().clone();
+                if size >= 8 {
+                    for _ in 0..kicks {
+                        double_bridge(&mut start.path, &mut rng);
+                    }
+                }
+                start.cost = self.cost(&start.path);
+
+                let route = self.search_from(start, rng.gen(), options.steps, None, &mut |_: &Progress| {}, &mut workspace);
+                if is_far_from_pool(&pool, &route, options.min_distance) {
+                    pool.insert(route.cost, &route.path);
+                    break;
+                }
+                kicks *= 2;
+            }
+        }
+
+        pool
+    }
+}
+
+/// Whether `route` has at least `min_distance` edges missing from each tour of `pool`.
+fn is_far_from_pool(pool: &ElitePool, route: &Route, min_distance: usize) -> bool {
+    let len = route.path.len();
+    let hash = route.tour_hash();
+    pool.routes()
+        .iter()
+        .zip(pool.hashes())
+        // Tours with different hashes differ by at least an edge
+        .all(|(other, &other_hash)| {
+            (min_distance <= 1 && other_hash != hash) || len - other.path.common_edges(&route.path) >= min_distance
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::multistart::{is_far_from_pool, MultiStart};
+    use crate::types::path::Path;
+    use crate::types::pool::ElitePool;
+    use crate::types::route::Route;
+
+    #[test]
+    fn solve_diverse() {
+        let gls = random_gls(100);
+        let options = MultiStart::new(6, 10).with_min_distance(10);
+        let pool = gls.solve_diverse(666, &options);
+
+        assert!(!pool.is_empty() && pool.len() <= 6);
+        assert!(pool.best().unwrap().cost <= gls.solve(666, 10).cost);
+        for (i, route) in pool.routes().iter().enumerate() {
+            assert!(route.path.is_hamiltonian());
+            assert_eq!(route.cost, gls.cost(&route.path));
+            for other in &pool.routes()[i + 1..] {
+                assert!(100 - route.path.common_edges(&other.path) >= 10);
+            }
+        }
+
+        // No tour can be 101 edges away from another one
+        let far = gls.solve_diverse(666, &options.with_min_distance(101).with_retries(1));
+        assert_eq!(far.len(), 1);
+    }
+
+    #[test]
+    fn far_from_pool() {
+        let mut pool = ElitePool::new(2);
+        pool.insert(10, &Path::new(vec![0, 1, 2, 3, 4, 5]));
+        let route = |path: Vec<usize>| Route::new(10, Path::new(path));
+
+        // The same tour from another vertex, backwards
+        assert!(!is_far_from_pool(&pool, &route(vec![3, 2, 1, 0, 5, 4]), 1));
+        // 2-opt move, replacing 2 edges
+        let moved = route(vec![0, 1, 3, 2, 4, 5]);
+        assert!(is_far_from_pool(&pool, &moved, 1));
+        assert!(is_far_from_pool(&pool, &moved, 2));
+        assert!(!is_far_from_pool(&pool, &moved, 3));
+    }
+}
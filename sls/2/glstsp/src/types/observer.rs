@@ -0,0 +1,225 @@
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Snapshot of a running search, handed to the observer after every GLS step.
+pub struct Progress<'a> {
+    pub step: usize,
+    pub steps: usize,
+    pub cost: i32,
+    pub best_cost: i32,
+    pub elapsed: Duration,
+    /// Current tour, a local minimum of the augmented cost.
+    pub path: &'a Path,
+    pub penalties: &'a Penalties,
+    /// Edges penalized by this step, none for the first local search (step 0).
+    pub penalized: usize,
+    /// Moves applied by the local search of this step, or by the last one, without penalties,
+    /// in `Observer::on_finish`.
+    pub moves: u64,
+}
+
+impl Progress<'_> {
+    pub fn steps_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 { self.step as f64 / secs } else { 0.0 }
+    }
+}
+
+pub trait Observer {
+    fn on_step(&mut self, progress: &Progress);
+
+    /// Whether to stop the search before its next step, e.g. on a request of the user. The
+    /// tour reached so far still gets its last local search.
+    fn should_stop(&self) -> bool {
+        false
+    }
+
+    /// Called once after the last local search, `progress.step` being the number of steps run.
+    fn on_finish(&mut self, progress: &Progress) {
+        let _ = progress;
+    }
+}
+
+impl<F> Observer for F where F: FnMut(&Progress) {
+    fn on_step(&mut self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+/// What happened in a search, as reported by `Throttled`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SearchEvent {
+    /// The first local search is done.
+    Started { cost: i32, elapsed: Duration },
+    /// `step` found a tour shorter than all the previous ones.
+    Improved { step: usize, cost: i32, elapsed: Duration },
+    /// `step` penalized `edges` edges, its local optimum costing `cost`.
+    Penalized { step: usize, edges: usize, cost: i32, best_cost: i32, elapsed: Duration },
+    /// The search ended after `steps` steps.
+    Finished { steps: usize, cost: i32, elapsed: Duration },
+    /// The search stopped after `steps` steps, the cancellation flag being raised.
+    Cancelled { steps: usize, cost: i32, elapsed: Duration },
+}
+
+/// Observer turning the progress of a search into `SearchEvent`s for `callback`, at a rate an
+/// integration can forward, e.g. to a web client.
+///
+/// `Started`, `Finished` and `Cancelled` are always reported. The per-step events are dropped
+/// until the intervals since the last reported event have passed, an `Improved` event taking
+/// precedence over a `Penalized` one at the same step.
+pub struct Throttled<F> {
+    callback: F,
+    interval: Duration,
+    step_interval: usize,
+    cancel: Option<Arc<AtomicBool>>,
+    /// Elapsed time and step of the last reported event.
+    last: (Duration, usize),
+    best_cost: i32,
+}
+
+impl<F: FnMut(&SearchEvent)> Throttled<F> {
+    /// Every event reported.
+    pub fn new(callback: F) -> Self {
+        Self { callback, interval: Duration::ZERO, step_interval: 1, cancel: None, last: (Duration::ZERO, 0), best_cost: i32::MAX }
+    }
+
+    /// At most one per-step event per `interval`.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// At most one per-step event per `steps` steps.
+    pub fn with_step_interval(mut self, steps: usize) -> Self {
+        self.step_interval = steps.max(1);
+        self
+    }
+
+    /// Stop the search before its next step once `cancel` is raised, reporting `Cancelled`.
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn report(&mut self, event: SearchEvent, progress: &Progress) {
+        self.last = (progress.elapsed, progress.step);
+        (self.callback)(&event);
+    }
+}
+
+impl<F: FnMut(&SearchEvent)> Observer for Throttled<F> {
+    fn on_step(&mut self, progress: &Progress) {
+        let elapsed = progress.elapsed;
+        if progress.step == 0 {
+            self.best_cost = progress.cost;
+            return self.report(SearchEvent::Started { cost: progress.cost, elapsed }, progress);
+        }
+
+        let improved = progress.cost < self.best_cost;
+        self.best_cost = self.best_cost.min(progress.cost);
+        let (last_elapsed, last_step) = self.last;
+        if elapsed < last_elapsed + self.interval || progress.step < last_step + self.step_interval {
+            return;
+        }
+
+        let step = progress.step;
+        let event = if improved {
+            SearchEvent::Improved { step, cost: progress.cost, elapsed }
+        } else {
+            SearchEvent::Penalized { step, edges: progress.penalized, cost: progress.cost, best_cost: self.best_cost, elapsed }
+        };
+        self.report(event, progress);
+    }
+
+    fn should_stop(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    fn on_finish(&mut self, progress: &Progress) {
+        let (steps, cost, elapsed) = (progress.step, progress.cost, progress.elapsed);
+        let event = if self.should_stop() {
+            SearchEvent::Cancelled { steps, cost, elapsed }
+        } else {
+            SearchEvent::Finished { steps, cost, elapsed }
+        };
+        self.report(event, progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::observer::{Progress, SearchEvent, Throttled};
+    use crate::types::path::Path;
+    use crate::types::penalties::Penalties;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn steps_per_sec() {
+        let penalties = Penalties::from_size(2);
+        let path = Path::sequential(2);
+        let progress = |step, elapsed| Progress {
+            step,
+            steps: 10,
+            cost: 0,
+            best_cost: 0,
+            elapsed,
+            path: &path,
+            penalties: &penalties,
+            penalized: 0,
+            moves: 0,
+        };
+
+        assert_eq!(progress(10, Duration::from_secs(2)).steps_per_sec(), 5.0);
+        assert_eq!(progress(0, Duration::from_secs(0)).steps_per_sec(), 0.0);
+    }
+
+    fn create_gls() -> GuidedLocalSearch {
+        GuidedLocalSearch::from_fn(40, |i, j| ((i * 37 + j * 53) % 97 + 1) as i32)
+    }
+
+    #[test]
+    fn throttled() {
+        let gls = create_gls();
+        let mut events = Vec::new();
+        let route = gls.solve_observed(666, 30, &mut Throttled::new(|event: &SearchEvent| events.push(*event)).with_step_interval(10));
+
+        assert!(matches!(events[0], SearchEvent::Started { .. }));
+        assert!(matches!(events.last(), Some(&SearchEvent::Finished { steps: 30, cost, .. }) if cost == route.cost));
+        let steps: Vec<_> = events[1..events.len() - 1].iter()
+            .map(|event| match *event {
+                SearchEvent::Improved { step, .. } | SearchEvent::Penalized { step, .. } => step,
+                _ => panic!("{:?}", event),
+            })
+            .collect();
+        assert_eq!(steps, vec![10, 20, 30]);
+
+        // Without throttling, one event per step
+        let mut count = 0;
+        gls.solve_observed(666, 30, &mut Throttled::new(|_: &SearchEvent| count += 1));
+        assert_eq!(count, 32);
+    }
+
+    #[test]
+    fn cancelled() {
+        let gls = create_gls();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut events = Vec::new();
+        let mut observer = Throttled::new(|event: &SearchEvent| {
+            if let SearchEvent::Penalized { step: 5, .. } | SearchEvent::Improved { step: 5, .. } = event {
+                cancel.store(true, Ordering::Relaxed);
+            }
+            events.push(*event);
+        }).with_cancel(Arc::clone(&cancel));
+
+        let route = gls.solve_observed(666, 100, &mut observer);
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.info.unwrap().iterations, 5);
+        assert!(matches!(events.last(), Some(SearchEvent::Cancelled { steps: 5, .. })));
+    }
+}
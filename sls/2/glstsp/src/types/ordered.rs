@@ -0,0 +1,101 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::{insert_cheapest, Route};
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+impl GuidedLocalSearch {
+    /// Tour visiting the vertices of `order` in this relative order (see `follows_order`), the
+    /// other vertices being inserted where they cost the least, improved by `steps` GLS steps
+    /// that keep the order.
+    ///
+    /// The local search skips the 2-opt moves reversing more than one vertex of `order`.
+    pub fn complete_ordered(&self, order: &[usize], seed: u64, steps: usize) -> Route {
+        let size = self.distances().size();
+        let mut ordered = vec![false; size];
+        for &vertex in order {
+            assert!(vertex < size && !ordered[vertex], "invalid order vertex {}", vertex);
+            ordered[vertex] = true;
+        }
+
+        let mut tour = order.to_vec();
+        insert_cheapest(self.distances(), &mut tour, (0..size).filter(|&v| !ordered[v]));
+        let mut path = Path::new(tour);
+
+        let allowed = |path: &Path, from: usize, to: usize| ordered_between(path, &ordered, from, to) <= 1;
+
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = self.neighborhood(&mut rng);
+        let mut penalties = Penalties::from_size(size);
+
+        self.local_search_filtered(&mut path, &neighborhood, 0, &mut penalties, allowed);
+        let mut best = Route::new(self.cost(&path), path.clone());
+        let penalty_factor = self.penalty_factor(best.cost);
+
+        for _ in 0..steps {
+            self.penalize(&path, &mut penalties, &mut rng);
+            self.local_search_filtered(&mut path, &neighborhood, penalty_factor, &mut penalties, allowed);
+
+            let cost = self.cost(&path);
+            if cost < best.cost {
+                best = Route::new(cost, path.clone());
+            }
+        }
+
+        self.local_search_filtered(&mut best.path, &neighborhood, 0, &mut penalties, allowed);
+        best.cost = self.cost(&best.path);
+        best
+    }
+}
+
+/// Whether walking `path` forward from `order[0]` meets the vertices of `order` in this order,
+/// other vertices possibly in between.
+pub fn follows_order(path: &Path, order: &[usize]) -> bool {
+    let len = path.len();
+    let mut positions = vec![0; len];
+    for (pos, vertex) in path.0.iter().copied().enumerate() {
+        positions[vertex] = pos;
+    }
+
+    let Some(&first) = order.first() else {
+        return true;
+    };
+    let offsets: Vec<_> = order.iter().map(|&v| (positions[v] + len - positions[first]) % len).collect();
+    offsets.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+/// Number of ordered vertices, up to 2, at the positions `from` to `to` of `path`, wrapping
+/// around its end.
+fn ordered_between(path: &Path, ordered: &[bool], from: usize, to: usize) -> usize {
+    let len = path.len();
+    let count = (to + len - from) % len + 1;
+    (0..count).map(|k| path[(from + k) % len]).filter(|&v| ordered[v]).take(2).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::ordered::follows_order;
+    use crate::types::path::Path;
+
+    #[test]
+    fn order() {
+        let path = Path::new(vec![4, 0, 3, 1, 2]);
+        assert!(follows_order(&path, &[0, 1, 2]));
+        assert!(follows_order(&path, &[2, 4, 3]));
+        assert!(!follows_order(&path, &[1, 0, 2]));
+        assert!(follows_order(&path, &[]));
+    }
+
+    #[test]
+    fn complete_ordered() {
+        let gls = random_gls(60);
+        let order = [5, 17, 3, 42, 11, 29, 0, 50, 8, 33];
+
+        let actual = gls.complete_ordered(&order, 666, 20);
+        assert!(actual.path.is_hamiltonian());
+        assert!(follows_order(&actual.path, &order));
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+    }
+}
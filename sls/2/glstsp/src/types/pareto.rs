@@ -0,0 +1,95 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::route::Route;
+
+impl GuidedLocalSearch {
+    /// Approximate Pareto front of the distance and a second objective, the sum of the
+    /// `secondary` costs of the tour edges, by a weighted scan.
+    ///
+    /// Solves `weights + 1` weighted sums `(1 - w) * distance + w * secondary` for `w` from 0
+    /// to 1, the secondary costs being scaled to the same mean as the distances. The result is
+    /// the non-dominated tours found, by increasing distance (their `cost`) and so decreasing
+    /// second objective.
+    pub fn pareto_front(&self, secondary: &SymmetricMatrix, weights: usize, seed: u64, steps: usize) -> Vec<Route> {
+        let distances = self.distances();
+        assert_eq!(secondary.size(), distances.size());
+        assert!(weights > 0);
+
+        let scale = match (distances.mean(), secondary.mean()) {
+            (Some(d), Some(s)) if s > 0.0 => d / s,
+            _ => 1.0,
+        };
+
+        let mut candidates: Vec<(Route, i32)> = (0..=weights)
+            .map(|k| {
+                let w = k as f64 / weights as f64;
+                let weighted = SymmetricMatrix::from_fn(distances.size(), |i, j| {
+                    ((1.0 - w) * distances[(i, j)] as f64 + w * scale * secondary[(i, j)] as f64).round() as i32
+                });
+
+                let route = GuidedLocalSearch::new(weighted).solve(seed, steps);
+                let cost = self.cost(&route.path);
+                let second = secondary.sum(route.path.edges());
+                (Route::new(cost, route.path), second)
+            })
+            .collect();
+
+        non_dominated(&mut candidates)
+    }
+}
+
+/// Keep the routes that no other route beats on both objectives, by increasing cost.
+fn non_dominated(candidates: &mut [(Route, i32)]) -> Vec<Route> {
+    candidates.sort_by_key(|(route, second)| (route.cost, *second));
+
+    let mut res = Vec::new();
+    let mut best_second = i32::MAX;
+    for (route, second) in candidates.iter() {
+        if *second < best_second {
+            best_second = *second;
+            res.push(route.clone());
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use crate::types::pareto::non_dominated;
+    use crate::types::route::Route;
+    use rand_mt::Mt64;
+    use rand::{SeedableRng, Rng};
+
+    #[test]
+    fn dominance() {
+        let route = |cost| Route::new(cost, Path::sequential(3));
+        let mut candidates = vec![(route(10), 5), (route(8), 9), (route(12), 5), (route(9), 9), (route(11), 2)];
+
+        let costs: Vec<_> = non_dominated(&mut candidates).iter().map(|route| route.cost).collect();
+        assert_eq!(costs, vec![8, 10, 11]);
+    }
+
+    #[test]
+    fn pareto_front() {
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&random_points(40)));
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+        let secondary = SymmetricMatrix::from_fn(40, |_, _| rng.gen_range(0..1000));
+
+        let front = gls.pareto_front(&secondary, 4, 666, 10);
+
+        assert!(!front.is_empty());
+        assert!(front[0].cost <= gls.solve(666, 10).cost);
+        for pair in front.windows(2) {
+            assert!(pair[0].cost < pair[1].cost);
+            assert!(secondary.sum(pair[0].path.edges()) > secondary.sum(pair[1].path.edges()));
+        }
+        for route in &front {
+            assert!(route.path.is_hamiltonian());
+            assert_eq!(route.cost, gls.cost(&route.path));
+        }
+    }
+}
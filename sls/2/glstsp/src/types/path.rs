@@ -1,13 +1,17 @@
 use std::ops::{Index, IndexMut};
 use std::iter;
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Path(pub(crate) Vec<usize>);
 
 #[derive(Eq, PartialEq, Debug)]
 pub enum HamiltonianResult {
     Ok,
     VisitedTwice(usize),
+    /// The vertex is not below the length of the path.
+    OutOfRange(usize),
+    /// A tour needs at least 2 vertices.
+    TooShort,
 }
 
 impl Path
@@ -17,6 +21,20 @@ impl Path
         Self(path)
     }
 
+    /// Create a path from untrusted vertices, e.g. read from a file, checking that it is a
+    /// permutation of `0..path.len()`.
+    pub fn try_new(path: Vec<usize>) -> Result<Self, HamiltonianResult> {
+        if path.len() < 2 {
+            return Err(HamiltonianResult::TooShort);
+        }
+
+        let res = Self(path);
+        match res.check_hamiltonian() {
+            HamiltonianResult::Ok => Ok(res),
+            error => Err(error),
+        }
+    }
+
     pub fn from_size(size: usize) -> Self {
         Self::new(vec![0usize; size])
     }
@@ -34,11 +52,19 @@ impl Path
         Self::new(path)
     }
 
+    /// Vertices in visiting order.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+
     /// Check if the path is complete and Hamiltonian
     pub fn check_hamiltonian(&self) -> HamiltonianResult {
         let mut visited = vec![false; self.0.len()];
 
         for vertex in self.0.iter().copied() {
+            if vertex >= visited.len() {
+                return HamiltonianResult::OutOfRange(vertex);
+            }
             if visited[vertex] {
                 return HamiltonianResult::VisitedTwice(vertex);
             }
@@ -93,6 +119,48 @@ impl Path
             )
     }
 
+    /// Edge from the vertex at position `i` to the next one, same as `edges().nth(i)` in O(1).
+    #[inline]
+    pub fn edge_at(&self, i: usize) -> (usize, usize) {
+        let next = if i + 1 == self.0.len() { 0 } else { i + 1 };
+        (self.0[i], self.0[next])
+    }
+
+    /// Successor encoding: `res[v]` is the vertex visited after `v`, giving O(1) successor
+    /// lookups. The starting vertex is lost, the tour being the same whatever its start.
+    pub fn to_successor_vec(&self) -> Vec<usize> {
+        let mut res = vec![0; self.0.len()];
+        for (a, b) in self.edges() {
+            res[a] = b;
+        }
+        res
+    }
+
+    /// Tour starting at vertex 0 from its successor encoding, which must form a single cycle
+    /// (`VisitedTwice` reports the vertex closing a shorter cycle).
+    pub fn from_successor_vec(successors: &[usize]) -> Result<Self, HamiltonianResult> {
+        let len = successors.len();
+        if len < 2 {
+            return Err(HamiltonianResult::TooShort);
+        }
+
+        let mut res = Vec::with_capacity(len);
+        let mut vertex = 0;
+        for _ in 0..len {
+            res.push(vertex);
+            vertex = successors[vertex];
+            if vertex >= len {
+                return Err(HamiltonianResult::OutOfRange(vertex));
+            }
+        }
+
+        // Back to the start after exactly `len` steps, the tour visits every vertex once
+        if vertex != 0 {
+            return Err(HamiltonianResult::VisitedTwice(vertex));
+        }
+        Self::try_new(res)
+    }
+
     /// Create an iterator of edges interpolating all vertices with the next ones skipping `skip` amount.
     ///
     /// `Path::new(vec![2, 0, 1, 3]).interpolate_edges(skip: 0)` should return an iterator equivalent to
@@ -112,15 +180,31 @@ impl Path
 impl Index<usize> for Path {
     type Output = usize;
 
+    #[cfg(feature = "checked-index")]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+
+    #[cfg(not(feature = "checked-index"))]
     fn index(&self, index: usize) -> &Self::Output {
         debug_assert!(index < self.0.len());
+        // SAFETY: the solver only indexes with positions taken from neighborhoods, which are
+        // permutations of `0..len` checked in `GuidedLocalSearch::local_search`, or computed
+        // modulo `len`. Opting out of `checked-index` extends this contract to every caller.
         unsafe { self.0.get_unchecked(index) }
     }
 }
 
 impl IndexMut<usize> for Path {
+    #[cfg(feature = "checked-index")]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+
+    #[cfg(not(feature = "checked-index"))]
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         debug_assert!(index < self.0.len());
+        // SAFETY: see `index`
         unsafe { self.0.get_unchecked_mut(index) }
     }
 }
@@ -142,6 +226,13 @@ mod tests {
         Path::new(vec![0, 1, 2, 3, 4, 5, 6, 7])
     }
 
+    #[test]
+    #[cfg(feature = "checked-index")]
+    #[should_panic]
+    fn checked_index() {
+        let _ = create_path()[8];
+    }
+
     #[cfg(test)]
     mod hamiltonian {
         use crate::types::path::{Path, HamiltonianResult};
@@ -170,6 +261,20 @@ mod tests {
             let path = Path(vec![0, 1, 2, 3, 4, 5, 6, 0]);
             assert_eq!(path.check_hamiltonian(), HamiltonianResult::VisitedTwice(0));
         }
+
+        #[test]
+        fn out_of_range() {
+            let path = Path(vec![0, 1, 8, 3, 4, 5, 6, 7]);
+            assert_eq!(path.check_hamiltonian(), HamiltonianResult::OutOfRange(8));
+        }
+
+        #[test]
+        fn try_new() {
+            assert_eq!(Path::try_new(vec![2, 0, 1]), Ok(Path(vec![2, 0, 1])));
+            assert_eq!(Path::try_new(vec![2, 0, 0]), Err(HamiltonianResult::VisitedTwice(0)));
+            assert_eq!(Path::try_new(vec![0, 3, 1]), Err(HamiltonianResult::OutOfRange(3)));
+            assert_eq!(Path::try_new(vec![0]), Err(HamiltonianResult::TooShort));
+        }
     }
 
     #[cfg(test)]
@@ -282,6 +387,66 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod random_twists {
+        use crate::types::path::Path;
+        use rand_mt::Mt64;
+        use rand::{SeedableRng, Rng};
+
+        /// Reverse the positions `i, i + 1, ..., j` (modulo the length) one by one.
+        fn reverse_model(path: &mut [usize], i: usize, j: usize) {
+            let len = path.len();
+            let positions: Vec<_> = (0..(j + len - i) % len + 1).map(|k| (i + k) % len).collect();
+            let values: Vec<_> = positions.iter().map(|&p| path[p]).collect();
+            for (&p, &v) in positions.iter().zip(values.iter().rev()) {
+                path[p] = v;
+            }
+        }
+
+        #[test]
+        fn same_as_model() {
+            let mut rng: Mt64 = SeedableRng::seed_from_u64(666);
+
+            for len in 2..20 {
+                let mut actual = Path::sequential(len);
+                let mut expected: Vec<_> = (0..len).collect();
+
+                for _ in 0..100 {
+                    let i = rng.gen_range(0..len);
+                    let j = rng.gen_range(0..len);
+                    actual.twist(i, j);
+                    reverse_model(&mut expected, i, j);
+                    assert_eq!(actual.0, expected, "twist({}, {}) with {} vertices", i, j, len);
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod successors {
+        use crate::types::path::{HamiltonianResult, Path};
+
+        #[test]
+        fn round_trip() {
+            let path = Path::new(vec![2, 0, 3, 1, 4]);
+            let successors = path.to_successor_vec();
+
+            assert_eq!(successors, vec![3, 4, 0, 1, 2]);
+            // Same tour, rotated to start at vertex 0
+            assert_eq!(Path::from_successor_vec(&successors), Ok(Path::new(vec![0, 3, 1, 4, 2])));
+        }
+
+        #[test]
+        fn errors() {
+            assert_eq!(Path::from_successor_vec(&[0]), Err(HamiltonianResult::TooShort));
+            assert_eq!(Path::from_successor_vec(&[1, 5, 0]), Err(HamiltonianResult::OutOfRange(5)));
+            // Two cycles 0 -> 1 -> 0 and 2 -> 3 -> 2
+            assert_eq!(Path::from_successor_vec(&[1, 0, 3, 2]), Err(HamiltonianResult::VisitedTwice(0)));
+            // 0 -> 1 -> 2 -> 1
+            assert_eq!(Path::from_successor_vec(&[1, 2, 1]), Err(HamiltonianResult::VisitedTwice(1)));
+        }
+    }
+
     #[cfg(test)]
     mod edges {
         use crate::types::path::Path;
@@ -308,6 +473,14 @@ mod tests {
 
             itertools::assert_equal(actual, expected);
         }
+
+        #[test]
+        fn edge_at() {
+            let path = Path(vec![2, 0, 1, 3]);
+
+            let actual = (0..path.len()).map(|i| path.edge_at(i));
+            itertools::assert_equal(actual, path.edges());
+        }
     }
 
     #[cfg(test)]
@@ -0,0 +1,204 @@
+use crate::types::edge::Edge;
+use crate::types::ids::VertexId;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::memory::{self, MemoryFileError, SearchMemory};
+use std::io;
+use std::io::{Read, Write};
+use std::ops::Index;
+
+/// Number of times GLS penalized each edge, the `p(i, j)` of the augmented cost.
+///
+/// Stored as a matrix like the distances, the local search reading rows of both, but only
+/// updated edge by edge so the two can't be mixed up. The largest and total penalties are kept
+/// up to date along the way.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Penalties {
+    matrix: SymmetricMatrix,
+    max: i32,
+    total: i64,
+}
+
+impl Penalties {
+    /// No penalty on the edges between `size` vertices.
+    pub fn from_size(size: usize) -> Self {
+        Self { matrix: SymmetricMatrix::from_size(size), max: 0, total: 0 }
+    }
+
+    pub fn size(&self) -> usize {
+        self.matrix.size()
+    }
+
+    pub fn get(&self, edge: impl Into<Edge>) -> i32 {
+        self[edge.into()]
+    }
+
+    pub fn set(&mut self, edge: impl Into<Edge>, value: i32) {
+        let (a, b) = edge.into().endpoints();
+        let old = self.matrix[(a, b)];
+        self.matrix.set(a, b, value);
+        if a == b {
+            return;
+        }
+
+        self.total += value as i64 - old as i64;
+        if value >= self.max {
+            self.max = value;
+        } else if old == self.max {
+            self.update_max();
+        }
+    }
+
+    /// Penalize `edge` once more.
+    pub fn inc(&mut self, edge: impl Into<Edge>) {
+        let (a, b) = edge.into().endpoints();
+        self.matrix.inc(a, b, 1);
+        if a != b {
+            self.total += 1;
+            self.max = self.max.max(self.matrix[(a, b)]);
+        }
+    }
+
+    /// Remove every penalty.
+    pub fn reset(&mut self) {
+        let size = self.size();
+        self.reset_to_size(size);
+    }
+
+    /// Resize to `size` vertices without penalties, reusing the allocation.
+    pub(crate) fn reset_to_size(&mut self, size: usize) {
+        self.matrix.reset(size);
+        self.max = 0;
+        self.total = 0;
+    }
+
+    /// Multiply every penalty by `factor`, truncating, so the search slowly forgets the old
+    /// local optima.
+    pub fn decay(&mut self, factor: f64) {
+        assert!((0.0..=1.0).contains(&factor));
+        for value in &mut self.matrix.data {
+            *value = (*value as f64 * factor) as i32;
+        }
+        self.update_max();
+        self.total = self.nonzero().map(|(_, penalty)| penalty as i64).sum();
+    }
+
+    /// Largest penalty of an edge, 0 without penalties.
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    /// Sum of the penalties of all the edges, each counted once.
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    fn update_max(&mut self) {
+        self.max = self.nonzero().map(|(_, penalty)| penalty).max().unwrap_or(0).max(0);
+    }
+
+    /// The penalized edges and their penalties, by increasing edge.
+    pub fn nonzero(&self) -> impl Iterator<Item=(Edge, i32)> + '_ {
+        self.matrix.pairs()
+            .map(move |(a, b)| (Edge::new(a, b), self.matrix[(a, b)]))
+            .filter(|&(_, penalty)| penalty != 0)
+    }
+
+    pub fn sum(&self, edges: impl Iterator<Item=(usize, usize)>) -> i32 {
+        self.matrix.sum(edges)
+    }
+
+    /// Penalties of the edges from `i`, indexed by the other vertex.
+    #[inline]
+    pub(crate) fn row(&self, i: usize) -> &[i32] {
+        self.matrix.row(i)
+    }
+}
+
+impl Index<(usize, usize)> for Penalties {
+    type Output = i32;
+
+    #[inline]
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.matrix[index]
+    }
+}
+
+impl Index<(VertexId, VertexId)> for Penalties {
+    type Output = i32;
+
+    #[inline]
+    fn index(&self, index: (VertexId, VertexId)) -> &Self::Output {
+        &self.matrix[index]
+    }
+}
+
+impl Index<Edge> for Penalties {
+    type Output = i32;
+
+    #[inline]
+    fn index(&self, edge: Edge) -> &Self::Output {
+        &self.matrix[edge]
+    }
+}
+
+impl SearchMemory for Penalties {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn value(&self, edge: Edge) -> i32 {
+        self[edge]
+    }
+
+    /// Sum the penalties, as if one run had found the local optima of both.
+    fn merge(&mut self, other: &Self) {
+        assert_eq!(other.size(), self.size());
+        for (edge, penalty) in other.nonzero() {
+            self.set(edge, self[edge] + penalty);
+        }
+    }
+
+    fn write_memory(&self, writer: &mut impl Write) -> io::Result<()> {
+        memory::write_data(b'P', self.size(), 0, self.nonzero(), writer)
+    }
+
+    fn read_memory(reader: &mut impl Read, size: usize) -> Result<Self, MemoryFileError> {
+        let data = memory::read_data(b'P', size, reader)?;
+        let mut res = Self::from_size(data.size);
+        for (edge, penalty) in data.entries {
+            res.set(edge, penalty);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::edge::Edge;
+    use crate::types::penalties::Penalties;
+
+    #[test]
+    fn penalties() {
+        let mut penalties = Penalties::from_size(4);
+        penalties.inc((2, 1));
+        penalties.inc(Edge::new(1, 2));
+        penalties.set((0, 3), 5);
+        assert_eq!(penalties.get((1, 2)), 2);
+        assert_eq!(penalties[(3, 0)], 5);
+        assert_eq!(penalties.nonzero().collect::<Vec<_>>(), vec![(Edge::new(0, 3), 5), (Edge::new(1, 2), 2)]);
+        assert_eq!((penalties.max(), penalties.total()), (5, 7));
+
+        penalties.decay(0.5);
+        assert_eq!(penalties.nonzero().collect::<Vec<_>>(), vec![(Edge::new(0, 3), 2), (Edge::new(1, 2), 1)]);
+        assert_eq!((penalties.max(), penalties.total()), (2, 3));
+
+        // Lowering the largest penalty finds the next one
+        penalties.set((0, 3), 0);
+        assert_eq!((penalties.max(), penalties.total()), (1, 1));
+
+        penalties.reset();
+        assert_eq!(penalties.size(), 4);
+        assert_eq!(penalties.nonzero().count(), 0);
+        assert_eq!((penalties.max(), penalties.total()), (0, 0));
+    }
+}
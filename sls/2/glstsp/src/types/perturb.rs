@@ -0,0 +1,257 @@
+use crate::types::edge::Edge;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::seq::index::{sample, sample_weighted};
+
+/// Pick `amount` distinct cut positions in `1..len`, sorted.
+fn cuts(len: usize, amount: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut res: Vec<_> = sample(rng, len - 1, amount).into_iter()
+        .map(|i| i + 1)
+        .collect();
+    res.sort_unstable();
+    res
+}
+
+/// Pick `amount` distinct cut positions in `1..len`, sorted, preferring to cut highly
+/// penalized edges.
+///
+/// Cutting at `i` breaks the edge `(path[i - 1], path[i])`, which is chosen with a weight
+/// proportional to `1 + penalty`, so good (never penalized) edges are still breakable but
+/// the ones GLS kept penalizing go first.
+fn penalized_cuts(path: &Path, penalties: &Penalties, amount: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let weight = |i: usize| 1.0 + penalties[Edge::new(path[i], path[i + 1])] as f64;
+
+    let mut res: Vec<_> = sample_weighted(rng, path.len() - 1, weight, amount)
+        .unwrap()
+        .into_iter()
+        .map(|i| i + 1)
+        .collect();
+    res.sort_unstable();
+    res
+}
+
+/// Double bridge kick: cut the tour in `A B C D` and reconnect it as `A C B D`.
+///
+/// The tour must have at least 4 vertices.
+pub fn double_bridge(path: &mut Path, rng: &mut impl Rng) {
+    debug_assert!(path.len() >= 4);
+    let cuts = cuts(path.len(), 3, rng);
+    double_bridge_at(path, &cuts);
+}
+
+/// Same as `double_bridge`, choosing the bridges among the most penalized edges.
+pub fn penalized_double_bridge(path: &mut Path, penalties: &Penalties, rng: &mut impl Rng) {
+    debug_assert!(path.len() >= 4);
+    let cuts = penalized_cuts(path, penalties, 3, rng);
+    double_bridge_at(path, &cuts);
+}
+
+fn double_bridge_at(path: &mut Path, cuts: &[usize]) {
+    let len = path.len();
+    let (a, b, c) = (cuts[0], cuts[1], cuts[2]);
+
+    let mut res = Vec::with_capacity(len);
+    res.extend_from_slice(&path.0[..a]);
+    res.extend_from_slice(&path.0[b..c]);
+    res.extend_from_slice(&path.0[a..b]);
+    res.extend_from_slice(&path.0[c..]);
+    path.0 = res;
+
+    debug_assert!(path.is_hamiltonian());
+}
+
+/// Cut the tour in 3 segments and reconnect them in a random order, reversing each one with
+/// probability 1/2.
+///
+/// The tour must have at least 3 vertices.
+pub fn segment_shuffle(path: &mut Path, rng: &mut impl Rng) {
+    debug_assert!(path.len() >= 3);
+    let cuts = cuts(path.len(), 2, rng);
+    segment_shuffle_at(path, &cuts, rng);
+}
+
+/// Same as `segment_shuffle`, cutting preferably the most penalized edges.
+pub fn penalized_segment_shuffle(path: &mut Path, penalties: &Penalties, rng: &mut impl Rng) {
+    debug_assert!(path.len() >= 3);
+    let cuts = penalized_cuts(path, penalties, 2, rng);
+    segment_shuffle_at(path, &cuts, rng);
+}
+
+fn segment_shuffle_at(path: &mut Path, cuts: &[usize], rng: &mut impl Rng) {
+    let len = path.len();
+    let mut segments = vec![0..cuts[0], cuts[0]..cuts[1], cuts[1]..len];
+    segments.shuffle(rng);
+
+    let mut res = Vec::with_capacity(len);
+    for segment in segments {
+        let segment = &path.0[segment];
+        if rng.gen::<bool>() {
+            res.extend(segment.iter().rev());
+        } else {
+            res.extend_from_slice(segment);
+        }
+    }
+    path.0 = res;
+
+    debug_assert!(path.is_hamiltonian());
+}
+
+/// Reverse a random segment of the tour.
+pub fn random_reversal(path: &mut Path, rng: &mut impl Rng) {
+    let len = path.len();
+    let i = rng.gen_range(0..len);
+    let j = rng.gen_range(0..len);
+    path.twist(i.min(j), i.max(j));
+}
+
+/// Reverse a random segment of the tour whose two boundary edges are preferably the most
+/// penalized ones.
+pub fn penalized_reversal(path: &mut Path, penalties: &Penalties, rng: &mut impl Rng) {
+    let cuts = penalized_cuts(path, penalties, 2, rng);
+    path.twist(cuts[0], cuts[1] - 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::path::Path;
+    use crate::types::penalties::Penalties;
+    use rand_mt::Mt64;
+    use rand::SeedableRng;
+
+    fn create_path() -> Path {
+        Path::sequential(16)
+    }
+
+    fn create_rng() -> Mt64 {
+        SeedableRng::seed_from_u64(666)
+    }
+
+    /// Penalties for `create_path` where only the edges entering the positions 4, 8 and 12
+    /// are (heavily) penalized.
+    fn create_penalties() -> Penalties {
+        let mut penalties = Penalties::from_size(16);
+        penalties.set((3, 4), 1_000_000);
+        penalties.set((7, 8), 1_000_000);
+        penalties.set((11, 12), 1_000_000);
+        penalties
+    }
+
+    #[cfg(test)]
+    mod double_bridge {
+        use crate::types::perturb::double_bridge;
+        use crate::types::perturb::tests::{create_path, create_rng};
+        use crate::types::path::Path;
+
+        #[test]
+        fn is_hamiltonian() {
+            let mut rng = create_rng();
+            let mut actual = create_path();
+
+            for _ in 0..100 {
+                double_bridge(&mut actual, &mut rng);
+                assert!(actual.is_hamiltonian());
+            }
+        }
+
+        #[test]
+        fn changes_path() {
+            let mut actual = create_path();
+            double_bridge(&mut actual, &mut create_rng());
+            assert_ne!(actual, create_path());
+        }
+
+        #[test]
+        fn minimal() {
+            let mut actual = Path::sequential(4);
+            double_bridge(&mut actual, &mut create_rng());
+            assert_eq!(actual, Path::new(vec![0, 2, 1, 3]));
+        }
+    }
+
+    #[cfg(test)]
+    mod segment_shuffle {
+        use crate::types::perturb::segment_shuffle;
+        use crate::types::perturb::tests::{create_path, create_rng};
+
+        #[test]
+        fn is_hamiltonian() {
+            let mut rng = create_rng();
+            let mut actual = create_path();
+
+            for _ in 0..100 {
+                segment_shuffle(&mut actual, &mut rng);
+                assert!(actual.is_hamiltonian());
+            }
+        }
+
+        #[test]
+        fn deterministic() {
+            let mut a = create_path();
+            let mut b = create_path();
+            segment_shuffle(&mut a, &mut create_rng());
+            segment_shuffle(&mut b, &mut create_rng());
+            assert_eq!(a, b);
+        }
+    }
+
+    #[cfg(test)]
+    mod random_reversal {
+        use crate::types::perturb::random_reversal;
+        use crate::types::perturb::tests::{create_path, create_rng};
+
+        #[test]
+        fn is_hamiltonian() {
+            let mut rng = create_rng();
+            let mut actual = create_path();
+
+            for _ in 0..100 {
+                random_reversal(&mut actual, &mut rng);
+                assert!(actual.is_hamiltonian());
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod penalized {
+        use crate::types::perturb::{penalized_double_bridge, penalized_segment_shuffle, penalized_reversal};
+        use crate::types::perturb::tests::{create_path, create_rng, create_penalties};
+        use crate::types::path::Path;
+
+        #[test]
+        fn double_bridge() {
+            let mut actual = create_path();
+            penalized_double_bridge(&mut actual, &create_penalties(), &mut create_rng());
+
+            let expected = Path::new(vec![0, 1, 2, 3, 8, 9, 10, 11, 4, 5, 6, 7, 12, 13, 14, 15]);
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn segment_shuffle() {
+            let mut rng = create_rng();
+            let mut actual = create_path();
+            let penalties = create_penalties();
+
+            for _ in 0..100 {
+                penalized_segment_shuffle(&mut actual, &penalties, &mut rng);
+                assert!(actual.is_hamiltonian());
+            }
+        }
+
+        #[test]
+        fn reversal() {
+            let mut actual = create_path();
+            penalized_reversal(&mut actual, &create_penalties(), &mut create_rng());
+
+            let mut reversed = actual.0.clone();
+            reversed.sort_unstable();
+            assert_eq!(reversed, create_path().0);
+
+            // Only whole blocks between penalized edges can be reversed
+            let breaks = actual.edges().filter(|&(a, b)| a + 1 != b && b + 1 != a).count();
+            assert_eq!(breaks, 3);
+        }
+    }
+}
@@ -5,11 +5,18 @@ pub struct Point {
 }
 
 impl Point {
-    #[cfg(test)]
     pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
 
+    pub fn x(self) -> i32 {
+        self.x
+    }
+
+    pub fn y(self) -> i32 {
+        self.y
+    }
+
     pub fn dist(self, other: Self) -> i32 {
         let dx = self.x - other.x;
         let dy = self.y - other.y;
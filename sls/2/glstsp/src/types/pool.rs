@@ -0,0 +1,201 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::observer::Progress;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+/// The best distinct tours seen so far, sorted by increasing cost.
+///
+/// Two tours are the same if they have the same edges, whatever their starting vertex and
+/// direction.
+pub struct ElitePool {
+    capacity: usize,
+    routes: Vec<Route>,
+    /// `Path::tour_hash` of each route, to compare the edges only when they match.
+    hashes: Vec<u64>,
+}
+
+impl ElitePool {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self { capacity, routes: Vec::with_capacity(capacity + 1), hashes: Vec::with_capacity(capacity + 1) }
+    }
+
+    /// Insert a copy of the tour if it is among the `capacity` best ones and not already in the
+    /// pool, returns whether it was inserted.
+    pub fn insert(&mut self, cost: i32, path: &Path) -> bool {
+        if self.routes.len() == self.capacity && cost >= self.routes[self.capacity - 1].cost {
+            return false;
+        }
+
+        let hash = path.tour_hash();
+        let duplicate = self.routes.iter()
+            .zip(&self.hashes)
+            .any(|(route, &other)| other == hash && route.cost == cost && route.path.common_edges(path) == path.len());
+        if duplicate {
+            return false;
+        }
+
+        let index = self.routes.partition_point(|route| route.cost <= cost);
+        self.routes.insert(index, Route::new(cost, path.clone()));
+        self.hashes.insert(index, hash);
+        self.routes.truncate(self.capacity);
+        self.hashes.truncate(self.capacity);
+        true
+    }
+
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// `Path::tour_hash` of each route of `routes`.
+    pub(crate) fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    pub fn best(&self) -> Option<&Route> {
+        self.routes.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl GuidedLocalSearch {
+    /// Walk from `from` toward `to` with 2-opt moves, each one placing the next vertex of `to`,
+    /// and return the best tour strictly between them after a local search with `neighborhood`.
+    ///
+    /// `None` if the tours are too close to have an intermediate tour.
+    pub fn path_relinking(&self, from: &Path, to: &Path, neighborhood: &Path) -> Option<Route> {
+        let mut penalties = Penalties::from_size(from.len());
+        self.relink(from, to, neighborhood, &mut penalties)
+    }
+
+    fn relink(&self, from: &Path, to: &Path, neighborhood: &Path, penalties: &mut Penalties) -> Option<Route> {
+        let size = from.len();
+        assert_eq!(to.len(), size);
+        let distances = self.distances();
+
+        // Rotate `from` to start like `to`, which doesn't change the tour
+        let start = from.0.iter().position(|&v| v == to[0]).unwrap();
+        let mut current = Path(from.0[start..].iter().chain(&from.0[..start]).copied().collect());
+
+        let mut positions = vec![0; size];
+        for (pos, &vertex) in current.0.iter().enumerate() {
+            positions[vertex] = pos;
+        }
+
+        let mut cost = self.cost(&current);
+        let mut best: Option<Route> = None;
+
+        for k in 1..size - 1 {
+            let p = positions[to[k]];
+            if p == k {
+                continue;
+            }
+
+            // Reversing k..=p brings to[k] at k: {k - 1, k}, {p, p + 1} -> {k - 1, p}, {k, p + 1}
+            let (before, first, last, after) = (current[k - 1], current[k], current[p], current[(p + 1) % size]);
+            cost += distances[(before, last)] + distances[(first, after)]
+                - distances[(before, first)] - distances[(last, after)];
+
+            current.twist(k, p);
+            for pos in k..=p {
+                positions[current[pos]] = pos;
+            }
+
+            if current != *to && best.as_ref().is_none_or(|best| cost < best.cost) {
+                best = Some(Route::new(cost, current.clone()));
+            }
+        }
+
+        let mut best = best?;
+        self.local_search(&mut best.path, neighborhood, 0, penalties);
+        best.cost = self.cost(&best.path);
+        Some(best)
+    }
+
+    /// Same as `solve`, keeping the `pool_size` best distinct tours visited by GLS and then
+    /// relinking every ordered pair of them as an intensification stage.
+    pub fn solve_with_relinking(&self, seed: u64, steps: usize, pool_size: usize) -> Route {
+        let mut pool = ElitePool::new(pool_size);
+        let mut route = self.solve_observed(seed, steps, &mut |progress: &Progress| {
+            pool.insert(progress.cost, progress.path);
+        });
+        pool.insert(route.cost, &route.path);
+
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = self.neighborhood(&mut rng);
+        let mut penalties = Penalties::from_size(route.path.len());
+
+        for from in pool.routes() {
+            for to in pool.routes() {
+                if from == to {
+                    continue;
+                }
+                if let Some(relinked) = self.relink(&from.path, &to.path, &neighborhood, &mut penalties) {
+                    if relinked.cost < route.cost {
+                        route = relinked;
+                    }
+                }
+            }
+        }
+
+        route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use crate::types::path::Path;
+    use crate::types::pool::ElitePool;
+
+    #[test]
+    fn pool() {
+        let mut pool = ElitePool::new(2);
+
+        assert!(pool.insert(10, &Path::new(vec![0, 1, 2, 3])));
+        assert!(pool.insert(8, &Path::new(vec![0, 2, 1, 3])));
+        // Same tour as the first one, rotated and reversed
+        assert!(!pool.insert(10, &Path::new(vec![2, 1, 0, 3])));
+        assert!(!pool.insert(11, &Path::new(vec![0, 1, 3, 2])));
+        assert!(pool.insert(9, &Path::new(vec![0, 1, 3, 2])));
+
+        let costs: Vec<_> = pool.routes().iter().map(|route| route.cost).collect();
+        assert_eq!(costs, vec![8, 9]);
+        assert_eq!(pool.best().unwrap().path, Path::new(vec![0, 2, 1, 3]));
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn path_relinking() {
+        let gls = random_gls(100);
+        let neighborhood = Path::sequential(100);
+        let from = gls.nearest_neighbor().path;
+        let to = gls.solve(666, 5).path;
+
+        let actual = gls.path_relinking(&from, &to, &neighborhood).unwrap();
+        assert!(actual.path.is_hamiltonian());
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+
+        assert_eq!(gls.path_relinking(&to, &to, &neighborhood), None);
+    }
+
+    #[test]
+    fn solve_with_relinking() {
+        let gls = random_gls(100);
+        let actual = gls.solve_with_relinking(666, 10, 4);
+
+        assert!(actual.path.is_hamiltonian());
+        assert_eq!(actual.cost, gls.cost(&actual.path));
+        assert!(actual.cost <= gls.solve(666, 10).cost);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::point::Point;
+use crate::types::route::Route;
+use crate::types::spatial::SpatialGrid;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+
+impl GuidedLocalSearch {
+    /// Same as `solve`, the 2-opt moves only adding edges between vertices at distance at most
+    /// `radius` of each other, `points` being the coordinates of the vertices.
+    ///
+    /// Each local search pass costs the number of vertices times the number of neighbors within
+    /// the radius instead of its square, a large speedup on spread out instances. The tours are
+    /// somewhat longer, a radius of a few times the typical edge length keeping the loss small.
+    pub fn solve_within_radius(&self, points: &[Point], radius: i32, seed: u64, steps: usize) -> Route {
+        let size = self.distances().size();
+        assert_eq!(points.len(), size);
+        assert!(radius >= 0);
+
+        let grid = SpatialGrid::new(points, radius);
+        let near: Vec<Vec<usize>> = (0..size)
+            .map(|a| grid.within(points[a], radius).into_iter().filter(|&b| b != a).collect())
+            .collect();
+
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = self.neighborhood(&mut rng);
+        let mut penalties = Penalties::from_size(size);
+        let mut path = self.nearest_neighbor().path;
+
+        self.radius_local_search(&mut path, &neighborhood, &near, 0, &penalties);
+        let mut best = Route::new(self.cost(&path), path.clone());
+        let penalty_factor = self.penalty_factor(best.cost);
+
+        for _ in 0..steps {
+            self.penalize(&path, &mut penalties, &mut rng);
+            self.radius_local_search(&mut path, &neighborhood, &near, penalty_factor, &penalties);
+
+            let cost = self.cost(&path);
+            if cost < best.cost {
+                best = Route::new(cost, path.clone());
+            }
+        }
+
+        self.radius_local_search(&mut best.path, &neighborhood, &near, 0, &penalties);
+        best.cost = self.cost(&best.path);
+        best
+    }
+
+    /// 2-opt joining each vertex to one of its `near` vertices, with either their successors or
+    /// their predecessors, until no move lowers the augmented cost. The positions are visited in
+    /// the order of `neighborhood`.
+    fn radius_local_search(
+        &self,
+        path: &mut Path,
+        neighborhood: &Path,
+        near: &[Vec<usize>],
+        penalty_factor: i32,
+        penalties: &Penalties)
+    {
+        let size = path.len();
+        if size < 4 {
+            return;
+        }
+        let distances = self.distances();
+        let augmented = |a: usize, b: usize| distances[(a, b)] + penalty_factor * penalties[(a, b)];
+
+        let mut positions = vec![0; size];
+        for (pos, &vertex) in path.0.iter().enumerate() {
+            positions[vertex] = pos;
+        }
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for &i in &neighborhood.0 {
+                let a = path[i];
+                for &b in &near[a] {
+                    let j = positions[b];
+                    let (next_i, next_j) = ((i + 1) % size, (j + 1) % size);
+                    let (prev_i, prev_j) = ((i + size - 1) % size, (j + size - 1) % size);
+
+                    // {a, a_next}, {b, b_next} -> {a, b}, {a_next, b_next}
+                    let (a_next, b_next) = (path[next_i], path[next_j]);
+                    let gain = augmented(a, a_next) + augmented(b, b_next) - augmented(a, b) - augmented(a_next, b_next);
+                    let twist = if gain > 0 && b != a_next && b_next != a {
+                        Some((next_i, j))
+                    } else {
+                        // {a_prev, a}, {b_prev, b} -> {a, b}, {a_prev, b_prev}
+                        let (a_prev, b_prev) = (path[prev_i], path[prev_j]);
+                        let gain = augmented(a_prev, a) + augmented(b_prev, b) - augmented(a, b) - augmented(a_prev, b_prev);
+                        Some((i, prev_j)).filter(|_| gain > 0 && b != a_prev && b_prev != a)
+                    };
+
+                    if let Some((from, to)) = twist {
+                        path.twist(from, to);
+                        let mut pos = from;
+                        loop {
+                            positions[path[pos]] = pos;
+                            if pos == to {
+                                break;
+                            }
+                            pos = (pos + 1) % size;
+                        }
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+
+    #[test]
+    fn solve_within_radius() {
+        let points = random_points(300);
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points));
+        let full = gls.solve(666, 20);
+
+        let limited = gls.solve_within_radius(&points, 150, 666, 20);
+        assert!(limited.path.is_hamiltonian());
+        assert_eq!(limited.cost, gls.cost(&limited.path));
+        assert!((limited.cost as f64) < full.cost as f64 * 1.1);
+
+        let tiny = gls.solve_within_radius(&points, 0, 666, 20);
+        assert_eq!(tiny.path, gls.nearest_neighbor().path);
+    }
+}
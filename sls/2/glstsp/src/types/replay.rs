@@ -0,0 +1,108 @@
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+
+/// A change of the tour or of the penalties during a solve, vertices and positions being
+/// stored on 32 bits to keep the log of long runs small.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Event {
+    /// `Path::twist(from, to)` applied by the local search.
+    Twist { from: u32, to: u32 },
+    /// Penalty of the edge `{a, b}` incremented.
+    Penalize { a: u32, b: u32 },
+    /// The current tour became the one to roll back to (see `Acceptance::Threshold`).
+    Incumbent,
+    /// The current tour was rejected, the search restarting from the incumbent.
+    Restore,
+    /// The final tour was rotated to start at the depot.
+    Rotate { start: u32 },
+}
+
+/// Events of a solve, recorded with `SolverWorkspace::with_event_log`, from which `replay`
+/// rebuilds the final tour and penalties without the distances.
+///
+/// Replaying a log and comparing with the route of another run, or with a run of a changed
+/// solver, tells at which move they diverge.
+#[derive(Clone, Default, Debug)]
+pub struct EventLog {
+    start: Vec<u32>,
+    events: Vec<Event>,
+}
+
+impl EventLog {
+    /// Clear the log for a solve starting from `path`.
+    pub(crate) fn start(&mut self, path: &Path) {
+        self.start.clear();
+        self.start.extend(path.0.iter().map(|&v| v as u32));
+        self.events.clear();
+    }
+
+    pub(crate) fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// The tour the search started from.
+    pub fn start_path(&self) -> Path {
+        Path::new(self.start.iter().map(|&v| v as usize).collect())
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Final tour and penalties of the solve, by applying the events to the start tour.
+    pub fn replay(&self) -> (Path, Penalties) {
+        self.replay_until(self.events.len())
+    }
+
+    /// Tour and penalties after the first `count` events.
+    pub fn replay_until(&self, count: usize) -> (Path, Penalties) {
+        let mut path = self.start_path();
+        let mut penalties = Penalties::from_size(path.len());
+        let mut incumbent: Option<Path> = None;
+
+        for event in &self.events[..count] {
+            match *event {
+                Event::Twist { from, to } => path.twist(from as usize, to as usize),
+                Event::Penalize { a, b } => penalties.inc((a as usize, b as usize)),
+                Event::Incumbent => incumbent = Some(path.clone()),
+                Event::Restore => path.clone_from(incumbent.as_ref().expect("restore without incumbent")),
+                Event::Rotate { start } => {
+                    let pos = path.0.iter().position(|&v| v == start as usize).unwrap();
+                    path.0.rotate_left(pos);
+                },
+            }
+        }
+
+        (path, penalties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::config::{Acceptance, GlsConfig};
+    use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::replay::Event;
+
+    #[test]
+    fn replay() {
+        let points = random_points(200);
+        let config = GlsConfig::default().with_acceptance(Acceptance::Threshold { percent: 0 }).with_depot(7);
+        let gls = GuidedLocalSearch::new(SymmetricMatrix::from_euclidean_coords(&points)).with_config(&config);
+
+        let mut workspace = SolverWorkspace::new().with_event_log();
+        let route = gls.solve_with_workspace(666, 30, &mut workspace);
+        let log = workspace.event_log().unwrap();
+
+        let (path, penalties) = log.replay();
+        assert_eq!(path, route.path);
+        assert!(penalties == *workspace.penalties());
+        assert_eq!(log.start_path(), gls.nearest_neighbor().path);
+        assert!(log.events().contains(&Event::Restore));
+
+        // Unlogged solves are the same
+        assert_eq!(gls.solve(666, 30), route);
+        assert!(SolverWorkspace::new().event_log().is_none());
+    }
+}
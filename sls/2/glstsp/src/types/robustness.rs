@@ -0,0 +1,117 @@
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::{Rng, SeedableRng};
+
+impl SymmetricMatrix {
+    /// Same costs, each multiplied by its own factor drawn uniformly in
+    /// `[1 - spread, 1 + spread]` and rounded, e.g. travel times misestimated by up to 10% for
+    /// a spread of `0.1`. The same `seed` gives the same matrix.
+    pub fn with_noise(&self, spread: f64, seed: u64) -> Self {
+        assert!((0.0..=1.0).contains(&spread));
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        self.map(|value| (value as f64 * rng.gen_range(1.0 - spread..=1.0 + spread)).round() as i32)
+    }
+
+    /// `count` matrices `with_noise`, with the seeds `seed`, `seed + 1`...
+    pub fn noisy_replicas(&self, spread: f64, count: usize, seed: u64) -> Vec<Self> {
+        (0..count as u64).map(|i| self.with_noise(spread, seed.wrapping_add(i))).collect()
+    }
+}
+
+/// Costs of a tour under several cost matrices, see `Route::evaluate_under`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Robustness {
+    /// Cost under each matrix, in order.
+    pub costs: Vec<i32>,
+    pub mean: f64,
+    /// Population standard deviation of the costs.
+    pub std: f64,
+    pub worst: i32,
+}
+
+impl Robustness {
+    /// Relative excess of the worst cost over `cost`, e.g. the cost the tour was planned
+    /// with, `0.05` being 5% above it.
+    pub fn worst_gap(&self, cost: i32) -> f64 {
+        (self.worst - cost) as f64 / cost as f64
+    }
+}
+
+impl Route {
+    /// Cost of the tour under each of `matrices`, e.g. `noisy_replicas` of the matrix it was
+    /// found with, to tell how much it relies on the costs being exact. Comparing two tours
+    /// of close costs this way favors the one whose cost varies less.
+    ///
+    /// Panics without matrices, or if one has a size other than the tour length.
+    pub fn evaluate_under(&self, matrices: &[SymmetricMatrix]) -> Robustness {
+        assert!(!matrices.is_empty());
+        let costs: Vec<_> = matrices
+            .iter()
+            .map(|matrix| {
+                assert_eq!(matrix.size(), self.path.len());
+                matrix.sum(self.path.edges())
+            })
+            .collect();
+
+        let count = costs.len() as f64;
+        let mean = costs.iter().map(|&cost| cost as f64).sum::<f64>() / count;
+        let variance = costs.iter().map(|&cost| (cost as f64 - mean).powi(2)).sum::<f64>() / count;
+        let worst = *costs.iter().max().unwrap();
+        Robustness { costs, mean, std: variance.sqrt(), worst }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::scattered_points;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use crate::types::route::Route;
+
+    fn create_matrix() -> SymmetricMatrix {
+        let points = scattered_points(40);
+        SymmetricMatrix::from_euclidean_coords(&points)
+    }
+
+    #[test]
+    fn noise() {
+        let matrix = create_matrix();
+        let noisy = matrix.with_noise(0.1, 666);
+
+        assert!(noisy == matrix.with_noise(0.1, 666));
+        assert!(noisy != matrix.with_noise(0.1, 667));
+        assert!(matrix.with_noise(0.0, 666) == matrix);
+        for (i, j) in matrix.pairs() {
+            assert_eq!(noisy[(i, j)], noisy[(j, i)]);
+            let (value, noisy) = (matrix[(i, j)] as f64, noisy[(i, j)] as f64);
+            assert!((noisy - value).abs() <= value * 0.1 + 0.5);
+        }
+
+        let replicas = matrix.noisy_replicas(0.1, 3, 666);
+        assert_eq!(replicas.len(), 3);
+        assert!(replicas[1] == matrix.with_noise(0.1, 667));
+    }
+
+    #[test]
+    fn evaluate_under() {
+        let matrix = create_matrix();
+        let route = GuidedLocalSearch::new(matrix.clone()).solve(666, 10);
+
+        let exact = route.evaluate_under(&[matrix.clone(), matrix.clone()]);
+        assert_eq!((exact.costs, exact.mean, exact.std, exact.worst), (vec![route.cost; 2], route.cost as f64, 0.0, route.cost));
+
+        let noisy = route.evaluate_under(&matrix.noisy_replicas(0.2, 20, 666));
+        assert_eq!(noisy.costs.len(), 20);
+        assert!(noisy.std > 0.0);
+        assert_eq!(noisy.worst, *noisy.costs.iter().max().unwrap());
+        assert!(noisy.worst as f64 >= noisy.mean);
+
+        let square = Route::new(4, Path::new(vec![0, 1, 2, 3]));
+        let costs = [SymmetricMatrix::from_fn(4, |_, _| 1), SymmetricMatrix::from_fn(4, |_, _| 2)];
+        let robustness = square.evaluate_under(&costs);
+        assert_eq!(robustness.worst_gap(4), 1.0);
+        assert_eq!((robustness.costs, robustness.mean, robustness.std), (vec![4, 8], 6.0, 2.0));
+    }
+}
@@ -1,9 +1,78 @@
+use crate::types::config::Orientation;
 use crate::types::path::Path;
+use crate::types::instance::Instance;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::point::Point;
+use std::time::Duration;
 
-#[derive(Eq, PartialEq, Debug)]
+/// Routes compare by cost and path, whatever their `info`.
+#[derive(Clone, Debug)]
 pub struct Route {
     pub cost: i32,
     pub path: Path,
+    /// How the route was computed, for routes returned by a GLS run.
+    pub info: Option<RunInfo>,
+}
+
+impl PartialEq for Route {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost && self.path == other.path
+    }
+}
+
+impl Eq for Route {}
+
+/// Everything needed to reproduce a route, and to check that a stored one matches its instance.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct RunInfo {
+    /// Version of this crate.
+    pub version: &'static str,
+    /// `GlsConfig::fingerprint` of the run options.
+    pub config: u64,
+    pub seed: u64,
+    /// `SymmetricMatrix::checksum` of the distances.
+    pub instance: u64,
+    pub elapsed: Duration,
+    /// Penalization steps run, fewer than requested if a deadline stopped the search. The
+    /// `steps` of the run count these under `StepUnit::Penalization`.
+    pub iterations: usize,
+    /// 2-opt moves evaluated by all the local searches of the run. Under
+    /// `StepUnit::Evaluations(n)`, the `steps` of the run are a budget of `steps * n` of these
+    /// after the first local search, `iterations` being the penalization steps it lasted.
+    pub evaluations: u64,
+    /// 2-opt moves applied by all the local searches of the run.
+    pub moves: u64,
+    /// Improving moves the local searches skipped, their gain being below the minimum (see
+    /// `GuidedLocalSearch::with_min_gain`).
+    pub skipped: u64,
+}
+
+/// One edge of a tour as driven, from a stop to the next one.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Leg {
+    pub from: usize,
+    pub to: usize,
+    pub distance: i32,
+    /// Distance from the start of the tour to the end of this leg.
+    pub cumulative: i32,
+    /// Coordinates of `from` and `to`, when the instance has some.
+    pub coords: Option<(Point, Point)>,
+    /// Labels of `from` and `to`, when the instance has some.
+    pub labels: Option<(String, String)>,
+}
+
+/// Edge lengths of a tour (see `Route::stats`).
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RouteStats {
+    pub min: i32,
+    pub max: i32,
+    pub mean: f64,
+    /// Mean of the two middle lengths for an even number of edges.
+    pub median: f64,
+    /// Population standard deviation.
+    pub std_dev: f64,
+    /// The 10 longest edges, as returned by `Route::longest_edges`.
+    pub longest: Vec<((usize, usize), i32)>,
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -15,6 +84,328 @@ pub enum HamiltonianResult {
 impl Route
 {
     pub fn new(cost: i32, path: Path) -> Route {
-        Route { cost, path }
+        Route { cost, path, info: None }
+    }
+
+    /// Travel cost of the tour, from the distances of `instance`, plus the service time of
+    /// each vertex.
+    pub fn total_duration(&self, instance: &Instance) -> i32 {
+        let travel = instance.distances().sum(self.path.edges());
+        let service: i32 = self.path.0.iter().map(|&v| instance.service_time(v)).sum();
+        travel + service
+    }
+
+    /// Cost of the two edges incident to each vertex, indexed by vertex.
+    pub fn vertex_costs(&self, instance: &Instance) -> Vec<i32> {
+        let distances = instance.distances();
+        let mut res = vec![0; self.path.len()];
+
+        for e in self.path.edges() {
+            let cost = distances[e];
+            res[e.0] += cost;
+            res[e.1] += cost;
+        }
+
+        res
+    }
+
+    /// The `k` longest edges of the tour with their costs, longest first.
+    ///
+    /// Edges with the same cost are kept in tour order.
+    pub fn longest_edges(&self, instance: &Instance, k: usize) -> Vec<((usize, usize), i32)> {
+        let distances = instance.distances();
+
+        let mut edges: Vec<_> = self.path.edges()
+            .map(|e| (e, distances[e]))
+            .collect();
+
+        edges.sort_by(|(_, a), (_, b)| b.cmp(a));
+        edges.truncate(k);
+        edges
+    }
+
+    /// Summary of the edge lengths of the tour, from the distances of `instance`, all zero for
+    /// an empty tour.
+    pub fn stats(&self, instance: &Instance) -> RouteStats {
+        if self.path.is_empty() {
+            return RouteStats::default();
+        }
+        let distances = instance.distances();
+        let mut lengths: Vec<i32> = self.path.edges().map(|e| distances[e]).collect();
+        lengths.sort_unstable();
+
+        let count = lengths.len();
+        let mean = lengths.iter().map(|&l| l as f64).sum::<f64>() / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (lengths[count / 2 - 1] as f64 + lengths[count / 2] as f64) / 2.0
+        } else {
+            lengths[count / 2] as f64
+        };
+        let variance = lengths.iter().map(|&l| (l as f64 - mean).powi(2)).sum::<f64>() / count as f64;
+
+        RouteStats {
+            min: lengths[0],
+            max: lengths[count - 1],
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            longest: self.longest_edges(instance, 10),
+        }
+    }
+
+    /// See `Path::tour_hash`.
+    pub fn tour_hash(&self) -> u64 {
+        self.path.tour_hash()
+    }
+
+    /// Relative excess of the cost over the one of `optimum`, `0.01` being 1% above it.
+    pub fn gap_to(&self, optimum: &Route) -> f64 {
+        self.gap_to_cost(optimum.cost)
+    }
+
+    /// Relative excess of the cost over `optimum`, e.g. the known optimal length of a TSPLIB
+    /// instance. An optimum of 0 gives 0 for a cost of 0 and infinity for any other.
+    pub fn gap_to_cost(&self, optimum: i32) -> f64 {
+        if optimum == 0 {
+            return if self.cost == 0 { 0.0 } else { f64::INFINITY };
+        }
+        (self.cost as f64 - optimum as f64) / optimum as f64
+    }
+
+    /// Valid tour of `instance` close to `self`, whose path may repeat vertices, miss some or
+    /// hold ids out of range, e.g. a tour edited in a spreadsheet.
+    ///
+    /// Out of range ids and repeated visits are dropped, keeping the first one, then each
+    /// missing vertex is inserted, by increasing id, where it costs the least.
+    pub fn repair(&self, instance: &Instance) -> Route {
+        let size = instance.size();
+        let distances = instance.distances();
+
+        let mut visited = vec![false; size];
+        let mut tour = Vec::with_capacity(size);
+        for vertex in self.path.0.iter().copied() {
+            if vertex < size && !visited[vertex] {
+                visited[vertex] = true;
+                tour.push(vertex);
+            }
+        }
+
+        insert_cheapest(distances, &mut tour, (0..size).filter(|&v| !visited[v]));
+
+        let path = Path::new(tour);
+        Route::new(distances.sum(path.edges()), path)
+    }
+
+    /// Same tour starting at `depot`, e.g. to hand it to a driver leaving from there.
+    ///
+    /// Panics if `depot` is not a vertex of the tour.
+    pub fn rotated_to_start(&self, depot: usize) -> Route {
+        let start = self.path.0.iter().position(|&v| v == depot).expect("depot not in the tour");
+        let mut route = self.clone();
+        route.path.0.rotate_left(start);
+        route
+    }
+
+    /// Same tour, from the same first vertex, driven in `orientation`. Tours of instances
+    /// without coordinates are returned as is.
+    pub fn oriented(&self, instance: &Instance, orientation: Orientation) -> Route {
+        match instance.points() {
+            Some(points) => self.oriented_by(orientation, |v| (points[v].x() as f64, points[v].y() as f64)),
+            None => self.clone(),
+        }
+    }
+
+    /// `oriented` for the coordinates `position(v)` of each vertex `v`.
+    pub(crate) fn oriented_by(&self, orientation: Orientation, position: impl Fn(usize) -> (f64, f64)) -> Route {
+        // Twice the signed area by the shoelace formula, positive counterclockwise
+        let area: f64 = self.path.edges()
+            .map(|(a, b)| {
+                let ((xa, ya), (xb, yb)) = (position(a), position(b));
+                xa * yb - xb * ya
+            })
+            .sum();
+
+        let reverse = match orientation {
+            Orientation::Any => false,
+            Orientation::Clockwise => area > 0.0,
+            Orientation::CounterClockwise => area < 0.0,
+        };
+        let mut route = self.clone();
+        if reverse && route.path.len() > 1 {
+            route.path.0[1..].reverse();
+        }
+        route
+    }
+
+    /// The legs of the tour in driving order, from its first vertex back to it.
+    pub fn legs(&self, instance: &Instance) -> Vec<Leg> {
+        let distances = instance.distances();
+        let points = instance.points();
+        let labels = instance.labels();
+
+        let mut cumulative = 0;
+        self.path.edges()
+            .map(|(from, to)| {
+                let distance = distances[(from, to)];
+                cumulative += distance;
+                let coords = points.map(|points| (points[from], points[to]));
+                let labels = labels.map(|labels| (labels[from].clone(), labels[to].clone()));
+                Leg { from, to, distance, cumulative, coords, labels }
+            })
+            .collect()
+    }
+}
+
+/// Insert each of `vertices`, in turn, in the closed tour `tour` where it costs the least.
+pub(crate) fn insert_cheapest(distances: &SymmetricMatrix, tour: &mut Vec<usize>, vertices: impl Iterator<Item=usize>) {
+    for vertex in vertices {
+        let len = tour.len();
+        let row = distances.row(vertex);
+        let pos = (0..len)
+            .min_by_key(|&i| {
+                let (a, b) = (tour[i], tour[(i + 1) % len]);
+                row[a] + row[b] - distances[(a, b)]
+            })
+            .map_or(0, |i| i + 1);
+        tour.insert(pos, vertex);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::config::Orientation;
+    use crate::types::instance::Instance;
+    use crate::types::point::Point;
+    use crate::types::route::{Route, RouteStats};
+    use crate::types::path::Path;
+
+    fn create_instance() -> Instance {
+        crate::instance! { 0-1: 2, 0-2: 7, 0-3: 3, 1-2: 4, 1-3: 1, 2-3: 9 }
+    }
+
+    #[test]
+    fn legs() {
+        let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+        let legs = route.legs(&create_instance());
+
+        let actual: Vec<_> = legs.iter().map(|leg| (leg.from, leg.to, leg.distance, leg.cumulative)).collect();
+        assert_eq!(actual, vec![(0, 1, 2, 2), (1, 2, 4, 6), (2, 3, 9, 15), (3, 0, 3, 18)]);
+        assert_eq!(legs[0].coords, None);
+        assert_eq!(legs[0].labels, None);
+
+        let labels = vec!["depot".to_string(), "a".to_string(), "b".to_string(), "c".to_string()];
+        let legs = route.legs(&create_instance().with_labels(labels));
+        assert_eq!(legs[3].labels, Some(("c".to_string(), "depot".to_string())));
+
+        let points = vec![Point::new(0, 0), Point::new(3, 4), Point::new(3, 0)];
+        let legs = Route::new(12, Path::new(vec![0, 1, 2])).legs(&Instance::from_points(points.clone()));
+        assert_eq!(legs[2].coords, Some((points[2], points[0])));
+        assert_eq!(legs[2].cumulative, 12);
+    }
+
+    #[test]
+    fn total_duration() {
+        let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+        assert_eq!(route.total_duration(&create_instance()), 18);
+
+        let instance = create_instance().with_service_times(vec![5, 0, 10, 1]);
+        assert_eq!(route.total_duration(&instance), 34);
+    }
+
+    #[test]
+    fn rotated_to_start() {
+        let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+        assert_eq!(route.rotated_to_start(2), Route::new(18, Path::new(vec![2, 3, 0, 1])));
+        assert_eq!(route.rotated_to_start(0), route);
+    }
+
+    #[test]
+    fn oriented() {
+        // A square, counterclockwise from its bottom-left corner
+        let points = vec![Point::new(0, 0), Point::new(10, 0), Point::new(10, 10), Point::new(0, 10)];
+        let instance = Instance::from_points(points);
+        let route = Route::new(40, Path::new(vec![1, 2, 3, 0]));
+
+        assert_eq!(route.oriented(&instance, Orientation::CounterClockwise), route);
+        assert_eq!(route.oriented(&instance, Orientation::Any), route);
+        let clockwise = route.oriented(&instance, Orientation::Clockwise);
+        assert_eq!(clockwise, Route::new(40, Path::new(vec![1, 0, 3, 2])));
+        assert_eq!(clockwise.oriented(&instance, Orientation::Clockwise), clockwise);
+    }
+
+    #[test]
+    fn repair() {
+        let instance = create_instance();
+
+        // 2 repeated, 7 out of range, 3 missing
+        let broken = Route::new(0, Path::new(vec![0, 2, 7, 1, 2]));
+        let actual = broken.repair(&instance);
+        assert_eq!(actual, Route::new(15, Path::new(vec![0, 2, 1, 3])));
+
+        let valid = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+        assert_eq!(valid.repair(&instance), valid);
+
+        let empty = Route::new(0, Path(vec![9, 9]));
+        assert!(empty.repair(&instance).path.is_hamiltonian());
+    }
+
+    #[test]
+    fn vertex_costs() {
+        let instance = create_instance();
+        let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+
+        assert_eq!(route.vertex_costs(&instance), vec![5, 6, 13, 12]);
+    }
+
+    #[test]
+    fn stats() {
+        let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+        let stats = route.stats(&create_instance());
+
+        assert_eq!((stats.min, stats.max), (2, 9));
+        assert_eq!(stats.mean, 4.5);
+        assert_eq!(stats.median, 3.5);
+        assert!((stats.std_dev - 2.69258).abs() < 1e-5);
+        assert_eq!(stats.longest, route.longest_edges(&create_instance(), 4));
+
+        let stats = Route::new(13, Path::new(vec![3, 1, 2])).stats(&create_instance());
+        assert_eq!(stats.median, 4.0);
+
+        assert_eq!(Route::new(0, Path(vec![])).stats(&create_instance()), RouteStats::default());
+    }
+
+    #[test]
+    fn gap() {
+        let optimum = Route::new(400, Path::new(vec![0, 1, 2, 3]));
+        assert_eq!(Route::new(410, Path::new(vec![0, 2, 1, 3])).gap_to(&optimum), 0.025);
+        assert_eq!(optimum.gap_to(&optimum), 0.0);
+        assert_eq!(Route::new(300, Path::new(vec![0, 1])).gap_to_cost(400), -0.25);
+        assert_eq!(Route::new(0, Path::new(vec![0, 1])).gap_to_cost(0), 0.0);
+        assert_eq!(Route::new(300, Path::new(vec![0, 1])).gap_to_cost(0), f64::INFINITY);
+    }
+
+    #[cfg(test)]
+    mod longest_edges {
+        use crate::types::route::tests::create_instance;
+        use crate::types::route::Route;
+        use crate::types::path::Path;
+
+        #[test]
+        fn some() {
+            let instance = create_instance();
+            let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+
+            let expected = vec![((2, 3), 9), ((1, 2), 4)];
+            assert_eq!(route.longest_edges(&instance, 2), expected);
+        }
+
+        #[test]
+        fn more_than_available() {
+            let instance = create_instance();
+            let route = Route::new(18, Path::new(vec![0, 1, 2, 3]));
+
+            let expected = vec![((2, 3), 9), ((1, 2), 4), ((3, 0), 3), ((0, 1), 2)];
+            assert_eq!(route.longest_edges(&instance, 10), expected);
+        }
     }
 }
@@ -0,0 +1,155 @@
+use crate::types::point::Point;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+
+/// How `representatives` spreads the chosen points over the instance.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Selection {
+    /// From a point drawn with the seed, repeatedly the point farthest from the chosen ones.
+    /// Covers the extent of the instance, outliers included.
+    FarthestPoint,
+    /// The points closest to the centers of `count` clusters, refined by `iterations` rounds of
+    /// k-means from the farthest point selection. Follows the density of the instance.
+    KMeans { iterations: usize },
+}
+
+/// Indices of `count` points spread over `points` by `selection`, in increasing order, e.g.
+/// to solve a smaller instance (see `Instance::subset`) whose tour outlines the full one.
+pub fn representatives(points: &[Point], count: usize, selection: Selection, seed: u64) -> Vec<usize> {
+    assert!(count <= points.len());
+    let mut res = match selection {
+        Selection::FarthestPoint => farthest_points(points, count, seed),
+        Selection::KMeans { iterations } => k_means(points, count, iterations, seed),
+    };
+    res.sort_unstable();
+    res
+}
+
+/// The tour of a subset mapped back to the full instance: the vertex `subset[v]` for each
+/// vertex `v` of `route`, a tour of `Instance::subset(subset)`.
+///
+/// The result visits the representatives only, the other vertices being inserted afterwards.
+pub fn skeleton(route: &Route, subset: &[usize]) -> Vec<usize> {
+    assert_eq!(route.path.len(), subset.len());
+    route.path.as_slice().iter().map(|&v| subset[v]).collect()
+}
+
+fn farthest_points(points: &[Point], count: usize, seed: u64) -> Vec<usize> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+    let first = rng.gen_range(0..points.len());
+
+    let mut res = vec![first];
+    // Distance from each point to the closest chosen one
+    let mut to_chosen: Vec<i32> = points.iter().map(|p| p.dist(points[first])).collect();
+    // Below any distance, so chosen points are not chosen again, e.g. among duplicates
+    to_chosen[first] = -1;
+    while res.len() < count {
+        let (_, Reverse(next)) = to_chosen.iter()
+            .enumerate()
+            .map(|(i, &distance)| (distance, Reverse(i)))
+            .max()
+            .unwrap();
+        res.push(next);
+        for (distance, point) in to_chosen.iter_mut().zip(points) {
+            *distance = (*distance).min(point.dist(points[next]));
+        }
+        to_chosen[next] = -1;
+    }
+    res
+}
+
+fn k_means(points: &[Point], count: usize, iterations: usize, seed: u64) -> Vec<usize> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let position = |p: Point| (p.x() as f64, p.y() as f64);
+    let squared = |(x, y): (f64, f64), (cx, cy): (f64, f64)| (x - cx).powi(2) + (y - cy).powi(2);
+    let closest = |centers: &[(f64, f64)], p: (f64, f64)| {
+        (0..centers.len()).min_by(|&a, &b| squared(p, centers[a]).total_cmp(&squared(p, centers[b]))).unwrap()
+    };
+
+    let mut centers: Vec<_> = farthest_points(points, count, seed).into_iter().map(|i| position(points[i])).collect();
+    for _ in 0..iterations {
+        let mut sums = vec![(0.0, 0.0, 0usize); count];
+        for &point in points {
+            let p = position(point);
+            let sum = &mut sums[closest(&centers, p)];
+            *sum = (sum.0 + p.0, sum.1 + p.1, sum.2 + 1);
+        }
+        // Empty clusters keep their center
+        for (center, &(x, y, size)) in centers.iter_mut().zip(&sums) {
+            if size > 0 {
+                *center = (x / size as f64, y / size as f64);
+            }
+        }
+    }
+
+    // The closest point to each center not chosen for another one
+    let mut chosen = vec![false; points.len()];
+    centers.iter()
+        .map(|&center| {
+            let i = (0..points.len())
+                .filter(|&i| !chosen[i])
+                .min_by(|&a, &b| squared(position(points[a]), center).total_cmp(&squared(position(points[b]), center)))
+                .unwrap();
+            chosen[i] = true;
+            i
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::instance::Instance;
+    use crate::types::point::Point;
+    use crate::types::sampling::{representatives, skeleton, Selection};
+
+    #[test]
+    fn farthest_point() {
+        // At least 3 apart on a line of length 10, wherever it starts
+        let line: Vec<_> = (0..=10).map(|x| Point::new(x, 0)).collect();
+        for seed in 0..10 {
+            let actual = representatives(&line, 3, Selection::FarthestPoint, seed);
+            assert!(actual.windows(2).all(|pair| pair[1] - pair[0] >= 3), "{:?}", actual);
+        }
+
+        let same = vec![Point::new(1, 1); 3];
+        assert_eq!(representatives(&same, 3, Selection::FarthestPoint, 666), vec![0, 1, 2]);
+        assert!(representatives(&same, 0, Selection::KMeans { iterations: 5 }, 666).is_empty());
+    }
+
+    #[test]
+    fn k_means() {
+        // Two 10 x 10 grids, their centers at (104.5, 104.5) and (804.5, 804.5)
+        let mut points: Vec<_> = (0..100).map(|i| Point::new(100 + i % 10, 100 + i / 10)).collect();
+        points.extend((0..100).map(|i| Point::new(800 + i % 10, 800 + i / 10)));
+
+        let actual = representatives(&points, 2, Selection::KMeans { iterations: 10 }, 666);
+        assert_eq!(actual, vec![44, 144]);
+        // The point farthest from the drawn one is a corner
+        let farthest = representatives(&points, 2, Selection::FarthestPoint, 666);
+        assert!(farthest.iter().any(|&i| [0, 9].contains(&(i % 10)) && [0, 9].contains(&(i % 100 / 10))), "{:?}", farthest);
+    }
+
+    #[test]
+    fn skeleton_of_subset() {
+        let points = random_points(300);
+        let instance = Instance::from_points(points.clone());
+        let subset = representatives(&points, 30, Selection::KMeans { iterations: 5 }, 666);
+        assert_eq!(subset.len(), 30);
+        assert!(subset.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let route = GuidedLocalSearch::from_instance(&instance.subset(&subset)).solve(666, 10);
+        let actual = skeleton(&route, &subset);
+        assert_eq!(actual.len(), 30);
+        assert!(actual.iter().all(|v| subset.contains(v)));
+        assert_eq!(instance.distances().sum(actual.iter().copied().zip(actual.iter().copied().cycle().skip(1))), route.cost);
+    }
+}
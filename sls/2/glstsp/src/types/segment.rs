@@ -0,0 +1,120 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use crate::types::penalties::Penalties;
+use crate::types::route::Route;
+use rand_mt::Mt64;
+use rand::SeedableRng;
+use std::ops::Range;
+
+impl GuidedLocalSearch {
+    /// Re-optimize the vertices at the positions `range` of the tour with `steps` GLS steps,
+    /// the rest of the tour and the vertices at both ends of the range staying in place.
+    ///
+    /// The segment is solved as a tour of its own vertices in which the edge between its ends
+    /// stands for the rest of the tour and is never removed. The route only changes if the
+    /// segment gets shorter.
+    pub fn optimize_segment(&self, route: &mut Route, range: Range<usize>, seed: u64, steps: usize) {
+        assert!(range.start <= range.end && range.end <= route.path.len());
+        let segment = &route.path.0[range.clone()];
+        let size = segment.len();
+        if size < 4 {
+            return;
+        }
+
+        let distances = self.distances();
+        let mut sub = SymmetricMatrix::from_size(size);
+        for i in 0..size {
+            for j in i + 1..size {
+                sub.set(i, j, distances[(segment[i], segment[j])]);
+            }
+        }
+
+        // Tour 0, 1, ..., size - 1 of the segment, closed by the edge between its ends
+        let mut path = Path::sequential(size);
+        let cost = sub.sum(path.edges()) - sub[(0, size - 1)];
+        let penalty_factor = (0.3 * (cost as f64 / size as f64)) as i32;
+
+        // Removing the closing edge must never pay off, even with the largest penalties. Clamped,
+        // it may: the segment is then left as is by the check on the cost below.
+        let max_distance = sub.max().unwrap() as i64;
+        let closing = 2 * (max_distance + penalty_factor as i64 * steps as i64) + 1;
+        let closing = closing.min(i32::MAX as i64) as i32;
+        sub.set(0, size - 1, -closing);
+        let gls = GuidedLocalSearch::new(sub);
+
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(seed);
+        let neighborhood = gls.neighborhood(&mut rng);
+        let mut penalties = Penalties::from_size(size);
+
+        gls.local_search(&mut path, &neighborhood, 0, &mut penalties);
+        let mut best = Route::new(gls.cost(&path), path.clone());
+
+        for _ in 0..steps {
+            gls.penalize(&path, &mut penalties, &mut rng);
+            gls.local_search(&mut path, &neighborhood, penalty_factor, &mut penalties);
+
+            let cost = gls.cost(&path);
+            if cost < best.cost {
+                best = Route::new(cost, path.clone());
+            }
+        }
+
+        gls.local_search(&mut best.path, &neighborhood, 0, &mut penalties);
+        best.cost = gls.cost(&best.path);
+        if best.cost as i64 + closing as i64 >= cost as i64 {
+            return;
+        }
+
+        // Open the tour at the closing edge, from the first vertex of the segment to the last one
+        let start = best.path.0.iter().position(|&v| v == 0).unwrap();
+        let forward = best.path[(start + 1) % size] != size - 1;
+        let order: Vec<_> = (0..size)
+            .map(|k| if forward { (start + k) % size } else { (start + size - k) % size })
+            .map(|v| best.path[v])
+            .collect();
+        debug_assert_eq!(order[size - 1], size - 1);
+
+        let reordered: Vec<_> = order.iter().map(|&v| segment[v]).collect();
+        route.path.0[range].copy_from_slice(&reordered);
+        route.cost = self.cost(&route.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_gls;
+    use rand_mt::Mt64;
+    use rand::SeedableRng;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn optimize_segment() {
+        let gls = random_gls(100);
+        let mut route = gls.solve(666, 5);
+
+        // Scramble the inside of the segment
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(666);
+        route.path.0[21..59].shuffle(&mut rng);
+        route.cost = gls.cost(&route.path);
+        let scrambled = route.clone();
+
+        gls.optimize_segment(&mut route, 20..60, 666, 10);
+
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, gls.cost(&route.path));
+        assert!(route.cost < scrambled.cost);
+        assert_eq!(route.path.0[..21], scrambled.path.0[..21]);
+        assert_eq!(route.path.0[59..], scrambled.path.0[59..]);
+    }
+
+    #[test]
+    fn short_segment() {
+        let gls = random_gls(100);
+        let mut route = gls.nearest_neighbor();
+        let expected = route.clone();
+
+        gls.optimize_segment(&mut route, 10..13, 666, 10);
+        assert_eq!(route, expected);
+    }
+}
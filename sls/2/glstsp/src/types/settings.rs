@@ -0,0 +1,219 @@
+use crate::types::config::{Acceptance, Arithmetic, ConfigError, GlsConfig, Orientation, Overflow, StepUnit, TieBreak, UtilityComparison};
+use std::env;
+#[cfg(feature = "config-file")]
+use std::fs;
+#[cfg(feature = "config-file")]
+use std::io;
+
+/// Prefix of the environment variables read by `GlsConfig::from_env`, followed by the key in
+/// upper case, e.g. `GLSTSP_STEPS`.
+pub const ENV_PREFIX: &str = "GLSTSP_";
+
+/// Keys of the settings, each setting the `GlsConfig` field of the same name:
+///
+/// - `seed`, `steps`, `alpha_percent`, `min_gain` and `depot`: integers.
+/// - `acceptance`: `always`, or `threshold:<percent>`.
+/// - `utility`: `truncated` or `exact`.
+/// - `tie_break`: `all`, `random` or `longest`.
+/// - `arithmetic`: `float` or `integer`.
+/// - `orientation`: `any`, `clockwise` or `counterclockwise`.
+/// - `overflow`: `unchecked`, `saturate` or `error`.
+/// - `step_unit`: `penalization`, or `evaluations:<count>`.
+pub const KEYS: &[&str] = &[
+    "seed", "steps", "acceptance", "utility", "tie_break", "arithmetic", "alpha_percent", "min_gain", "depot",
+    "orientation", "overflow", "step_unit",
+];
+
+/// Settings that can't make a `GlsConfig`.
+#[derive(Eq, PartialEq, Debug)]
+pub enum SettingsError {
+    #[cfg(feature = "config-file")]
+    Io(io::ErrorKind),
+    /// The file is not TOML, with the message of the parser.
+    #[cfg(feature = "config-file")]
+    Toml(String),
+    /// Not one of `KEYS`, e.g. a typo in a file.
+    UnknownKey(String),
+    InvalidValue { key: String, value: String },
+    /// The settings parse, but the configuration doesn't pass `GlsConfig::validate`.
+    Config(ConfigError),
+}
+
+impl GlsConfig {
+    /// Default configuration changed by the `GLSTSP_<KEY>` environment variables that are set,
+    /// e.g. `GLSTSP_STEPS=500 GLSTSP_ACCEPTANCE=threshold:5`, for deployments to tune the
+    /// solver without rebuilding. See `KEYS` for the keys and their values.
+    pub fn from_env() -> Result<Self, SettingsError> {
+        Self::from_lookup(|key| env::var(format!("{}{}", ENV_PREFIX, key.to_ascii_uppercase())).ok())
+    }
+
+    /// Default configuration changed by the keys for which `lookup` has a value.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, SettingsError> {
+        let mut config = Self::default();
+        for key in KEYS {
+            if let Some(value) = lookup(key) {
+                config.set(key, value.trim())?;
+            }
+        }
+        config.validate().map_err(SettingsError::Config)
+    }
+
+    /// Default configuration changed by the top-level keys of the TOML file at `path`, e.g.
+    /// `steps = 500` and `tie_break = "random"`. See `KEYS` for the keys and their values.
+    #[cfg(feature = "config-file")]
+    pub fn from_toml(path: impl AsRef<std::path::Path>) -> Result<Self, SettingsError> {
+        let text = fs::read_to_string(path).map_err(|e| SettingsError::Io(e.kind()))?;
+        Self::from_toml_str(&text)
+    }
+
+    /// Same as `from_toml`, from the content of the file.
+    #[cfg(feature = "config-file")]
+    pub fn from_toml_str(text: &str) -> Result<Self, SettingsError> {
+        let table = match text.parse::<toml::Value>().map_err(|e| SettingsError::Toml(e.to_string()))? {
+            toml::Value::Table(table) => table,
+            _ => return Err(SettingsError::Toml("not a table".to_string())),
+        };
+
+        let mut config = Self::default();
+        for (key, value) in &table {
+            let value = match value {
+                toml::Value::String(value) => value.clone(),
+                toml::Value::Integer(value) => value.to_string(),
+                _ => return Err(SettingsError::InvalidValue { key: key.clone(), value: value.to_string() }),
+            };
+            config.set(key, &value)?;
+        }
+        config.validate().map_err(SettingsError::Config)
+    }
+
+    /// Set the field of `key` to `value`, parsed as documented in `KEYS`.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), SettingsError> {
+        let invalid = || SettingsError::InvalidValue { key: key.to_string(), value: value.to_string() };
+        match key {
+            "seed" => self.seed = value.parse().map_err(|_| invalid())?,
+            "steps" => self.steps = value.parse().map_err(|_| invalid())?,
+            "depot" => self.depot = Some(value.parse().map_err(|_| invalid())?),
+            "alpha_percent" => self.alpha_percent = value.parse().map_err(|_| invalid())?,
+            "min_gain" => self.min_gain = value.parse().map_err(|_| invalid())?,
+            "acceptance" => {
+                self.acceptance = match value.split_once(':') {
+                    None if value == "always" => Acceptance::Always,
+                    Some(("threshold", percent)) => Acceptance::Threshold { percent: percent.trim().parse().map_err(|_| invalid())? },
+                    _ => return Err(invalid()),
+                }
+            },
+            "utility" => {
+                self.utility = match value {
+                    "truncated" => UtilityComparison::Truncated,
+                    "exact" => UtilityComparison::Exact,
+                    _ => return Err(invalid()),
+                }
+            },
+            "tie_break" => {
+                self.tie_break = match value {
+                    "all" => TieBreak::All,
+                    "random" => TieBreak::Random,
+                    "longest" => TieBreak::Longest,
+                    _ => return Err(invalid()),
+                }
+            },
+            "arithmetic" => {
+                self.arithmetic = match value {
+                    "float" => Arithmetic::Float,
+                    "integer" => Arithmetic::Integer,
+                    _ => return Err(invalid()),
+                }
+            },
+            "orientation" => {
+                self.orientation = match value {
+                    "any" => Orientation::Any,
+                    "clockwise" => Orientation::Clockwise,
+                    "counterclockwise" => Orientation::CounterClockwise,
+                    _ => return Err(invalid()),
+                }
+            },
+            "overflow" => {
+                self.overflow = match value {
+                    "unchecked" => Overflow::Unchecked,
+                    "saturate" => Overflow::Saturate,
+                    "error" => Overflow::Error,
+                    _ => return Err(invalid()),
+                }
+            },
+            "step_unit" => {
+                self.step_unit = match value.split_once(':') {
+                    None if value == "penalization" => StepUnit::Penalization,
+                    Some(("evaluations", count)) => StepUnit::Evaluations(count.trim().parse().map_err(|_| invalid())?),
+                    _ => return Err(invalid()),
+                }
+            },
+            _ => return Err(SettingsError::UnknownKey(key.to_string())),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::config::{Acceptance, ConfigError, GlsConfig, Overflow, StepUnit, TieBreak};
+    use crate::types::settings::SettingsError;
+
+    fn from_pairs(pairs: &[(&str, &str)]) -> Result<GlsConfig, SettingsError> {
+        GlsConfig::from_lookup(|key| pairs.iter().find(|&&(k, _)| k == key).map(|&(_, v)| v.to_string()))
+    }
+
+    #[test]
+    fn lookup() {
+        assert_eq!(from_pairs(&[]), Ok(GlsConfig::default()));
+
+        let config = from_pairs(&[("steps", "500"), ("acceptance", "threshold:5"), ("tie_break", " random "), ("depot", "3")]);
+        let expected = GlsConfig::default()
+            .with_steps(500)
+            .with_acceptance(Acceptance::Threshold { percent: 5 })
+            .with_tie_break(TieBreak::Random)
+            .with_depot(3);
+        assert_eq!(config, Ok(expected));
+
+        let invalid = |key: &str, value: &str| Err(SettingsError::InvalidValue { key: key.to_string(), value: value.to_string() });
+        assert_eq!(from_pairs(&[("steps", "many")]), invalid("steps", "many"));
+        assert_eq!(from_pairs(&[("acceptance", "threshold")]), invalid("acceptance", "threshold"));
+        assert_eq!(from_pairs(&[("overflow", "wrap")]), invalid("overflow", "wrap"));
+        assert_eq!(from_pairs(&[("steps", "0")]), Err(SettingsError::Config(ConfigError::ZeroSteps)));
+        assert_eq!(
+            from_pairs(&[("step_unit", "evaluations:5000")]),
+            Ok(GlsConfig::default().with_step_unit(StepUnit::Evaluations(5000))),
+        );
+    }
+
+    #[test]
+    fn from_env() {
+        // Only this test sets the variables
+        std::env::set_var("GLSTSP_OVERFLOW", "saturate");
+        let config = GlsConfig::from_env();
+        std::env::remove_var("GLSTSP_OVERFLOW");
+        assert_eq!(config, Ok(GlsConfig::default().with_overflow(Overflow::Saturate)));
+    }
+
+    #[cfg(feature = "config-file")]
+    #[test]
+    fn toml() {
+        let text = "steps = 250\nalpha_percent = 20\narithmetic = \"integer\"\n";
+        let expected = GlsConfig::default()
+            .with_steps(250)
+            .with_alpha_percent(20)
+            .with_arithmetic(crate::types::config::Arithmetic::Integer);
+        assert_eq!(GlsConfig::from_toml_str(text), Ok(expected));
+
+        let path = std::env::temp_dir().join("glstsp-settings.toml");
+        std::fs::write(&path, text).unwrap();
+        assert_eq!(GlsConfig::from_toml(&path), Ok(expected));
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(GlsConfig::from_toml_str("step = 250"), Err(SettingsError::UnknownKey("step".to_string())));
+        assert!(matches!(GlsConfig::from_toml_str("steps = "), Err(SettingsError::Toml(_))));
+        assert_eq!(
+            GlsConfig::from_toml_str("steps = 2.5"),
+            Err(SettingsError::InvalidValue { key: "steps".to_string(), value: "2.5".to_string() }),
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use crate::types::config::GlsConfig;
+use crate::types::coord::Coord2;
+use crate::types::gls::{GuidedLocalSearch, SolverWorkspace};
+use crate::types::instance::Instance;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::observer::{Observer, Progress};
+use crate::types::perturb::double_bridge;
+use crate::types::route::Route;
+use crate::types::tsplib::{Tsplib, TsplibError};
+use rand_mt::Mt64;
+use rand::SeedableRng;
+use std::time::{Duration, Instant};
+
+/// GLS steps of each run of `solve_until_gap`, before restarting from the best tour.
+const RESTART_STEPS: usize = 1000;
+
+/// Why `solve_until_gap` returned.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GapStop {
+    /// The tour is within the gap.
+    Reached,
+    /// Time ran out first, the tour being the best found.
+    TimeLimit,
+}
+
+/// Tour of `points` with the Euclidean distances rounded to the nearest integer, as TSPLIB
+/// `EUC_2D`. Scale the coordinates first if they are too close for integer distances.
+///
+/// Panics if the depot of `config` is not one of the points, see `GlsConfig::validate_for`.
+pub fn solve_points(points: &[(f64, f64)], config: &GlsConfig) -> Route {
+    let coords: Vec<_> = points.iter().map(|&(x, y)| Coord2::new([x, y])).collect();
+    GuidedLocalSearch::new(SymmetricMatrix::from_coords(&coords, 1.0))
+        .with_config(config)
+        .solve(config.seed, config.steps)
+        .oriented_by(config.orientation, |v| points[v])
+}
+
+/// Tour of the TSPLIB instance stored at `path`, see `Tsplib::read_file`. `config` is checked
+/// by `GlsConfig::validate_for`.
+pub fn solve_file(path: impl AsRef<std::path::Path>, config: &GlsConfig) -> Result<Route, TsplibError> {
+    let instance = Tsplib::read_file(path)?.into_instance();
+    config.validate_for(instance.size()).map_err(TsplibError::Config)?;
+    Ok(GuidedLocalSearch::from_instance(&instance)
+        .with_config(config)
+        .solve(config.seed, config.steps)
+        .oriented(&instance, config.orientation))
+}
+
+/// Tour of `instance` within `gap` of `bound`, the known optimal cost or a lower bound on it,
+/// e.g. `0.01` for 1% above it, or the best one found in `max_time`.
+///
+/// GLS runs with the default configuration, restarting every `RESTART_STEPS` steps from the
+/// best tour kicked by a double bridge, with the next seed. The target being checked after
+/// every step, the search stops as soon as it is met.
+pub fn solve_until_gap(instance: &Instance, bound: i32, gap: f64, max_time: Duration) -> (Route, GapStop) {
+    let start = Instant::now();
+    let deadline = Some(start + max_time);
+    let config = GlsConfig::default();
+    let gls = GuidedLocalSearch::from_instance(instance).with_config(&config);
+    let target = (bound as f64 * (1.0 + gap)).floor() as i32;
+
+    let mut rng: Mt64 = SeedableRng::seed_from_u64(config.seed);
+    let mut workspace = SolverWorkspace::new();
+    let mut observer = Target { cost: target, best: None };
+    let mut seed = config.seed;
+    let route = gls.search_in(seed, RESTART_STEPS, deadline, &mut observer, &mut workspace);
+    let mut best = observer.best(route);
+
+    while best.cost > target && start.elapsed() < max_time {
+        let mut route = best.clone();
+        if route.path.len() >= 8 {
+            double_bridge(&mut route.path, &mut rng);
+            route.cost = gls.cost(&route.path);
+        }
+        seed = seed.wrapping_add(1);
+        observer.best = None;
+        let route = gls.search_from(route, seed, RESTART_STEPS, deadline, &mut observer, &mut workspace);
+        let route = observer.best(route);
+        if route.cost < best.cost {
+            best = route;
+        }
+    }
+
+    let stop = if best.cost <= target { GapStop::Reached } else { GapStop::TimeLimit };
+    (gls.pinned(best), stop)
+}
+
+/// Observer of `solve_until_gap`, keeping the best tour of a run and stopping it at the target.
+struct Target {
+    cost: i32,
+    best: Option<Route>,
+}
+
+impl Target {
+    /// Best tour of the run that returned `last`.
+    fn best(&mut self, last: Route) -> Route {
+        match self.best.take() {
+            Some(best) if best.cost < last.cost => best,
+            _ => last,
+        }
+    }
+}
+
+impl Observer for Target {
+    fn on_step(&mut self, progress: &Progress) {
+        if self.best.as_ref().is_none_or(|best| progress.cost < best.cost) {
+            self.best = Some(Route::new(progress.cost, progress.path.clone()));
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        self.best.as_ref().is_some_and(|best| best.cost <= self.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::config::{ConfigError, GlsConfig, Orientation};
+    use crate::types::solve::{solve_file, solve_points, solve_until_gap, GapStop};
+    use crate::types::tsplib::{Tsplib, TsplibError};
+    use std::time::Duration;
+
+    #[test]
+    fn points() {
+        let points = [(0.0, 0.0), (30.0, 0.0), (10.0, 0.0), (30.0, 40.0), (0.0, 40.0), (20.0, 40.0)];
+        let route = solve_points(&points, &GlsConfig::default().with_steps(10));
+
+        assert!(route.path.is_hamiltonian());
+        assert_eq!(route.cost, 140);
+    }
+
+    #[test]
+    fn pinned() {
+        // A square, counterclockwise 0, 2, 3, 1
+        let points = [(0.0, 0.0), (0.0, 10.0), (10.0, 0.0), (10.0, 10.0)];
+        let config = GlsConfig::default().with_steps(10).with_depot(3);
+
+        let clockwise = solve_points(&points, &config.with_orientation(Orientation::Clockwise));
+        assert_eq!(clockwise.path.as_slice(), &[3, 2, 0, 1]);
+        let counter = solve_points(&points, &config.with_orientation(Orientation::CounterClockwise));
+        assert_eq!(counter.path.as_slice(), &[3, 1, 0, 2]);
+        assert_eq!(clockwise.cost, 40);
+        assert_eq!(solve_points(&points, &config).path.as_slice()[0], 3);
+    }
+
+    #[test]
+    fn file() {
+        let config = GlsConfig::default().with_steps(10);
+        let route = solve_file("data/tsplib/burma14.tsp", &config).unwrap();
+        assert_eq!(route.path.len(), 14);
+        // Optimum 3323
+        assert!(route.cost < 3323 * 103 / 100, "{}", route.cost);
+
+        let depot = solve_file("data/tsplib/burma14.tsp", &config.with_depot(14));
+        assert_eq!(depot.err(), Some(TsplibError::Config(ConfigError::DepotOutOfRange { depot: 14, size: 14 })));
+
+        let missing = solve_file("data/tsplib/missing.tsp", &config);
+        assert_eq!(missing.err(), Some(TsplibError::Io(std::io::ErrorKind::NotFound)));
+    }
+
+    #[test]
+    fn until_gap() {
+        let instance = Tsplib::read_file("data/tsplib/burma14.tsp").unwrap().into_instance();
+        let (route, stop) = solve_until_gap(&instance, 3323, 0.0, Duration::from_secs(10));
+        assert_eq!((route.cost, stop), (3323, GapStop::Reached));
+
+        // Below the optimum
+        let (route, stop) = solve_until_gap(&instance, 3000, 0.0, Duration::from_millis(50));
+        assert_eq!((route.cost, stop), (3323, GapStop::TimeLimit));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::types::point::Point;
+
+/// Points bucketed in square cells, to find the points near a location without scanning them
+/// all, e.g. to generate the moves of a local search between close vertices only.
+///
+/// Points are referred to by their index in the slice the grid is built from.
+#[derive(Clone, Debug)]
+pub struct SpatialGrid {
+    points: Vec<Point>,
+    origin: (i32, i32),
+    cell: i32,
+    columns: usize,
+    rows: usize,
+    /// Indices of the points of each cell, row by row.
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialGrid {
+    /// Grid of cells of side `cell`, enlarged if needed to keep about one cell per point. The
+    /// queries are fastest when a cell holds a few points, or when its side is close to the
+    /// radius of the range queries.
+    pub fn new(points: &[Point], cell: i32) -> Self {
+        let min_x = points.iter().map(|p| p.x()).min().unwrap_or(0);
+        let min_y = points.iter().map(|p| p.y()).min().unwrap_or(0);
+        let width = points.iter().map(|p| p.x() - min_x).max().unwrap_or(0) as i64 + 1;
+        let height = points.iter().map(|p| p.y() - min_y).max().unwrap_or(0) as i64 + 1;
+
+        let mut cell = cell.max(1) as i64;
+        while (width / cell + 1) * (height / cell + 1) > 4 * points.len().max(1) as i64 {
+            cell *= 2;
+        }
+        let cell = cell.min(i32::MAX as i64) as i32;
+        let (columns, rows) = ((width / cell as i64 + 1) as usize, (height / cell as i64 + 1) as usize);
+
+        let mut res = Self {
+            points: points.to_vec(),
+            origin: (min_x, min_y),
+            cell,
+            columns,
+            rows,
+            cells: vec![Vec::new(); columns * rows],
+        };
+        for (i, &point) in points.iter().enumerate() {
+            let (column, row) = res.cell_of(point.x(), point.y());
+            res.cells[row * columns + column].push(i);
+        }
+        res
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Side of the cells, which may be larger than the one given to `new`.
+    pub fn cell_size(&self) -> i32 {
+        self.cell
+    }
+
+    /// Cell containing `(x, y)`, clamped to the grid.
+    fn cell_of(&self, x: i32, y: i32) -> (usize, usize) {
+        let clamp = |value: i32, origin: i32, count: usize| {
+            (((value as i64 - origin as i64) / self.cell as i64).max(0) as usize).min(count - 1)
+        };
+        (clamp(x, self.origin.0, self.columns), clamp(y, self.origin.1, self.rows))
+    }
+
+    /// Indices of the points at distance at most `radius` of `center`, in no particular order.
+    pub fn within(&self, center: Point, radius: i32) -> Vec<usize> {
+        let (min_column, min_row) = self.cell_of(center.x().saturating_sub(radius), center.y().saturating_sub(radius));
+        let (max_column, max_row) = self.cell_of(center.x().saturating_add(radius), center.y().saturating_add(radius));
+
+        let mut res = Vec::new();
+        for row in min_row..=max_row {
+            for column in min_column..=max_column {
+                res.extend(self.cells[row * self.columns + column].iter()
+                    .filter(|&&i| self.points[i].dist(center) <= radius));
+            }
+        }
+        res
+    }
+
+    /// Indices of the `k` points closest to `center`, or all of them if there are fewer, by
+    /// increasing distance then index.
+    ///
+    /// The cells are visited in rings around the one of `center` until no point further out
+    /// can be closer than the `k`-th found.
+    pub fn nearest(&self, center: Point, k: usize) -> Vec<usize> {
+        let k = k.min(self.points.len());
+        let (column, row) = self.cell_of(center.x(), center.y());
+        let mut found: Vec<(i32, usize)> = Vec::new();
+
+        for ring in 0..self.columns.max(self.rows) {
+            let (min_column, max_column) = (column.saturating_sub(ring), (column + ring).min(self.columns - 1));
+            let (min_row, max_row) = (row.saturating_sub(ring), (row + ring).min(self.rows - 1));
+            for r in min_row..=max_row {
+                for c in min_column..=max_column {
+                    // Only the border of the ring, the inside was visited
+                    if r + ring != row && r != row + ring && c + ring != column && c != column + ring {
+                        continue;
+                    }
+                    found.extend(self.cells[r * self.columns + c].iter().map(|&i| (self.points[i].dist(center), i)));
+                }
+            }
+
+            // Points out of the rings are more than `ring * cell` away
+            if found.len() >= k {
+                found.sort_unstable();
+                if k == 0 || (found[k - 1].0 as i64) < ring as i64 * self.cell as i64 {
+                    break;
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found.into_iter().take(k).map(|(_, i)| i).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::point::Point;
+    use crate::types::spatial::SpatialGrid;
+
+    #[test]
+    fn within() {
+        let points = random_points(500);
+        for &(cell, radius) in &[(1, 0), (50, 50), (50, 120), (5000, 300)] {
+            let grid = SpatialGrid::new(&points, cell);
+            for &center in points[..20].iter().chain(&[Point::new(-200, 1500)]) {
+                let mut actual = grid.within(center, radius);
+                actual.sort_unstable();
+                let expected: Vec<_> = (0..points.len()).filter(|&i| points[i].dist(center) <= radius).collect();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn nearest() {
+        let points = random_points(500);
+        for &cell in &[1, 30, 5000] {
+            let grid = SpatialGrid::new(&points, cell);
+            for &center in points[..20].iter().chain(&[Point::new(-200, 1500)]) {
+                let mut expected: Vec<_> = (0..points.len()).map(|i| (points[i].dist(center), i)).collect();
+                expected.sort_unstable();
+                for &k in &[0, 1, 8, 600] {
+                    let expected: Vec<_> = expected.iter().take(k).map(|&(_, i)| i).collect();
+                    assert_eq!(grid.nearest(center, k), expected);
+                }
+            }
+        }
+
+        assert!(SpatialGrid::new(&[], 10).nearest(Point::new(0, 0), 3).is_empty());
+    }
+}
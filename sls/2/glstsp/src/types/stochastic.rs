@@ -0,0 +1,172 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use crate::types::route::Route;
+
+/// Linearizations of the quantile objective tried by `StochasticCosts::solve`.
+const QUANTILE_ITERATIONS: usize = 3;
+
+/// How to turn the random cost of a tour into a number to minimize.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RiskMeasure {
+    Expected,
+    /// Quantile of the tour cost, e.g. `0.9` for a cost exceeded once in ten times.
+    Quantile(f64),
+}
+
+/// Edge costs given as independent normal distributions, e.g. uncertain travel times.
+///
+/// A tour cost is then normal too, its mean and variance being the sums over the tour edges.
+pub struct StochasticCosts {
+    mean: SymmetricMatrix,
+    std: SymmetricMatrix,
+}
+
+impl StochasticCosts {
+    pub fn new(mean: SymmetricMatrix, std: SymmetricMatrix) -> Self {
+        assert_eq!(mean.size(), std.size());
+        Self { mean, std }
+    }
+
+    pub fn size(&self) -> usize {
+        self.mean.size()
+    }
+
+    pub fn expected(&self, path: &Path) -> i32 {
+        self.mean.sum(path.edges())
+    }
+
+    pub fn variance(&self, path: &Path) -> f64 {
+        path.edges().map(|e| (self.std[e] as f64).powi(2)).sum()
+    }
+
+    pub fn evaluate(&self, path: &Path, risk: RiskMeasure) -> f64 {
+        let expected = self.expected(path) as f64;
+        match risk {
+            RiskMeasure::Expected => expected,
+            RiskMeasure::Quantile(q) => expected + normal_quantile(q) * self.variance(path).sqrt(),
+        }
+    }
+
+    /// Tour minimizing the risk measure, its cost being the rounded measure.
+    ///
+    /// The expected cost is solved directly. A quantile, `mean + z * std` of the whole tour, is
+    /// not a sum over the edges: it is approximated by GLS runs on its linearization around the
+    /// best tour so far, edge `e` weighing `mean(e) + z * std(e)² / (2 * std(tour))`.
+    pub fn solve(&self, risk: RiskMeasure, seed: u64, steps: usize) -> Route {
+        let gls = GuidedLocalSearch::new(self.mean.clone());
+        let mut best = gls.solve(seed, steps).path;
+
+        if let RiskMeasure::Quantile(q) = risk {
+            let z = normal_quantile(q);
+            let mut best_risk = self.evaluate(&best, risk);
+
+            for _ in 0..QUANTILE_ITERATIONS {
+                let std = self.variance(&best).sqrt().max(1.0);
+                let weights = SymmetricMatrix::from_fn(self.size(), |i, j| {
+                    let variance = (self.std[(i, j)] as f64).powi(2);
+                    (self.mean[(i, j)] as f64 + z * variance / (2.0 * std)).round().max(0.0) as i32
+                });
+
+                let path = GuidedLocalSearch::new(weights).solve(seed, steps).path;
+                let path_risk = self.evaluate(&path, risk);
+                if path_risk >= best_risk {
+                    break;
+                }
+                best = path;
+                best_risk = path_risk;
+            }
+        }
+
+        Route::new(self.evaluate(&best, risk).round() as i32, best)
+    }
+}
+
+/// Inverse of the standard normal distribution function, by Acklam's rational approximation
+/// (relative error below 1.2e-9).
+pub fn normal_quantile(p: f64) -> f64 {
+    assert!(p > 0.0 && p < 1.0, "probability {} out of (0, 1)", p);
+
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02,
+        1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02,
+        6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00,
+        -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00,
+        3.754408661907416e+00];
+    const LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::gls::GuidedLocalSearch;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use crate::types::stochastic::{normal_quantile, RiskMeasure, StochasticCosts};
+    use rand_mt::Mt64;
+    use rand::{SeedableRng, Rng};
+
+    fn create_costs() -> StochasticCosts {
+        let mean = SymmetricMatrix::from_euclidean_coords(&random_points(40));
+        let mut rng: Mt64 = SeedableRng::seed_from_u64(42);
+        // Some roads are much less predictable than others
+        let std = mean.map(|d| if rng.gen_bool(0.2) { d } else { d / 20 });
+        StochasticCosts::new(mean, std)
+    }
+
+    #[test]
+    fn quantiles() {
+        assert!(normal_quantile(0.5).abs() < 1e-9);
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.01) + 2.326348).abs() < 1e-6);
+    }
+
+    #[test]
+    fn evaluate() {
+        let mut mean = SymmetricMatrix::from_size(3);
+        let mut std = SymmetricMatrix::from_size(3);
+        for (i, j, m, s) in [(0, 1, 10, 3), (1, 2, 20, 0), (0, 2, 30, 4)] {
+            mean.set(i, j, m);
+            std.set(i, j, s);
+        }
+        let costs = StochasticCosts::new(mean, std);
+        let path = Path::sequential(3);
+
+        assert_eq!(costs.evaluate(&path, RiskMeasure::Expected), 60.0);
+        assert_eq!(costs.variance(&path), 25.0);
+        assert_eq!(costs.evaluate(&path, RiskMeasure::Quantile(0.5)), 60.0);
+        assert!((costs.evaluate(&path, RiskMeasure::Quantile(0.975)) - (60.0 + 5.0 * 1.959964)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn solve() {
+        let costs = create_costs();
+
+        let expected = costs.solve(RiskMeasure::Expected, 666, 10);
+        assert_eq!(expected, GuidedLocalSearch::new(costs.mean.clone()).solve(666, 10));
+
+        let risk = RiskMeasure::Quantile(0.95);
+        let actual = costs.solve(risk, 666, 10);
+        assert!(actual.path.is_hamiltonian());
+        assert_eq!(actual.cost, costs.evaluate(&actual.path, risk).round() as i32);
+        assert!(costs.evaluate(&actual.path, risk) <= costs.evaluate(&expected.path, risk));
+    }
+}
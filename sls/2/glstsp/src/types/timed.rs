@@ -0,0 +1,141 @@
+use crate::types::gls::GuidedLocalSearch;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use crate::types::route::Route;
+
+/// How `TimeDependentCosts::solve` turns the travel times into the static costs optimized by
+/// the local search.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Evaluation {
+    /// The matrix of the departure bucket for every leg, ignoring the time-dependence.
+    Departure,
+    /// Up to `iterations` re-solves where each vertex keeps the bucket it is left in by the
+    /// best tour so far, edge `{i, j}` costing the mean of its times in the buckets of `i`
+    /// and `j`.
+    FixedBuckets { iterations: usize },
+}
+
+/// Travel times depending on the departure time, e.g. slower roads at rush hour: leg `{a, b}`
+/// left at time `t` takes `matrices[t / bucket_len][(a, b)]`, the last matrix holding until the
+/// end of the day.
+pub struct TimeDependentCosts {
+    matrices: Vec<SymmetricMatrix>,
+    bucket_len: i32,
+}
+
+impl TimeDependentCosts {
+    pub fn new(matrices: Vec<SymmetricMatrix>, bucket_len: i32) -> Self {
+        assert!(!matrices.is_empty() && bucket_len > 0);
+        assert!(matrices.iter().all(|m| m.size() == matrices[0].size()));
+        Self { matrices, bucket_len }
+    }
+
+    pub fn size(&self) -> usize {
+        self.matrices[0].size()
+    }
+
+    /// Index of the matrix in use at `time`.
+    pub fn bucket(&self, time: i32) -> usize {
+        assert!(time >= 0);
+        ((time / self.bucket_len) as usize).min(self.matrices.len() - 1)
+    }
+
+    /// Time to drive the tour from its first vertex, leaving at `departure`, each leg taking the
+    /// matrix of the time it starts at.
+    pub fn tour_time(&self, path: &Path, departure: i32) -> i32 {
+        let arrival = path.edges().fold(departure, |time, e| time + self.matrices[self.bucket(time)][e]);
+        arrival - departure
+    }
+
+    /// Tour from `depot`, leaving at `departure`, its cost being its `tour_time`.
+    ///
+    /// Each static problem given by `evaluation` is solved by GLS with `seed` and `steps`, the
+    /// tour being driven in whichever direction is faster.
+    pub fn solve(&self, depot: usize, departure: i32, evaluation: Evaluation, seed: u64, steps: usize) -> Route {
+        let costs = self.matrices[self.bucket(departure)].clone();
+        let path = GuidedLocalSearch::new(costs).solve(seed, steps).path;
+        let mut best = self.fastest_direction(path, depot, departure);
+
+        if let Evaluation::FixedBuckets { iterations } = evaluation {
+            for _ in 0..iterations {
+                let buckets = self.departure_buckets(&best.path, departure);
+                let costs = SymmetricMatrix::from_fn(self.size(), |i, j| {
+                    (self.matrices[buckets[i]][(i, j)] + self.matrices[buckets[j]][(i, j)]) / 2
+                });
+
+                let path = GuidedLocalSearch::new(costs).solve(seed, steps).path;
+                let route = self.fastest_direction(path, depot, departure);
+                if route.cost >= best.cost {
+                    break;
+                }
+                best = route;
+            }
+        }
+
+        best
+    }
+
+    /// Bucket each vertex is left in when driving `path` from `departure`, indexed by vertex.
+    fn departure_buckets(&self, path: &Path, departure: i32) -> Vec<usize> {
+        let mut res = vec![0; path.len()];
+        let mut time = departure;
+        for (a, b) in path.edges() {
+            res[a] = self.bucket(time);
+            time += self.matrices[res[a]][(a, b)];
+        }
+        res
+    }
+
+    /// `path` from `depot`, in the direction of least tour time.
+    fn fastest_direction(&self, path: Path, depot: usize, departure: i32) -> Route {
+        let forward = Route::new(0, path).rotated_to_start(depot).path;
+        let mut backward = forward.clone();
+        backward.0[1..].reverse();
+
+        vec![forward, backward].into_iter()
+            .map(|path| Route::new(self.tour_time(&path, departure), path))
+            .min_by_key(|route| route.cost)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::random_points;
+    use crate::types::matrix::SymmetricMatrix;
+    use crate::types::path::Path;
+    use crate::types::timed::{Evaluation, TimeDependentCosts};
+
+    #[test]
+    fn tour_time() {
+        let free = SymmetricMatrix::from_fn(3, |_, _| 10);
+        let rush = SymmetricMatrix::from_fn(3, |_, _| 30);
+        let costs = TimeDependentCosts::new(vec![free, rush], 15);
+        let path = Path::sequential(3);
+
+        // Legs left at 0, 10 and 20, the last one at rush hour
+        assert_eq!(costs.tour_time(&path, 0), 50);
+        assert_eq!(costs.tour_time(&path, 20), 90);
+        assert_eq!(costs.bucket(1000), 1);
+    }
+
+    #[test]
+    fn solve() {
+        let points = random_points(40);
+        let free = SymmetricMatrix::from_euclidean_coords(&points);
+        // Rush hour slows down the west half of the map
+        let rush = SymmetricMatrix::from_fn(40, |i, j| {
+            if points[i].x() < 500 || points[j].x() < 500 { free[(i, j)] * 3 } else { free[(i, j)] }
+        });
+        let costs = TimeDependentCosts::new(vec![free, rush], 2000);
+
+        let fixed = costs.solve(0, 0, Evaluation::Departure, 666, 10);
+        assert!(fixed.path.is_hamiltonian());
+        assert_eq!(fixed.path[0], 0);
+        assert_eq!(fixed.cost, costs.tour_time(&fixed.path, 0));
+
+        let buckets = costs.solve(0, 0, Evaluation::FixedBuckets { iterations: 3 }, 666, 10);
+        assert_eq!(buckets.cost, costs.tour_time(&buckets.path, 0));
+        assert!(buckets.cost <= fixed.cost);
+    }
+}
@@ -0,0 +1,563 @@
+use crate::types::config::ConfigError;
+use crate::types::coord::Coord2;
+use crate::types::geo::GeoPoint;
+use crate::types::instance::Instance;
+use crate::types::matrix::SymmetricMatrix;
+use crate::types::path::Path;
+use std::fs;
+use std::io;
+
+/// Symmetric TSP instance read from a TSPLIB file.
+pub struct Tsplib {
+    pub name: String,
+    pub edge_weight_type: EdgeWeightType,
+    /// Node coordinates, `None` for `EXPLICIT` instances.
+    pub coords: Option<Vec<Coord2>>,
+    /// Coordinates to draw the tour with, which don't define the costs: the
+    /// `DISPLAY_DATA_SECTION`, else the node coordinates unless `DISPLAY_DATA_TYPE` is
+    /// `NO_DISPLAY`.
+    pub display: Option<Vec<Coord2>>,
+    pub distances: SymmetricMatrix,
+}
+
+/// Tour read from a TSPLIB `.tour` file, e.g. the `.opt.tour` files of the instances with a
+/// known optimal tour.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TsplibTour {
+    pub name: String,
+    /// 0-based vertices, in tour order.
+    pub path: Path,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EdgeWeightType {
+    Euc2d,
+    Ceil2d,
+    Att,
+    Geo,
+    Explicit(EdgeWeightFormat),
+}
+
+/// Layout of an `EDGE_WEIGHT_SECTION`, the column-wise layouts being mapped to their row-wise
+/// equivalent since the matrix is symmetric.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EdgeWeightFormat {
+    FullMatrix,
+    UpperRow,
+    LowerRow,
+    UpperDiagRow,
+    LowerDiagRow,
+}
+
+/// Lines are 1-based.
+#[derive(Eq, PartialEq, Debug)]
+pub enum TsplibError {
+    Io(io::ErrorKind),
+    /// The line is not a valid `KEY: VALUE` pair or section entry.
+    Parse { line: usize },
+    UnknownKeyword { line: usize },
+    /// The line declares a problem type, edge weight type or format this parser doesn't handle.
+    Unsupported { line: usize },
+    InvalidDimension { line: usize },
+    MissingDimension,
+    MissingEdgeWeightType,
+    MissingEdgeWeightFormat,
+    /// The section holding the costs (`NODE_COORD_SECTION` or `EDGE_WEIGHT_SECTION`) is missing.
+    MissingSection,
+    /// The node id is out of `1..=DIMENSION` or given twice.
+    InvalidNode { line: usize },
+    /// Not exactly one coordinate per node.
+    NodeCount { expected: usize, found: usize },
+    /// The number of weights doesn't match the dimension and format, `expected` is `None` if it
+    /// overflows.
+    WeightCount { expected: Option<usize>, found: usize },
+    /// `FULL_MATRIX` weights with `d(i, j) != d(j, i)`.
+    Asymmetric { i: usize, j: usize },
+    /// The options given with the file don't fit the instance, e.g. a depot beyond its nodes.
+    Config(ConfigError),
+}
+
+/// Node id (0-based), the line declaring it and its coordinates.
+type Node = (usize, usize, Coord2);
+
+enum Section {
+    Specification,
+    NodeCoords,
+    EdgeWeights,
+    DisplayData,
+}
+
+impl Tsplib {
+    /// Parse a symmetric (`TYPE: TSP`) TSPLIB instance with `EUC_2D`, `CEIL_2D`, `ATT`, `GEO` or
+    /// `EXPLICIT` edge weights.
+    ///
+    /// `COMMENT` lines, with or without a colon, blank lines and anything after `EOF` are
+    /// skipped.
+    ///
+    /// Malformed input is reported as an error, never a panic. Memory is only allocated in
+    /// proportion to the input, whatever `DIMENSION` claims.
+    pub fn parse(text: &str) -> Result<Self, TsplibError> {
+        let mut name = String::new();
+        let mut dimension = None;
+        let mut edge_weight_type = None;
+        let mut edge_weight_format = None;
+        let mut section = Section::Specification;
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut display_nodes: Vec<Node> = Vec::new();
+        let mut no_display = false;
+        let mut weights: Vec<i32> = Vec::new();
+        let mut has_section = false;
+
+        for (line, content) in text.lines().enumerate() {
+            let line = line + 1;
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+            if content == "EOF" {
+                break;
+            }
+
+            // Sections end at the next keyword
+            if !content.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                match section {
+                    Section::Specification => return Err(TsplibError::Parse { line }),
+                    Section::NodeCoords => nodes.push(parse_node(content, line, dimension)?),
+                    Section::DisplayData => display_nodes.push(parse_node(content, line, dimension)?),
+                    Section::EdgeWeights => {
+                        for weight in content.split_whitespace() {
+                            weights.push(weight.parse().map_err(|_| TsplibError::Parse { line })?);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // The colon is optional, e.g. `COMMENT free text`
+            let (key, value) = match content.find(':') {
+                Some(colon) => (content[..colon].trim(), content[colon + 1..].trim()),
+                None => match content.split_once(char::is_whitespace) {
+                    Some((key, value)) => (key, value.trim()),
+                    None => (content, ""),
+                },
+            };
+
+            section = Section::Specification;
+            match key {
+                "NAME" => name = value.to_string(),
+                "COMMENT" => {}
+                "TYPE" => if value != "TSP" {
+                    return Err(TsplibError::Unsupported { line });
+                },
+                "DIMENSION" => match value.parse::<usize>() {
+                    Ok(value) if value >= 2 => dimension = Some(value),
+                    _ => return Err(TsplibError::InvalidDimension { line }),
+                },
+                "EDGE_WEIGHT_TYPE" => edge_weight_type = Some(match value {
+                    "EUC_2D" => EdgeWeightType::Euc2d,
+                    "CEIL_2D" => EdgeWeightType::Ceil2d,
+                    "ATT" => EdgeWeightType::Att,
+                    "GEO" => EdgeWeightType::Geo,
+                    "EXPLICIT" => EdgeWeightType::Explicit(EdgeWeightFormat::FullMatrix),
+                    _ => return Err(TsplibError::Unsupported { line }),
+                }),
+                // Only meaningful for EXPLICIT instances
+                "EDGE_WEIGHT_FORMAT" if value == "FUNCTION" => {}
+                "EDGE_WEIGHT_FORMAT" => edge_weight_format = Some(match value {
+                    "FULL_MATRIX" => EdgeWeightFormat::FullMatrix,
+                    "UPPER_ROW" | "LOWER_COL" => EdgeWeightFormat::UpperRow,
+                    "LOWER_ROW" | "UPPER_COL" => EdgeWeightFormat::LowerRow,
+                    "UPPER_DIAG_ROW" | "LOWER_DIAG_COL" => EdgeWeightFormat::UpperDiagRow,
+                    "LOWER_DIAG_ROW" | "UPPER_DIAG_COL" => EdgeWeightFormat::LowerDiagRow,
+                    _ => return Err(TsplibError::Unsupported { line }),
+                }),
+                "NODE_COORD_TYPE" => if value != "TWOD_COORDS" && value != "NO_COORDS" {
+                    return Err(TsplibError::Unsupported { line });
+                },
+                "DISPLAY_DATA_TYPE" => match value {
+                    "COORD_DISPLAY" | "TWOD_DISPLAY" => {}
+                    "NO_DISPLAY" => no_display = true,
+                    _ => return Err(TsplibError::Unsupported { line }),
+                },
+                "DISPLAY_DATA_SECTION" => section = Section::DisplayData,
+                "NODE_COORD_SECTION" => {
+                    section = Section::NodeCoords;
+                    has_section = true;
+                }
+                "EDGE_WEIGHT_SECTION" => {
+                    section = Section::EdgeWeights;
+                    has_section = true;
+                }
+                _ => return Err(TsplibError::UnknownKeyword { line }),
+            }
+        }
+
+        let dimension = dimension.ok_or(TsplibError::MissingDimension)?;
+        let edge_weight_type = edge_weight_type.ok_or(TsplibError::MissingEdgeWeightType)?;
+        if !has_section {
+            return Err(TsplibError::MissingSection);
+        }
+
+        let (edge_weight_type, coords, distances) = match edge_weight_type {
+            EdgeWeightType::Explicit(_) => {
+                let format = edge_weight_format.ok_or(TsplibError::MissingEdgeWeightFormat)?;
+                let distances = explicit_distances(dimension, format, &weights)?;
+                (EdgeWeightType::Explicit(format), None, distances)
+            }
+            edge_weight_type => {
+                let coords = node_coords(dimension, nodes)?;
+                let distances = coord_distances(&coords, edge_weight_type);
+                (edge_weight_type, Some(coords), distances)
+            }
+        };
+
+        let display = if !display_nodes.is_empty() {
+            Some(node_coords(dimension, display_nodes)?)
+        } else if no_display {
+            None
+        } else {
+            coords.clone()
+        };
+
+        Ok(Self { name, edge_weight_type, coords, display, distances })
+    }
+
+    pub fn read_file(path: impl AsRef<std::path::Path>) -> Result<Self, TsplibError> {
+        let text = fs::read_to_string(path).map_err(|e| TsplibError::Io(e.kind()))?;
+        Self::parse(&text)
+    }
+
+    pub fn into_instance(self) -> Instance {
+        Instance::from_matrix(self.distances)
+    }
+}
+
+impl TsplibTour {
+    /// Parse a `TYPE: TOUR` file, whose `TOUR_SECTION` lists the 1-based node ids of a single
+    /// tour ended by `-1`. `InvalidNode` errors report a node out of range or visited twice.
+    pub fn parse(text: &str) -> Result<Self, TsplibError> {
+        let mut name = String::new();
+        let mut dimension = None;
+        let mut tour: Option<Vec<usize>> = None;
+        let mut visited = Vec::new();
+        let mut ended = false;
+
+        for (line, content) in text.lines().enumerate() {
+            let line = line + 1;
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+            if content == "EOF" {
+                break;
+            }
+
+            if let Some(tour) = tour.as_mut().filter(|_| !ended) {
+                for id in content.split_whitespace() {
+                    let id = id.parse::<i64>().map_err(|_| TsplibError::Parse { line })?;
+                    if id == -1 {
+                        ended = true;
+                        break;
+                    }
+                    if id < 1 || id as usize > visited.len() || visited[id as usize - 1] {
+                        return Err(TsplibError::InvalidNode { line });
+                    }
+                    visited[id as usize - 1] = true;
+                    tour.push(id as usize - 1);
+                }
+                continue;
+            }
+
+            let (key, value) = match content.find(':') {
+                Some(colon) => (content[..colon].trim(), content[colon + 1..].trim()),
+                None => (content, ""),
+            };
+            match key {
+                "NAME" => name = value.to_string(),
+                "COMMENT" => {}
+                "TYPE" => if value != "TOUR" {
+                    return Err(TsplibError::Unsupported { line });
+                },
+                "DIMENSION" => match value.parse::<usize>() {
+                    Ok(value) if value >= 2 => dimension = Some(value),
+                    _ => return Err(TsplibError::InvalidDimension { line }),
+                },
+                "TOUR_SECTION" if tour.is_none() => {
+                    let dimension = dimension.ok_or(TsplibError::MissingDimension)?;
+                    // Allocated as the ids are read, whatever `DIMENSION` claims
+                    visited = vec![false; dimension.min(text.len())];
+                    tour = Some(Vec::new());
+                }
+                // A second tour
+                "TOUR_SECTION" => return Err(TsplibError::Unsupported { line }),
+                _ => return Err(TsplibError::UnknownKeyword { line }),
+            }
+        }
+
+        let dimension = dimension.ok_or(TsplibError::MissingDimension)?;
+        let tour = tour.ok_or(TsplibError::MissingSection)?;
+        if tour.len() != dimension {
+            return Err(TsplibError::NodeCount { expected: dimension, found: tour.len() });
+        }
+
+        Ok(Self { name, path: Path::new(tour) })
+    }
+
+    pub fn read_file(path: impl AsRef<std::path::Path>) -> Result<Self, TsplibError> {
+        let text = fs::read_to_string(path).map_err(|e| TsplibError::Io(e.kind()))?;
+        Self::parse(&text)
+    }
+}
+
+/// Parse a `id x y` node coordinate line.
+fn parse_node(content: &str, line: usize, dimension: Option<usize>) -> Result<Node, TsplibError> {
+    let dimension = dimension.ok_or(TsplibError::MissingDimension)?;
+    let error = || TsplibError::Parse { line };
+
+    let mut fields = content.split_whitespace();
+    let id = fields.next().ok_or_else(error)?.parse::<usize>().map_err(|_| error())?;
+    let coord: Coord2 = fields.collect::<Vec<_>>().join(" ").parse().map_err(|_| error())?;
+    if !coord.0.iter().all(|c| c.is_finite()) {
+        return Err(error());
+    }
+    if id == 0 || id > dimension {
+        return Err(TsplibError::InvalidNode { line });
+    }
+
+    Ok((id - 1, line, coord))
+}
+
+/// Order the nodes by id, checking that every node is given exactly once.
+fn node_coords(dimension: usize, mut nodes: Vec<Node>) -> Result<Vec<Coord2>, TsplibError> {
+    nodes.sort_by_key(|&(id, line, _)| (id, line));
+    if let Some(pair) = nodes.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(TsplibError::InvalidNode { line: pair[1].1 });
+    }
+
+    // The ids are distinct and in range, so there is one per node if the count matches
+    if nodes.len() != dimension {
+        return Err(TsplibError::NodeCount { expected: dimension, found: nodes.len() });
+    }
+
+    Ok(nodes.into_iter().map(|(_, _, coord)| coord).collect())
+}
+
+fn coord_distances(coords: &[Coord2], edge_weight_type: EdgeWeightType) -> SymmetricMatrix {
+    let dist = |a: Coord2, b: Coord2| -> i32 {
+        match edge_weight_type {
+            EdgeWeightType::Euc2d => a.euclidean(b).round() as i32,
+            EdgeWeightType::Ceil2d => a.euclidean(b).ceil() as i32,
+            EdgeWeightType::Att => {
+                let r = (a.squared_euclidean(b) / 10.0).sqrt();
+                let t = r.round();
+                if t < r { t as i32 + 1 } else { t as i32 }
+            }
+            EdgeWeightType::Geo => GeoPoint::new(a.0[0], a.0[1]).tsplib_geo(GeoPoint::new(b.0[0], b.0[1])),
+            EdgeWeightType::Explicit(_) => unreachable!(),
+        }
+    };
+
+    let mut res = SymmetricMatrix::from_size(coords.len());
+    for (i, a) in coords.iter().copied().enumerate() {
+        for (j, b) in coords.iter().copied().enumerate().skip(i + 1) {
+            res.set(i, j, dist(a, b));
+        }
+    }
+    res
+}
+
+fn explicit_distances(dimension: usize, format: EdgeWeightFormat, weights: &[i32]) -> Result<SymmetricMatrix, TsplibError> {
+    let expected = match format {
+        EdgeWeightFormat::FullMatrix => dimension.checked_mul(dimension),
+        EdgeWeightFormat::UpperRow | EdgeWeightFormat::LowerRow =>
+            dimension.checked_mul(dimension - 1).map(|n| n / 2),
+        EdgeWeightFormat::UpperDiagRow | EdgeWeightFormat::LowerDiagRow =>
+            dimension.checked_mul(dimension + 1).map(|n| n / 2),
+    };
+    if expected != Some(weights.len()) {
+        return Err(TsplibError::WeightCount { expected, found: weights.len() });
+    }
+
+    // The count matches, so the matrix is no larger than the input
+    let mut res = SymmetricMatrix::from_size(dimension);
+    let full = weights;
+    let mut weights = weights.iter().copied();
+    let mut next = || weights.next().unwrap();
+
+    match format {
+        EdgeWeightFormat::FullMatrix => for i in 0..dimension {
+            for j in i + 1..dimension {
+                if full[i * dimension + j] != full[j * dimension + i] {
+                    return Err(TsplibError::Asymmetric { i, j });
+                }
+                res.set(i, j, full[i * dimension + j]);
+            }
+        },
+        EdgeWeightFormat::UpperRow => for i in 0..dimension {
+            for j in i + 1..dimension {
+                res.set(i, j, next());
+            }
+        },
+        EdgeWeightFormat::LowerRow => for i in 0..dimension {
+            for j in 0..i {
+                res.set(i, j, next());
+            }
+        },
+        EdgeWeightFormat::UpperDiagRow => for i in 0..dimension {
+            next();
+            for j in i + 1..dimension {
+                res.set(i, j, next());
+            }
+        },
+        EdgeWeightFormat::LowerDiagRow => for i in 0..dimension {
+            for j in 0..i {
+                res.set(i, j, next());
+            }
+            next();
+        },
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::coord::Coord2;
+    use crate::types::path::Path;
+    use crate::types::tsplib::{EdgeWeightFormat, EdgeWeightType, Tsplib, TsplibError, TsplibTour};
+
+    const EUC_2D: &str = "NAME : square
+COMMENT : 4 vertices
+TYPE : TSP
+DIMENSION : 4
+EDGE_WEIGHT_TYPE : EUC_2D
+NODE_COORD_SECTION
+1 0 0
+3 3.0 4.0
+2 0 4
+4 3e0 0
+EOF
+";
+
+    fn explicit(format: &str, weights: &str) -> Result<Tsplib, TsplibError> {
+        Tsplib::parse(&format!(
+            "NAME: triangle\nTYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+             EDGE_WEIGHT_FORMAT: {}\nEDGE_WEIGHT_SECTION\n{}\nEOF\n",
+            format, weights,
+        ))
+    }
+
+    #[test]
+    fn euc_2d() {
+        let actual = Tsplib::parse(EUC_2D).unwrap();
+
+        assert_eq!(actual.name, "square");
+        assert_eq!(actual.edge_weight_type, EdgeWeightType::Euc2d);
+        assert_eq!(actual.coords.unwrap()[2], Coord2::new([3.0, 4.0]));
+        assert_eq!(actual.distances.row(0), &[0, 4, 5, 3]);
+        assert_eq!(actual.distances.row(1), &[4, 0, 3, 5]);
+    }
+
+    #[test]
+    fn att_and_ceil() {
+        let ceil = Tsplib::parse(&EUC_2D.replace("EUC_2D", "CEIL_2D").replace("1 0 0", "1 0.5 0")).unwrap();
+        assert_eq!(ceil.distances.row(0), &[0, 5, 5, 3]);
+
+        let att = Tsplib::parse(&EUC_2D.replace("EUC_2D", "ATT")).unwrap();
+        // sqrt(25 / 10) = 1.58 rounds to 2, sqrt(16 / 10) = 1.26 rounds to 1 and is pushed up to 2
+        assert_eq!(att.distances.row(0), &[0, 2, 2, 1]);
+    }
+
+    #[test]
+    fn explicit_formats() {
+        let expected = [[0, 1, 2], [1, 0, 3], [2, 3, 0]];
+
+        for (format, weights) in [
+            ("FULL_MATRIX", "0 1 2\n1 0 3\n2 3 0"),
+            ("UPPER_ROW", "1 2\n3"),
+            ("LOWER_ROW", "1\n2 3"),
+            ("UPPER_DIAG_ROW", "0 1 2 0 3 0"),
+            ("LOWER_DIAG_ROW", "0\n1 0\n2 3 0"),
+            ("UPPER_COL", "1 2 3"),
+        ] {
+            let actual = explicit(format, weights).unwrap();
+            assert!(actual.coords.is_none());
+            for (i, row) in expected.iter().enumerate() {
+                assert_eq!(actual.distances.row(i), row, "{}", format);
+            }
+        }
+
+        let actual = explicit("LOWER_DIAG_ROW", "0 1 0 2 3 0").unwrap();
+        assert_eq!(actual.edge_weight_type, EdgeWeightType::Explicit(EdgeWeightFormat::LowerDiagRow));
+    }
+
+    #[test]
+    fn display_data() {
+        let text = "NAME: triangle\nCOMMENT drawn apart from its costs\nTYPE: TSP\nDIMENSION: 3\n\
+            EDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\nDISPLAY_DATA_TYPE: TWOD_DISPLAY\n\
+            EDGE_WEIGHT_SECTION\n1 2\n3\nDISPLAY_DATA_SECTION\n1 0 0\n2 10 0\n3 0 10\n\nEOF\n\ntrailing garbage\n";
+        let actual = Tsplib::parse(text).unwrap();
+        assert!(actual.coords.is_none());
+        assert_eq!(actual.display.unwrap()[1], Coord2::new([10.0, 0.0]));
+        assert_eq!(actual.distances.row(0), &[0, 1, 2]);
+
+        let square = Tsplib::parse(EUC_2D).unwrap();
+        assert_eq!(square.display, square.coords);
+        let hidden = Tsplib::parse(&EUC_2D.replace("NODE_COORD_SECTION", "DISPLAY_DATA_TYPE: NO_DISPLAY\nNODE_COORD_SECTION"));
+        assert_eq!(hidden.unwrap().display, None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(explicit("FULL_MATRIX", "0 1 2\n1 0 3\n2 4 0").err(), Some(TsplibError::Asymmetric { i: 1, j: 2 }));
+        assert_eq!(
+            explicit("UPPER_ROW", "1 2").err(),
+            Some(TsplibError::WeightCount { expected: Some(3), found: 2 }),
+        );
+        assert_eq!(explicit("UPPER_ROW", "1 x 3").err(), Some(TsplibError::Parse { line: 7 }));
+        assert_eq!(explicit("TRIANGLE", "1 2 3").err(), Some(TsplibError::Unsupported { line: 5 }));
+
+        let error = |from: &str, to: &str| Tsplib::parse(&EUC_2D.replace(from, to)).err();
+        assert_eq!(error("TYPE : TSP", "TYPE : ATSP"), Some(TsplibError::Unsupported { line: 3 }));
+        assert_eq!(error("DIMENSION : 4", "DIMENSION : -4"), Some(TsplibError::InvalidDimension { line: 4 }));
+        assert_eq!(error("DIMENSION : 4\n", ""), Some(TsplibError::MissingDimension));
+        assert_eq!(error("DIMENSION : 4", "DIMENSION : 1000000000000"), Some(TsplibError::NodeCount {
+            expected: 1_000_000_000_000,
+            found: 4,
+        }));
+        assert_eq!(error("NAME", "NOM"), Some(TsplibError::UnknownKeyword { line: 1 }));
+        assert_eq!(error("4 3e0 0", "5 3e0 0"), Some(TsplibError::InvalidNode { line: 10 }));
+        assert_eq!(error("4 3e0 0", "3 3e0 0"), Some(TsplibError::InvalidNode { line: 10 }));
+        assert_eq!(error("4 3e0 0\n", ""), Some(TsplibError::NodeCount { expected: 4, found: 3 }));
+        assert_eq!(error("4 3e0 0", "4 3e0 NaN"), Some(TsplibError::Parse { line: 10 }));
+        assert_eq!(error("4 3e0 0", "4 3e0"), Some(TsplibError::Parse { line: 10 }));
+        assert_eq!(error("NODE_COORD_SECTION\n1 0 0\n3 3.0 4.0\n2 0 4\n4 3e0 0\n", ""), Some(TsplibError::MissingSection));
+    }
+
+    #[test]
+    fn tour() {
+        let text = "NAME : square.opt.tour\nTYPE : TOUR\nDIMENSION : 4\nTOUR_SECTION\n1\n3 4\n2\n-1\nEOF\n";
+        let actual = TsplibTour::parse(text).unwrap();
+        assert_eq!(actual.name, "square.opt.tour");
+        assert_eq!(actual.path, Path::new(vec![0, 2, 3, 1]));
+
+        let error = |from: &str, to: &str| TsplibTour::parse(&text.replace(from, to)).err();
+        assert_eq!(error("TOUR\n", "TSP\n"), Some(TsplibError::Unsupported { line: 2 }));
+        assert_eq!(error("3 4", "3 3"), Some(TsplibError::InvalidNode { line: 6 }));
+        assert_eq!(error("3 4", "3 5"), Some(TsplibError::InvalidNode { line: 6 }));
+        assert_eq!(error("3 4", "3"), Some(TsplibError::NodeCount { expected: 4, found: 3 }));
+        assert_eq!(error("3 4", "3 x"), Some(TsplibError::Parse { line: 6 }));
+        assert_eq!(error("DIMENSION : 4\n", ""), Some(TsplibError::MissingDimension));
+        assert_eq!(error("TOUR_SECTION\n1\n3 4\n2\n-1\n", ""), Some(TsplibError::MissingSection));
+    }
+
+    #[test]
+    fn read_file() {
+        let actual = Tsplib::read_file(concat!(env!("CARGO_MANIFEST_DIR"), "/data/pcb3038.original.tsp")).unwrap();
+        assert_eq!(actual.name, "pcb3038");
+        assert_eq!(actual.distances.size(), 3038);
+
+        let missing = Tsplib::read_file(concat!(env!("CARGO_MANIFEST_DIR"), "/data/missing.tsp"));
+        assert_eq!(missing.err(), Some(TsplibError::Io(std::io::ErrorKind::NotFound)));
+    }
+}
@@ -0,0 +1,11 @@
+//! The bundled benchmark instance, solved through the public API.
+#![cfg(feature = "bundled-data")]
+
+use glstsp::run_pcb3038;
+
+#[test]
+fn ten_steps() {
+    let route = run_pcb3038(10);
+    assert!(route.path.is_hamiltonian());
+    assert_eq!(route.cost, 147965);
+}